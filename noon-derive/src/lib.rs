@@ -0,0 +1,276 @@
+//! Derive and attribute macros for [noon](https://docs.rs/noon).
+//!
+//! These are re-exported by `noon` when its `derive` feature is enabled, and shouldn't be depended on directly.
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Implements `noon::message::Message` for a struct or enum.
+///
+/// The response type is given with a `#[message(response = ...)]` attribute, and defaults to `()`.
+/// ```rust,ignore
+/// use noon::message::Message;
+///
+/// #[derive(Message)]
+/// #[message(response = Option<User>)]
+/// struct GetUser { id: i32 }
+/// ```
+#[proc_macro_derive(Message, attributes(message))]
+pub fn derive_message(input: TokenStream) -> TokenStream {
+    match Item::parse(input) {
+        Ok(item) => item.impl_message(),
+        Err(msg) => compile_error(msg),
+    }
+}
+
+/// Registers a free function as a handler, to be collected at link time by `noon::registry`.
+///
+/// ```rust,ignore
+/// #[noon::handler]
+/// fn get_user(req: GetUser) -> Option<User> {
+///     None
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return compile_error("`#[handler]` doesn't take arguments".into());
+    }
+    let name = match handler_name(item.clone()) {
+        Ok(name) => name,
+        Err(msg) => return compile_error(msg),
+    };
+    let registration = format!(
+        "const _: () = {{
+            #[used]
+            #[link_section = \"noon_handlers\"]
+            static REGISTRATION: ::noon::registry::Registration = ::noon::registry::Registration::new(
+                ::core::concat!(::core::module_path!(), \"::{name}\"),
+                || ::noon::registry::message_type(&{name}),
+                |mediator| {{ mediator.add_handler({name}); }},
+                |mediator| {{ mediator.add_handler({name}); }},
+            );
+        }};"
+    );
+    let mut output = item;
+    output.extend(
+        registration
+            .parse::<TokenStream>()
+            .expect("generated registration should be valid tokens"),
+    );
+    output
+}
+
+/// Finds the name of the function `#[handler]` is applied to.
+fn handler_name(item: TokenStream) -> Result<String, String> {
+    let mut tokens = item.into_iter();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) => match ident.to_string().as_str() {
+                "fn" => break,
+                "async" => return Err("`#[handler]` doesn't support `async fn`".into()),
+                _ => {}
+            },
+            Some(_) => {}
+            None => return Err("`#[handler]` can only be applied to a function".into()),
+        }
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected a function name".into()),
+    };
+    if matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '<') {
+        return Err("`#[handler]` doesn't support generic functions".into());
+    }
+    Ok(name)
+}
+
+struct Item {
+    name: String,
+    params: Vec<Vec<TokenTree>>,
+    where_clause: Vec<TokenTree>,
+    response: Option<Vec<TokenTree>>,
+}
+
+impl Item {
+    fn parse(input: TokenStream) -> Result<Self, String> {
+        let mut tokens = input.into_iter().peekable();
+        let mut response = None;
+
+        loop {
+            match tokens.next() {
+                Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                    if let Some(TokenTree::Group(attr)) = tokens.next() {
+                        if let Some(found) = parse_message_attr(attr.stream())? {
+                            if response.replace(found).is_some() {
+                                return Err(
+                                    "duplicate `#[message(response = ...)]` attribute".into()
+                                );
+                            }
+                        }
+                    }
+                }
+                Some(TokenTree::Ident(ident)) => {
+                    let ident = ident.to_string();
+                    if matches!(ident.as_str(), "struct" | "enum" | "union") {
+                        break;
+                    }
+                }
+                Some(_) => {}
+                None => return Err("expected a struct, enum or union".into()),
+            }
+        }
+
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            _ => return Err("expected a type name".into()),
+        };
+
+        let mut params = vec![];
+        if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '<') {
+            tokens.next();
+            let mut depth = 0;
+            let mut param: Vec<TokenTree> = vec![];
+            for token in tokens.by_ref() {
+                if let TokenTree::Punct(p) = &token {
+                    match p.as_char() {
+                        '>' if is_arrow(param.last()) => {}
+                        '<' => depth += 1,
+                        '>' if depth == 0 => break,
+                        '>' => depth -= 1,
+                        ',' if depth == 0 => {
+                            params.push(std::mem::take(&mut param));
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+                param.push(token);
+            }
+            if !param.is_empty() {
+                params.push(param);
+            }
+        }
+
+        let mut where_clause = vec![];
+        if matches!(tokens.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis)
+        {
+            tokens.next();
+        }
+        if matches!(tokens.peek(), Some(TokenTree::Ident(i)) if i.to_string() == "where") {
+            for token in tokens.by_ref() {
+                match &token {
+                    TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => break,
+                    TokenTree::Punct(p) if p.as_char() == ';' => break,
+                    _ => where_clause.push(token),
+                }
+            }
+        }
+
+        Ok(Self {
+            name,
+            params,
+            where_clause,
+            response,
+        })
+    }
+
+    fn impl_message(&self) -> TokenStream {
+        let params = self
+            .params
+            .iter()
+            .map(|param| tokens_to_string(strip_default(param)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let args = self
+            .params
+            .iter()
+            .map(|param| param_name(param))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let response = self
+            .response
+            .as_deref()
+            .map_or_else(|| "()".to_string(), tokens_to_string);
+        let code = format!(
+            "impl<{params}> ::noon::message::Message for {name}<{args}> {where_clause} {{ type Response = {response}; }}",
+            name = self.name,
+            where_clause = tokens_to_string(&self.where_clause),
+        );
+        code.parse().expect("generated impl should be valid tokens")
+    }
+}
+
+/// Extracts the response type from the contents of a `#[message(response = ...)]` attribute, ignoring other attributes.
+fn parse_message_attr(attr: TokenStream) -> Result<Option<Vec<TokenTree>>, String> {
+    let mut tokens = attr.into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "message" => {}
+        _ => return Ok(None),
+    }
+    let args = match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => g.stream(),
+        _ => return Err("expected `#[message(response = ...)]`".into()),
+    };
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(TokenTree::Ident(key)), Some(TokenTree::Punct(eq)))
+            if key.to_string() == "response" && eq.as_char() == '=' =>
+        {
+            let response: Vec<_> = args.collect();
+            if response.is_empty() {
+                return Err("expected a response type".into());
+            }
+            Ok(Some(response))
+        }
+        _ => Err("expected `#[message(response = ...)]`".into()),
+    }
+}
+
+/// Strips the default from a generic parameter, which isn't allowed on impls.
+fn strip_default(param: &[TokenTree]) -> &[TokenTree] {
+    let mut depth = 0;
+    for (i, token) in param.iter().enumerate() {
+        if let TokenTree::Punct(p) = token {
+            match p.as_char() {
+                '>' if i > 0 && is_arrow(param.get(i - 1)) => {}
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                '=' if depth == 0 => return &param[..i],
+                _ => {}
+            }
+        }
+    }
+    param
+}
+
+/// Strips the bounds and default from a generic parameter, so it can be used as an argument.
+fn param_name(param: &[TokenTree]) -> String {
+    let mut tokens = param.iter();
+    match tokens.next() {
+        Some(TokenTree::Punct(p)) if p.as_char() == '\'' && p.spacing() == Spacing::Joint => {
+            format!(
+                "'{}",
+                tokens.next().map(ToString::to_string).unwrap_or_default()
+            )
+        }
+        Some(TokenTree::Ident(i)) if i.to_string() == "const" => {
+            tokens.next().map(ToString::to_string).unwrap_or_default()
+        }
+        Some(token) => token.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Whether a `>` following `prev` is part of a `->`, rather than closing a generic argument list.
+fn is_arrow(prev: Option<&TokenTree>) -> bool {
+    matches!(prev, Some(TokenTree::Punct(p)) if p.as_char() == '-' && p.spacing() == Spacing::Joint)
+}
+
+fn tokens_to_string(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
+
+fn compile_error(msg: String) -> TokenStream {
+    format!("::core::compile_error!({:?});", msg)
+        .parse()
+        .expect("compile_error! invocation should be valid tokens")
+}
@@ -0,0 +1,495 @@
+//! Driving a mediator from many tasks by sending it commands.
+//!
+//! [`Mediate::into_actor`] moves a mediator into an [`Actor`], a future which runs the commands sent to it through a [`Mailbox`].
+//! The actor runs wherever it's polled, such as a dedicated thread or a task spawned with `tokio::task::spawn_local`,
+//! while the mailbox is `Send + Sync` and can be cloned, so a [`crate::flavor::Local`] mediator whose handlers aren't thread-safe
+//! can still be used from other threads and tasks.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use std::thread;
+//! # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+//! #     use std::task::{Context, Poll, Wake, Waker};
+//! #     struct Unpark(thread::Thread);
+//! #     impl Wake for Unpark { fn wake(self: std::sync::Arc<Self>) { self.0.unpark() } }
+//! #     let waker = Waker::from(std::sync::Arc::new(Unpark(thread::current())));
+//! #     let mut fut = std::pin::pin!(fut);
+//! #     loop {
+//! #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+//! #             Poll::Ready(out) => return out,
+//! #             Poll::Pending => thread::park(),
+//! #         }
+//! #     }
+//! # }
+//!
+//! struct Increment;
+//!
+//! let count = Rc::new(Cell::new(0));
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(move |_: Increment| {
+//!         count.set(count.get() + 1);
+//!         count.get()
+//!     })
+//!     .build();
+//! let (mailbox, actor) = mediator.into_actor();
+//!
+//! let workers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let mailbox = mailbox.clone();
+//!         thread::spawn(move || block_on(mailbox.handle::<Increment, i32, _>(Increment)))
+//!     })
+//!     .collect();
+//! drop(mailbox);
+//! // Runs the commands until every mailbox is dropped.
+//! block_on(actor);
+//!
+//! let mut counts: Vec<_> = workers.into_iter().map(|w| w.join().unwrap().unwrap()).collect();
+//! counts.sort();
+//! assert_eq!(counts, [1, 2, 3, 4]);
+//! ```
+//!
+//! [`Mailbox::address`] creates an [`Address`] for one type of request, which doesn't name the type of the mediator,
+//! so a component can hold typed handles for just the requests it sends:
+//! ```rust
+//! use noon::actor::Address;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! struct GetUser(u32);
+//!
+//! struct Profile {
+//!     users: Address<GetUser, String>,
+//! }
+//!
+//! let (mailbox, actor) = MediatorBuilder::new()
+//!     .add_handler(|req: GetUser| format!("user {}", req.0))
+//!     .build()
+//!     .into_actor();
+//! let profile = Profile { users: mailbox.address::<GetUser, String, _>() };
+//! let reply = profile.users.send(GetUser(1));
+//! ```
+//!
+//! Commands run one at a time, in the order they were sent, so an asynchronous handler holds back the commands after it until it completes.
+//! A mailbox from [`Mailbox::with_priority`] sends to another lane of the queue, so that health checks or cancellations can jump ahead of bulk work,
+//! see [`Priority`]. A command which already started isn't interrupted.
+//! Messages and responses cross to the actor and back, so they must be `Send`.
+//!
+//! [`Mediate::into_actor`]: crate::mediator::Mediate::into_actor
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationAsync, RequestResponse, RequestResponseAsync,
+};
+use crate::flavor::{Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::future::{self, Future};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+type Command<M> = Box<dyn for<'a> FnOnce(&'a M) -> Pin<Box<dyn Future<Output = ()> + 'a>> + Send>;
+
+fn command<M, F>(f: F) -> Command<M>
+where
+    F: for<'a> FnOnce(&'a M) -> Pin<Box<dyn Future<Output = ()> + 'a>> + Send + 'static,
+{
+    Box::new(f)
+}
+
+/// The error returned when the actor stopped before running a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorStopped;
+
+impl fmt::Display for ActorStopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the actor stopped")
+    }
+}
+
+impl Error for ActorStopped {}
+
+/// The lane of the actor's queue a mailbox sends to, see [`Mailbox::with_priority`].
+///
+/// The actor runs the commands queued in a higher lane before those in lower ones, and the commands in the same lane in the order they were sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+struct Queue<T> {
+    /// The lanes, indexed by priority.
+    lanes: [VecDeque<T>; 3],
+    waker: Option<Waker>,
+    mailboxes: usize,
+    stopped: bool,
+}
+
+impl<T> Queue<T> {
+    fn push(&mut self, priority: Priority, command: T) {
+        self.lanes[priority as usize].push_back(command);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.lanes.iter_mut().rev().find_map(VecDeque::pop_front)
+    }
+
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+}
+
+/// Sends commands to an [`Actor`], see [`crate::actor`].
+///
+/// Clones send to the same actor, which stops once every mailbox is dropped.
+pub struct Mailbox<M, S: Flavor = Local> {
+    queue: Arc<Mutex<Queue<Command<M>>>>,
+    priority: Priority,
+    _flavor: PhantomData<fn() -> S>,
+}
+
+impl<M: Mediate<S>, S: Flavor> Mailbox<M, S> {
+    fn send<R: Send + 'static>(
+        &self,
+        run: impl FnOnce(&M, ReplySender<R>) -> Pin<Box<dyn Future<Output = ()> + '_>> + Send + 'static,
+    ) -> Reply<R> {
+        let slot = Arc::new(Mutex::new(Slot {
+            value: None,
+            waker: None,
+            dropped: false,
+        }));
+        let sender = ReplySender {
+            slot: Arc::clone(&slot),
+        };
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.stopped {
+            queue.push(
+                self.priority,
+                command(move |mediator| run(mediator, sender)),
+            );
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+        Reply { slot }
+    }
+
+    /// Runs `f` on the actor's mediator, resolving to its result.
+    pub fn call<R, F>(&self, f: F) -> Reply<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&M) -> R + Send + 'static,
+    {
+        self.send(move |mediator, reply| {
+            reply.send(f(mediator));
+            Box::pin(future::ready(()))
+        })
+    }
+
+    pub fn handle<TMsg, TResp, I>(&self, msg: TMsg) -> Reply<TResp>
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Send + 'static,
+        TResp: Send + 'static,
+    {
+        self.call(move |mediator| mediator.handle(msg))
+    }
+
+    pub fn handle_async<TMsg, TResp, I>(&self, msg: TMsg) -> Reply<TResp>
+    where
+        M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        TMsg: Send + 'static,
+        TResp: Send + 'static,
+    {
+        self.send(move |mediator, reply| {
+            Box::pin(async move { reply.send(mediator.handle_async(msg).await) })
+        })
+    }
+
+    /// Notifies the receivers for `TMsg`, resolving once they've run.
+    pub fn notify<TMsg, I>(&self, msg: TMsg) -> Reply<()>
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        TMsg: Send + 'static,
+    {
+        self.call(move |mediator| mediator.notify(&msg))
+    }
+
+    pub fn notify_async<TMsg, I>(&self, msg: TMsg) -> Reply<()>
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        TMsg: Clone + Send + 'static,
+    {
+        self.send(move |mediator, reply| {
+            Box::pin(async move {
+                mediator.notify_async(msg).await;
+                reply.send(())
+            })
+        })
+    }
+}
+
+impl<M, S: Flavor> Mailbox<M, S> {
+    /// A mailbox sending to the same actor, whose commands are queued with `priority`.
+    ///
+    /// Addresses created from it send with the same priority, so a priority can be given to each type of request:
+    /// ```rust
+    /// use noon::actor::Priority;
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// struct HealthCheck;
+    /// struct Import(Vec<u32>);
+    ///
+    /// let (mailbox, actor) = MediatorBuilder::new()
+    ///     .add_handler(|_: HealthCheck| "ok")
+    ///     .add_handler(|req: Import| req.0.len())
+    ///     .build()
+    ///     .into_actor();
+    /// let health = mailbox.with_priority(Priority::High).address::<HealthCheck, &str, _>();
+    /// let imports = mailbox.with_priority(Priority::Low).address::<Import, usize, _>();
+    /// # drop((health, imports, mailbox, actor));
+    /// ```
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut mailbox = self.clone();
+        mailbox.priority = priority;
+        mailbox
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+}
+
+impl<M: Mediate<S> + 'static, S: Flavor> Mailbox<M, S> {
+    /// An address sending `TMsg`s to the handler for them.
+    pub fn address<TMsg, TResp, I>(&self) -> Address<TMsg, TResp>
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Send + 'static,
+        TResp: Send + 'static,
+    {
+        let mailbox = self.clone();
+        Address {
+            send: Arc::new(move |msg| mailbox.handle(msg)),
+        }
+    }
+
+    /// An address sending `TMsg`s to the asynchronous handler for them.
+    pub fn async_address<TMsg, TResp, I>(&self) -> Address<TMsg, TResp>
+    where
+        M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        TMsg: Send + 'static,
+        TResp: Send + 'static,
+    {
+        let mailbox = self.clone();
+        Address {
+            send: Arc::new(move |msg| mailbox.handle_async(msg)),
+        }
+    }
+}
+
+impl<M, S: Flavor> Clone for Mailbox<M, S> {
+    fn clone(&self) -> Self {
+        self.queue.lock().unwrap().mailboxes += 1;
+        Self {
+            queue: Arc::clone(&self.queue),
+            priority: self.priority,
+            _flavor: PhantomData,
+        }
+    }
+}
+
+impl<M, S: Flavor> Drop for Mailbox<M, S> {
+    fn drop(&mut self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.mailboxes -= 1;
+        if queue.mailboxes == 0 {
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<M, S: Flavor> fmt::Debug for Mailbox<M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let queued = self.queue.lock().unwrap().len();
+        f.debug_struct("Mailbox")
+            .field("priority", &self.priority)
+            .field("queued", &queued)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends one type of request to an actor, see [`Mailbox::address`].
+///
+/// Unlike a [`Mailbox`], an address doesn't name the type of the mediator, so components can hold addresses for just the requests they send.
+/// Clones are cheap, and keep the actor running like its mailboxes.
+pub struct Address<TMsg, TResp> {
+    send: Arc<dyn Fn(TMsg) -> Reply<TResp> + Send + Sync>,
+}
+
+impl<TMsg, TResp> Address<TMsg, TResp> {
+    /// Sends `msg`, resolving to the handler's response.
+    pub fn send(&self, msg: TMsg) -> Reply<TResp> {
+        (self.send)(msg)
+    }
+}
+
+impl<TMsg, TResp> Clone for Address<TMsg, TResp> {
+    fn clone(&self) -> Self {
+        Self {
+            send: Arc::clone(&self.send),
+        }
+    }
+}
+
+impl<TMsg, TResp> fmt::Debug for Address<TMsg, TResp> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Address")
+            .field("message", &std::any::type_name::<TMsg>())
+            .field("response", &std::any::type_name::<TResp>())
+            .finish()
+    }
+}
+
+/// The receiving side of the queue, which stops the mailboxes from sending once the actor is dropped.
+struct Commands<M> {
+    queue: Arc<Mutex<Queue<Command<M>>>>,
+}
+
+impl<M> Commands<M> {
+    /// The next command, or `None` once every mailbox is dropped.
+    fn next(&self) -> impl Future<Output = Option<Command<M>>> + '_ {
+        future::poll_fn(|cx| {
+            let mut queue = self.queue.lock().unwrap();
+            match queue.pop() {
+                Some(command) => Poll::Ready(Some(command)),
+                None if queue.mailboxes == 0 => Poll::Ready(None),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+    }
+}
+
+impl<M> Drop for Commands<M> {
+    fn drop(&mut self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.stopped = true;
+        // Dropped outside the lock, since a command may hold a mailbox.
+        let commands = std::mem::take(&mut queue.lanes);
+        drop(queue);
+        drop(commands);
+    }
+}
+
+/// Runs the commands sent to a mediator, see [`crate::actor`].
+///
+/// Resolves once every [`Mailbox`] is dropped and the commands sent before have run.
+#[must_use = "futures do nothing unless polled"]
+pub struct Actor {
+    run: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+impl Actor {
+    pub(crate) fn new<M: Mediate<S> + 'static, S: Flavor>(mediator: M) -> (Mailbox<M, S>, Self) {
+        let queue = Arc::new(Mutex::new(Queue {
+            lanes: Default::default(),
+            waker: None,
+            mailboxes: 1,
+            stopped: false,
+        }));
+        let commands = Commands {
+            queue: Arc::clone(&queue),
+        };
+        let run = async move {
+            while let Some(command) = commands.next().await {
+                command(&mediator).await;
+            }
+        };
+        let mailbox = Mailbox {
+            queue,
+            priority: Priority::Normal,
+            _flavor: PhantomData,
+        };
+        (mailbox, Self { run: Box::pin(run) })
+    }
+}
+
+impl Future for Actor {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.run.as_mut().poll(cx)
+    }
+}
+
+impl fmt::Debug for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Actor").finish_non_exhaustive()
+    }
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    dropped: bool,
+}
+
+struct ReplySender<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T> ReplySender<T> {
+    fn send(self, value: T) {
+        self.slot.lock().unwrap().value = Some(value);
+    }
+}
+
+/// Wakes the reply, which fails if no value was sent.
+impl<T> Drop for ReplySender<T> {
+    fn drop(&mut self) {
+        let mut slot = self.slot.lock().unwrap();
+        slot.dropped = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves to the result of a command, or [`ActorStopped`] if it didn't run.
+#[must_use = "futures do nothing unless polled"]
+pub struct Reply<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T> Future for Reply<T> {
+    type Output = Result<T, ActorStopped>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.value.take() {
+            Some(value) => Poll::Ready(Ok(value)),
+            None if slot.dropped => Poll::Ready(Err(ActorStopped)),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Reply<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reply").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,79 @@
+//! Authorizing dispatches against the caller's context.
+//!
+//! An [`Authorizer`] registered with [`MediatorBuilder::add_authorizer`] decides whether a message may reach a fallible handler
+//! taking a `(TMsg, Context)` tuple, from the message and the [`Context`] of the dispatch, such as its principal and claims.
+//! If it refuses, [`Mediate::try_handle_with_context`] returns its [`Unauthorized`] error, converted into the handler's error type,
+//! and the handler isn't called.
+//! ```rust
+//! use noon::auth::Unauthorized;
+//! use noon::context::Context;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! struct Principal { admin: bool }
+//! struct DeleteUser { id: u32 }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler(|(req, _): (DeleteUser, Context)| Ok::<_, Unauthorized>(req.id))
+//!     .add_authorizer(|ctx: &Context, _: &DeleteUser| match ctx.get::<Principal>() {
+//!         Some(principal) if principal.admin => Ok(()),
+//!         Some(_) => Err(Unauthorized::new("admins only")),
+//!         None => Err(Unauthorized::new("not signed in")),
+//!     })
+//!     .build();
+//!
+//! let admin = Context::new().with(Principal { admin: true });
+//! let guest = Context::new().with(Principal { admin: false });
+//! assert_eq!(mediator.try_handle_with_context(DeleteUser { id: 7 }, &admin), Ok(7));
+//! assert_eq!(
+//!     mediator.try_handle_with_context::<_, u32, _, _>(DeleteUser { id: 7 }, &guest),
+//!     Err(Unauthorized::new("admins only"))
+//! );
+//! ```
+//!
+//! An authorizer implementing [`Authorizer`] for every message type, such as one checking a role, can be added to each handler
+//! to enforce the same policy across all of them.
+//! Like validators, authorizers are pipeline behaviors added after their handler, see [`crate::pipeline`].
+//!
+//! [`MediatorBuilder::add_authorizer`]: crate::mediator::MediatorBuilder::add_authorizer
+//! [`Mediate::try_handle_with_context`]: crate::mediator::Mediate::try_handle_with_context
+use crate::context::Context;
+
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when an [`Authorizer`] refuses a dispatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unauthorized {
+    reason: String,
+}
+
+impl Unauthorized {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unauthorized: {}", self.reason)
+    }
+}
+
+impl Error for Unauthorized {}
+
+/// Decides whether a dispatch of `TMsg` may reach its handler, see [`crate::auth`].
+pub trait Authorizer<TMsg> {
+    fn authorize(&self, ctx: &Context, msg: &TMsg) -> Result<(), Unauthorized>;
+}
+
+impl<TMsg, F: Fn(&Context, &TMsg) -> Result<(), Unauthorized>> Authorizer<TMsg> for F {
+    fn authorize(&self, ctx: &Context, msg: &TMsg) -> Result<(), Unauthorized> {
+        self(ctx, msg)
+    }
+}
@@ -0,0 +1,162 @@
+//! Handling messages in batches.
+//!
+//! A handler registered with [`MediatorBuilder::add_batch_handler`] receives the messages dispatched with [`Mediate::handle_batch`]
+//! as a `Vec`, once enough of them accumulated or the oldest has waited long enough, according to its [`BatchPolicy`].
+//! This suits workloads such as bulk database writes, where handling each message on its own is too expensive.
+//! ```rust
+//! use noon::batch::BatchPolicy;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! struct Insert(u32);
+//!
+//! let writes = Rc::new(RefCell::new(vec![]));
+//! let written = writes.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .add_batch_handler(BatchPolicy::new(2), move |rows: Vec<Insert>| {
+//!         written.borrow_mut().push(rows.into_iter().map(|row| row.0).collect::<Vec<_>>())
+//!     })
+//!     .build();
+//! mediator.handle_batch(Insert(1));
+//! mediator.handle_batch(Insert(2));
+//! mediator.handle_batch(Insert(3));
+//! assert_eq!(*writes.borrow(), [vec![1, 2]]);
+//! assert_eq!(mediator.flush_batch::<Insert, _>(), 1);
+//! assert_eq!(*writes.borrow(), [vec![1, 2], vec![3]]);
+//! ```
+//!
+//! Batches are delivered from within a dispatch, since no task runs in the background:
+//! a batch whose oldest message exceeded the policy's delay is delivered by the next dispatch,
+//! and a partial batch is delivered by [`Mediate::flush_batch`] or when the mediator is dropped.
+//!
+//! [`MediatorBuilder::add_batch_handler`]: crate::mediator::MediatorBuilder::add_batch_handler
+//! [`Mediate::handle_batch`]: crate::mediator::Mediate::handle_batch
+//! [`Mediate::flush_batch`]: crate::mediator::Mediate::flush_batch
+use crate::entry::RequestResponse;
+use crate::flavor::Local;
+use crate::hlist::Cons;
+
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The handlers registered by [`crate::mediator::MediatorBuilder::add_batch_handler`] in front of `H`.
+pub type Batching<TMsg, H, S = Local> = Cons<
+    RequestResponse<FlushBatch<TMsg>, usize, S>,
+    Cons<RequestResponse<Batched<TMsg>, (), S>, H>,
+>;
+
+/// A message added to the pending batch of its batch handler, see [`crate::mediator::Mediate::handle_batch`].
+pub struct Batched<TMsg>(pub TMsg);
+
+/// Delivers the pending batch of the batch handler for `TMsg`, responding with its length.
+pub struct FlushBatch<TMsg>(PhantomData<fn() -> TMsg>);
+
+impl<TMsg> FlushBatch<TMsg> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<TMsg> Default for FlushBatch<TMsg> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Configures when a batch handler receives its pending batch.
+/// ```rust
+/// use noon::batch::BatchPolicy;
+/// use std::time::Duration;
+///
+/// let policy = BatchPolicy::new(500).with_max_delay(Duration::from_millis(100));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchPolicy {
+    max_size: usize,
+    max_delay: Option<Duration>,
+}
+
+impl BatchPolicy {
+    /// Delivers a batch once it holds `max_size` messages.
+    ///
+    /// # Panics
+    /// If `max_size` is zero.
+    pub fn new(max_size: usize) -> Self {
+        assert!(max_size > 0, "batch size must be positive");
+        Self {
+            max_size,
+            max_delay: None,
+        }
+    }
+
+    /// Also delivers a batch once its oldest message has waited for `max_delay`.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+}
+
+struct Pending<TMsg> {
+    messages: Vec<TMsg>,
+    since: Option<Instant>,
+}
+
+/// The pending batch of a batch handler, which is delivered when it's dropped.
+///
+/// The lock isn't held while the handler runs, so the handler can dispatch other messages.
+pub(crate) struct Batcher<TMsg, F: Fn(Vec<TMsg>)> {
+    policy: BatchPolicy,
+    pending: Mutex<Pending<TMsg>>,
+    handler: F,
+}
+
+impl<TMsg, F: Fn(Vec<TMsg>)> Batcher<TMsg, F> {
+    pub(crate) fn new(policy: BatchPolicy, handler: F) -> Self {
+        Self {
+            policy,
+            pending: Mutex::new(Pending {
+                messages: Vec::with_capacity(policy.max_size),
+                since: None,
+            }),
+            handler,
+        }
+    }
+
+    pub(crate) fn push(&self, msg: TMsg) {
+        let mut pending = self.pending.lock().unwrap();
+        let since = *pending.since.get_or_insert_with(Instant::now);
+        pending.messages.push(msg);
+        let waited = self
+            .policy
+            .max_delay
+            .is_some_and(|delay| since.elapsed() >= delay);
+        if pending.messages.len() >= self.policy.max_size || waited {
+            let batch = Self::take(&mut pending, self.policy.max_size);
+            drop(pending);
+            (self.handler)(batch);
+        }
+    }
+
+    pub(crate) fn flush(&self) -> usize {
+        let batch = Self::take(&mut self.pending.lock().unwrap(), self.policy.max_size);
+        let len = batch.len();
+        if len > 0 {
+            (self.handler)(batch);
+        }
+        len
+    }
+
+    fn take(pending: &mut Pending<TMsg>, capacity: usize) -> Vec<TMsg> {
+        pending.since = None;
+        mem::replace(&mut pending.messages, Vec::with_capacity(capacity))
+    }
+}
+
+impl<TMsg, F: Fn(Vec<TMsg>)> Drop for Batcher<TMsg, F> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
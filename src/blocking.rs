@@ -0,0 +1,55 @@
+//! Driving futures from synchronous code, behind the `blocking` feature.
+//!
+//! [`block_on`] is a minimal executor which polls a future on the current thread, parking it while the future is pending.
+//! It's meant for command-line tools and tests which use a mediator with asynchronous handlers from a synchronous `main`,
+//! see [`crate::mediator::Mediate::handle_blocking`]. Futures which rely on a specific runtime, such as `tokio`'s IO types, need that runtime instead.
+//! ```rust
+//! use noon::blocking::block_on;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_async_handler(|x: i32| async move { x * 2 })
+//!     .build();
+//! assert_eq!(block_on(mediator.handle_async(21)), 42);
+//! assert_eq!(mediator.handle_blocking(21), 42);
+//! ```
+//!
+//! [`spawn`] runs a future to completion on a thread of its own, which stands in for an executor's `spawn`,
+//! such as for the futures passed to [`crate::mediator::Mediate::notify_detached`].
+//! ```rust
+//! use noon::blocking;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::sync::mpsc;
+//!
+//! let (tx, rx) = mpsc::channel();
+//! let mediator = MediatorBuilder::new_shared()
+//!     .listen_for_async::<u32>()
+//!     .add_async_notification_receiver(move |x: u32| {
+//!         let tx = tx.clone();
+//!         async move { tx.send(x).unwrap() }
+//!     })
+//!     .build();
+//! mediator.notify_detached(7, |fut| drop(blocking::spawn(fut)));
+//! assert_eq!(rx.recv().unwrap(), 7);
+//! ```
+use crate::runtime;
+
+use std::future::Future;
+use std::thread::{self, JoinHandle};
+
+/// Runs `fut` to completion on the current thread.
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    runtime::block_on(fut)
+}
+
+/// Runs `fut` to completion on a new thread, returning a handle to join it.
+pub fn spawn<F>(fut: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    thread::Builder::new()
+        .name("noon-spawned".into())
+        .spawn(move || block_on(fut))
+        .expect("failed to spawn the future's thread")
+}
@@ -0,0 +1,210 @@
+//! Circuit breakers for fallible handlers.
+//!
+//! A [`CircuitBreaker`] counts the consecutive failures of the handlers it guards. Once they reach its threshold, the circuit opens
+//! and the handlers fail fast with [`CircuitOpen`] without being called, until the cooldown has passed.
+//! The circuit is then half-open: the next call is let through as a probe, closing the circuit if it succeeds and reopening it if it fails.
+//!
+//! Handlers are guarded with [`MediatorBuilder::add_try_handler_with_breaker`] or [`MediatorBuilder::add_async_try_handler_with_breaker`].
+//! Clones of a breaker share its state, so a clone can be kept to inspect it.
+//! ```rust
+//! use noon::breaker::{CircuitBreaker, CircuitOpen, CircuitState};
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum LookupError { Unavailable, Open }
+//!
+//! impl From<CircuitOpen> for LookupError {
+//!     fn from(_: CircuitOpen) -> Self { LookupError::Open }
+//! }
+//!
+//! let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler_with_breaker(breaker.clone(), |_: u32| Err::<u32, _>(LookupError::Unavailable))
+//!     .build();
+//! assert_eq!(mediator.try_handle::<_, u32, _, _>(1), Err(LookupError::Unavailable));
+//! assert_eq!(mediator.try_handle::<_, u32, _, _>(1), Err(LookupError::Unavailable));
+//! assert_eq!(breaker.state(), CircuitState::Open);
+//! assert_eq!(mediator.try_handle::<_, u32, _, _>(1), Err(LookupError::Open));
+//! ```
+//!
+//! [`MediatorBuilder::add_try_handler_with_breaker`]: crate::mediator::MediatorBuilder::add_try_handler_with_breaker
+//! [`MediatorBuilder::add_async_try_handler_with_breaker`]: crate::mediator::MediatorBuilder::add_async_try_handler_with_breaker
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The error returned instead of calling a handler while its circuit is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen;
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("circuit is open")
+    }
+}
+
+impl Error for CircuitOpen {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls are let through.
+    Closed,
+    /// Calls fail fast until the cooldown has passed.
+    Open,
+    /// A probe call is in flight, and other calls fail fast until it completes.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    circuit: Arc<Mutex<Circuit>>,
+}
+
+impl CircuitBreaker {
+    /// Opens the circuit after `failure_threshold` consecutive failures, for `cooldown`.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            circuit: Arc::new(Mutex::new(Circuit {
+                failure_threshold,
+                cooldown,
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+            })),
+        }
+    }
+
+    /// The state of the circuit. An open circuit whose cooldown has passed is reported as open until the next call probes it.
+    pub fn state(&self) -> CircuitState {
+        self.circuit.lock().unwrap().state
+    }
+
+    /// The number of failures since the last success.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.circuit.lock().unwrap().consecutive_failures
+    }
+
+    /// When the circuit was last opened, if it isn't closed.
+    pub fn opened_at(&self) -> Option<Instant> {
+        self.circuit.lock().unwrap().opened_at
+    }
+
+    /// Closes the circuit and clears its failures.
+    pub fn reset(&self) {
+        let mut circuit = self.circuit.lock().unwrap();
+        circuit.state = CircuitState::Closed;
+        circuit.consecutive_failures = 0;
+        circuit.opened_at = None;
+    }
+
+    fn acquire(&self) -> Result<Permit, CircuitOpen> {
+        let mut circuit = self.circuit.lock().unwrap();
+        match circuit.state {
+            CircuitState::Closed => {}
+            CircuitState::Open
+                if circuit
+                    .opened_at
+                    .is_some_and(|opened_at| opened_at.elapsed() >= circuit.cooldown) =>
+            {
+                circuit.state = CircuitState::HalfOpen
+            }
+            CircuitState::Open | CircuitState::HalfOpen => return Err(CircuitOpen),
+        }
+        Ok(Permit {
+            breaker: self.clone(),
+            finished: false,
+        })
+    }
+
+    fn record(&self, succeeded: bool) {
+        let mut circuit = self.circuit.lock().unwrap();
+        if succeeded {
+            circuit.state = CircuitState::Closed;
+            circuit.consecutive_failures = 0;
+            circuit.opened_at = None;
+            return;
+        }
+        circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+        if circuit.state == CircuitState::HalfOpen
+            || circuit.consecutive_failures >= circuit.failure_threshold
+        {
+            circuit.state = CircuitState::Open;
+            circuit.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Calls `f` unless the circuit is open, recording whether it succeeded.
+    pub(crate) fn call<TResp, TErr: From<CircuitOpen>>(
+        &self,
+        f: impl FnOnce() -> Result<TResp, TErr>,
+    ) -> Result<TResp, TErr> {
+        let permit = self.acquire()?;
+        let result = f();
+        permit.finish(result.is_ok());
+        result
+    }
+
+    /// Like [`CircuitBreaker::call`], recording whether the future returned by `f` succeeded once it completes, see [`Attempt::run`].
+    #[cfg(feature = "async")]
+    pub(crate) fn call_async<Fut>(&self, f: impl FnOnce() -> Fut) -> Attempt<Fut> {
+        Attempt(self.acquire().map(|permit| (permit, f())))
+    }
+}
+
+/// A call started through [`CircuitBreaker::call_async`], if the circuit let it through.
+#[cfg(feature = "async")]
+pub(crate) struct Attempt<Fut>(Result<(Permit, Fut), CircuitOpen>);
+
+#[cfg(feature = "async")]
+impl<Fut> Attempt<Fut> {
+    pub(crate) async fn run<TResp, TErr: From<CircuitOpen>>(self) -> Result<TResp, TErr>
+    where
+        Fut: Future<Output = Result<TResp, TErr>>,
+    {
+        let (permit, fut) = self.0?;
+        let result = fut.await;
+        permit.finish(result.is_ok());
+        result
+    }
+}
+
+/// Allows a single call through the circuit.
+///
+/// A probe which is dropped without completing, because its handler panicked or its future was dropped,
+/// leaves the circuit open with its cooldown passed, so the next call probes it again.
+struct Permit {
+    breaker: CircuitBreaker,
+    finished: bool,
+}
+
+impl Permit {
+    fn finish(mut self, succeeded: bool) {
+        self.finished = true;
+        self.breaker.record(succeeded);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let mut circuit = self.breaker.circuit.lock().unwrap();
+        if circuit.state == CircuitState::HalfOpen {
+            circuit.state = CircuitState::Open;
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Fanning notifications out to subscribers which come and go at runtime.
+//!
+//! A type of message listened for with [`MediatorBuilder::listen_for_broadcast`] is sent to a bounded channel rather than to registered receivers.
+//! Any number of tasks can obtain a [`BroadcastReceiver`] with [`Mediate::subscribe_broadcast`] while the mediator is running,
+//! and each receives a copy of every message sent after it subscribed, with the same semantics as `tokio::sync::broadcast`.
+//! ```rust
+//! use noon::broadcast::TryRecvError;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .listen_for_broadcast::<u32>(16)
+//!     .build();
+//! let mut first = mediator.subscribe_broadcast::<u32, _>();
+//! assert_eq!(mediator.notify_broadcast(1), 1);
+//! let mut second = mediator.subscribe_broadcast::<u32, _>();
+//! assert_eq!(mediator.notify_broadcast(2), 2);
+//!
+//! assert_eq!(first.try_recv(), Ok(1));
+//! assert_eq!(first.try_recv(), Ok(2));
+//! assert_eq!(second.try_recv(), Ok(2));
+//! assert_eq!(second.try_recv(), Err(TryRecvError::Empty));
+//! ```
+//!
+//! The channel holds the last `capacity` messages. A receiver which falls further behind skips the oldest messages,
+//! and is told how many with [`RecvError::Lagged`], so a slow subscriber can't hold back the mediator or the other subscribers.
+//!
+//! [`MediatorBuilder::listen_for_broadcast`]: crate::mediator::MediatorBuilder::listen_for_broadcast
+//! [`Mediate::subscribe_broadcast`]: crate::mediator::Mediate::subscribe_broadcast
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Channel<TMsg> {
+    /// The last messages sent, the first of which is numbered `first`.
+    messages: VecDeque<TMsg>,
+    first: u64,
+    capacity: usize,
+    receivers: usize,
+    wakers: Vec<Waker>,
+    closed: bool,
+}
+
+impl<TMsg> Channel<TMsg> {
+    fn next(&self) -> u64 {
+        self.first + self.messages.len() as u64
+    }
+
+    fn wake_all(&mut self) {
+        self.wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+/// The sending side of a broadcast channel, held by the mediator's entry. The channel closes once every clone is dropped.
+pub(crate) struct Sender<TMsg> {
+    channel: Arc<Mutex<Channel<TMsg>>>,
+}
+
+impl<TMsg> Sender<TMsg> {
+    /// # Panics
+    /// If `capacity` is zero.
+    pub(crate) fn new(capacity: usize) -> Arc<Self> {
+        assert!(capacity > 0, "broadcast capacity must be positive");
+        Arc::new(Self {
+            channel: Arc::new(Mutex::new(Channel {
+                messages: VecDeque::with_capacity(capacity),
+                first: 0,
+                capacity,
+                receivers: 0,
+                wakers: vec![],
+                closed: false,
+            })),
+        })
+    }
+
+    /// Sends `msg` to every receiver, returning how many there are. With no receivers, the message is dropped.
+    pub(crate) fn send(&self, msg: TMsg) -> usize {
+        let mut channel = self.channel.lock().unwrap();
+        if channel.receivers == 0 {
+            return 0;
+        }
+        if channel.messages.len() == channel.capacity {
+            channel.messages.pop_front();
+            channel.first += 1;
+        }
+        channel.messages.push_back(msg);
+        channel.wake_all();
+        channel.receivers
+    }
+
+    pub(crate) fn subscribe(&self) -> BroadcastReceiver<TMsg> {
+        let mut channel = self.channel.lock().unwrap();
+        channel.receivers += 1;
+        BroadcastReceiver {
+            next: channel.next(),
+            channel: Arc::clone(&self.channel),
+        }
+    }
+
+    pub(crate) fn receiver_count(&self) -> usize {
+        self.channel.lock().unwrap().receivers
+    }
+}
+
+impl<TMsg> Drop for Sender<TMsg> {
+    fn drop(&mut self) {
+        let mut channel = self.channel.lock().unwrap();
+        channel.closed = true;
+        channel.wake_all();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The mediator was dropped, and every message sent before has been received.
+    Closed,
+    /// The receiver fell behind, and skipped this many messages. The next receive returns the oldest message still held.
+    Lagged(u64),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Closed => f.write_str("broadcast channel closed"),
+            RecvError::Lagged(skipped) => write!(f, "receiver lagged by {} message(s)", skipped),
+        }
+    }
+}
+
+impl Error for RecvError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No message has been sent since the last one received.
+    Empty,
+    Closed,
+    Lagged(u64),
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("broadcast channel empty"),
+            TryRecvError::Closed => RecvError::Closed.fmt(f),
+            TryRecvError::Lagged(skipped) => RecvError::Lagged(*skipped).fmt(f),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
+
+/// Receives the messages broadcast after it subscribed, see [`crate::broadcast`].
+///
+/// Clones receive the same messages from the position of the receiver they were cloned from.
+pub struct BroadcastReceiver<TMsg> {
+    channel: Arc<Mutex<Channel<TMsg>>>,
+    next: u64,
+}
+
+impl<TMsg: Clone> BroadcastReceiver<TMsg> {
+    pub fn try_recv(&mut self) -> Result<TMsg, TryRecvError> {
+        let channel = self.channel.lock().unwrap();
+        if self.next < channel.first {
+            let skipped = channel.first - self.next;
+            self.next = channel.first;
+            return Err(TryRecvError::Lagged(skipped));
+        }
+        let index = (self.next - channel.first) as usize;
+        match channel.messages.get(index) {
+            Some(msg) => {
+                self.next += 1;
+                Ok(msg.clone())
+            }
+            None if channel.closed => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Resolves to the next message, once it's sent.
+    pub fn recv(&mut self) -> Recv<'_, TMsg> {
+        Recv { receiver: self }
+    }
+}
+
+impl<TMsg> Clone for BroadcastReceiver<TMsg> {
+    fn clone(&self) -> Self {
+        self.channel.lock().unwrap().receivers += 1;
+        Self {
+            channel: Arc::clone(&self.channel),
+            next: self.next,
+        }
+    }
+}
+
+impl<TMsg> Drop for BroadcastReceiver<TMsg> {
+    fn drop(&mut self) {
+        self.channel.lock().unwrap().receivers -= 1;
+    }
+}
+
+impl<TMsg> fmt::Debug for BroadcastReceiver<TMsg> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BroadcastReceiver")
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+pub struct Recv<'a, TMsg> {
+    receiver: &'a mut BroadcastReceiver<TMsg>,
+}
+
+impl<TMsg: Clone> Future for Recv<'_, TMsg> {
+    type Output = Result<TMsg, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.receiver.try_recv() {
+            Ok(msg) => Poll::Ready(Ok(msg)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Lagged(skipped)) => Poll::Ready(Err(RecvError::Lagged(skipped))),
+            Err(TryRecvError::Empty) => {
+                let mut channel = self.receiver.channel.lock().unwrap();
+                // A message may have been sent since `try_recv` released the lock.
+                if channel.next() > self.receiver.next || channel.closed {
+                    drop(channel);
+                    return self.poll(cx);
+                }
+                if !channel
+                    .wakers
+                    .iter()
+                    .any(|waker| waker.will_wake(cx.waker()))
+                {
+                    channel.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Piping a stream of requests through an asynchronous handler.
+//!
+//! [`Mediate::handle_stream_of`] calls the asynchronous handler for every item of a [`Stream`] of messages,
+//! with at most a given number of calls in flight, and yields their responses as a stream in the order they complete,
+//! like `buffer_unordered` from the `futures` crate, but checked against the mediator's handlers.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::stream::{self, StreamExt};
+//! # use std::sync::Arc;
+//! # use std::task::{Context, Poll, Wake, Waker};
+//! # struct Noop;
+//! # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+//! # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+//! #     let waker = Waker::from(Arc::new(Noop));
+//! #     let mut fut = std::pin::pin!(fut);
+//! #     loop {
+//! #         if let Poll::Ready(out) = fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+//! #             return out;
+//! #         }
+//! #     }
+//! # }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_async_handler(|x: u32| async move { x * 2 })
+//!     .build();
+//! let mut doubled = mediator.handle_stream_of::<u32, u32, _, _>(stream::iter([1, 2, 3]), 2);
+//! block_on(async {
+//!     let mut total = 0;
+//!     while let Some(x) = doubled.next().await {
+//!         total += x;
+//!     }
+//!     assert_eq!(total, 12);
+//! });
+//! ```
+//!
+//! A call is started for the next message as soon as one completes, so a slow call doesn't hold back the others.
+//!
+//! [`Mediate::handle_stream_of`]: crate::mediator::Mediate::handle_stream_of
+use crate::entry::RequestResponseAsync;
+use crate::flavor::{BoxFuture, Flavor};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+use crate::stream::Stream;
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The responses to the messages of a stream, see [`crate::buffered`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Buffered<'a, M: ?Sized, St, TResp: 'static, I, S: Flavor> {
+    mediator: &'a M,
+    /// `None` once the messages are exhausted.
+    messages: Option<St>,
+    in_flight: Vec<BoxFuture<'static, TResp, S>>,
+    concurrency: usize,
+    _index: PhantomData<fn() -> I>,
+}
+
+impl<'a, M: ?Sized, St, TResp: 'static, I, S: Flavor> Buffered<'a, M, St, TResp, I, S> {
+    /// # Panics
+    /// If `concurrency` is zero.
+    pub(crate) fn new(mediator: &'a M, messages: St, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "concurrency must be positive");
+        Self {
+            mediator,
+            messages: Some(messages),
+            in_flight: Vec::with_capacity(concurrency),
+            concurrency,
+            _index: PhantomData,
+        }
+    }
+
+    /// The number of calls which haven't completed yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+}
+
+impl<M, St, TResp, I, S> Stream for Buffered<'_, M, St, TResp, I, S>
+where
+    M: Mediate<S> + ?Sized,
+    S: Flavor,
+    St: Stream + Unpin,
+    St::Item: 'static,
+    TResp: 'static,
+    M::Handlers: ContainsAt<RequestResponseAsync<St::Item, TResp, S>, I>,
+{
+    type Item = TResp;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TResp>> {
+        let this = self.get_mut();
+        while this.in_flight.len() < this.concurrency {
+            let Some(messages) = &mut this.messages else {
+                break;
+            };
+            match Pin::new(messages).poll_next(cx) {
+                Poll::Ready(Some(msg)) => this.in_flight.push(this.mediator.handle_async(msg)),
+                Poll::Ready(None) => this.messages = None,
+                Poll::Pending => break,
+            }
+        }
+        for index in 0..this.in_flight.len() {
+            if let Poll::Ready(resp) = this.in_flight[index].as_mut().poll(cx) {
+                drop(this.in_flight.swap_remove(index));
+                if this.messages.is_some() {
+                    // Starts the next call, or registers for the next message.
+                    cx.waker().wake_by_ref();
+                }
+                return Poll::Ready(Some(resp));
+            }
+        }
+        if this.messages.is_none() && this.in_flight.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
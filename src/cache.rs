@@ -0,0 +1,265 @@
+//! Memoized handlers.
+//!
+//! A handler registered with [`MediatorBuilder::add_cached_handler`] stores its responses by message,
+//! so dispatching an equal message again returns a clone of the stored response without calling the handler.
+//! Stored responses are removed by dispatching [`Invalidate`] for the type of message,
+//! and the cache's counters are returned by dispatching [`CacheStats`].
+//! ```rust
+//! use noon::cache::{CacheStats, Invalidate};
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! #[derive(Clone, PartialEq, Eq, Hash)]
+//! struct GetPrice(&'static str);
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_cached_handler(|req: GetPrice| req.0.len() as u32 * 100)
+//!     .build();
+//! assert_eq!(mediator.handle(GetPrice("tea")), 300);
+//! assert_eq!(mediator.handle(GetPrice("tea")), 300); // not recomputed
+//! mediator.handle(Invalidate::Key(GetPrice("tea")));
+//!
+//! let stats = mediator.handle(CacheStats::<GetPrice>::new());
+//! assert_eq!((stats.hits, stats.misses), (1, 1));
+//! ```
+//! How long responses are kept and how many are kept is configured with a [`CachePolicy`],
+//! see [`MediatorBuilder::add_cached_handler_with_policy`].
+//!
+//! [`MediatorBuilder::add_cached_handler`]: crate::mediator::MediatorBuilder::add_cached_handler
+//! [`MediatorBuilder::add_cached_handler_with_policy`]: crate::mediator::MediatorBuilder::add_cached_handler_with_policy
+use crate::entry::RequestResponse;
+use crate::flavor::Local;
+use crate::hlist::Cons;
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The handlers registered by [`crate::mediator::MediatorBuilder::add_cached_handler`] in front of `H`.
+pub type Cached<TMsg, TResp, H, S = Local> = Cons<
+    RequestResponse<CacheStats<TMsg>, CacheMetrics, S>,
+    Cons<RequestResponse<Invalidate<TMsg>, (), S>, Cons<RequestResponse<TMsg, TResp, S>, H>>,
+>;
+
+/// Removes responses stored by a handler registered with [`crate::mediator::MediatorBuilder::add_cached_handler`].
+pub enum Invalidate<TMsg> {
+    /// Removes every stored response.
+    All,
+    /// Removes the response stored for a message equal to this one.
+    Key(TMsg),
+}
+
+/// Requests the [`CacheMetrics`] of the cached handler for `TMsg`.
+pub struct CacheStats<TMsg>(PhantomData<fn() -> TMsg>);
+
+impl<TMsg> CacheStats<TMsg> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<TMsg> Default for CacheStats<TMsg> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counters of a cached handler, returned when handling [`CacheStats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheMetrics {
+    /// Dispatches answered with a stored response.
+    pub hits: u64,
+    /// Dispatches which called the handler.
+    pub misses: u64,
+    /// Responses removed because they expired or the cache was full, excluding [`Invalidate`].
+    pub evictions: u64,
+    /// Responses currently stored.
+    pub len: usize,
+}
+
+/// Which response is removed when a full cache stores another one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    /// Removes the response which was returned least recently.
+    #[default]
+    LeastRecentlyUsed,
+    /// Removes the response which was stored first.
+    FirstIn,
+}
+
+/// Configures how long a cached handler keeps its responses, and how many.
+///
+/// The default policy keeps every response until it is invalidated.
+/// ```rust
+/// use noon::cache::{CachePolicy, Eviction};
+/// use std::time::Duration;
+///
+/// let policy = CachePolicy::new()
+///     .with_ttl(Duration::from_secs(30))
+///     .with_capacity(1000)
+///     .with_eviction(Eviction::FirstIn);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    ttl: Option<Duration>,
+    capacity: Option<usize>,
+    eviction: Eviction,
+}
+
+impl CachePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expires responses once they have been stored for `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Keeps at most `capacity` responses, removing one according to the eviction strategy when another is stored.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_eviction(mut self, eviction: Eviction) -> Self {
+        self.eviction = eviction;
+        self
+    }
+}
+
+struct Stored<TResp> {
+    resp: TResp,
+    stored_at: Instant,
+    inserted: u64,
+    used: u64,
+}
+
+struct Entries<TMsg, TResp> {
+    stored: HashMap<TMsg, Stored<TResp>>,
+    tick: u64,
+    metrics: CacheMetrics,
+}
+
+/// The responses stored for a cached handler.
+///
+/// The lock is only held while looking up or storing a response, so the handler can dispatch other messages.
+/// Finding the response to evict scans every stored response.
+pub(crate) struct Cache<TMsg, TResp> {
+    policy: CachePolicy,
+    entries: Mutex<Entries<TMsg, TResp>>,
+}
+
+impl<TMsg: Hash + Eq, TResp: Clone> Cache<TMsg, TResp> {
+    pub(crate) fn new(policy: CachePolicy) -> Self {
+        Self {
+            policy,
+            entries: Mutex::new(Entries {
+                stored: HashMap::new(),
+                tick: 0,
+                metrics: CacheMetrics::default(),
+            }),
+        }
+    }
+
+    /// Returns the stored response for `msg`, or stores and returns the response from `f`.
+    pub(crate) fn get_or_insert_with(&self, msg: TMsg, f: impl FnOnce(TMsg) -> TResp) -> TResp
+    where
+        TMsg: Clone,
+    {
+        if let Some(resp) = self.get(&msg) {
+            return resp;
+        }
+        let resp = f(msg.clone());
+        self.insert(msg, resp.clone());
+        resp
+    }
+
+    fn get(&self, msg: &TMsg) -> Option<TResp> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let expired = match entries.stored.get_mut(msg) {
+            Some(stored) if !self.is_expired(stored) => {
+                stored.used = tick;
+                let resp = stored.resp.clone();
+                entries.metrics.hits += 1;
+                return Some(resp);
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            entries.stored.remove(msg);
+            entries.metrics.evictions += 1;
+        }
+        entries.metrics.misses += 1;
+        None
+    }
+
+    fn insert(&self, msg: TMsg, resp: TResp) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(capacity) = self.policy.capacity {
+            if capacity == 0 {
+                return;
+            }
+            if !entries.stored.contains_key(&msg) && entries.stored.len() >= capacity {
+                self.evict(&mut entries);
+            }
+        }
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.stored.insert(
+            msg,
+            Stored {
+                resp,
+                stored_at: Instant::now(),
+                inserted: tick,
+                used: tick,
+            },
+        );
+    }
+
+    /// Removes the expired responses, or a single response chosen by the eviction strategy if none have expired.
+    fn evict(&self, entries: &mut Entries<TMsg, TResp>) {
+        let before = entries.stored.len();
+        entries.stored.retain(|_, stored| !self.is_expired(stored));
+        if entries.stored.len() == before {
+            // Ticks are unique, so they identify the response to remove without borrowing its key.
+            let order = |stored: &Stored<TResp>| match self.policy.eviction {
+                Eviction::LeastRecentlyUsed => stored.used,
+                Eviction::FirstIn => stored.inserted,
+            };
+            if let Some(victim) = entries.stored.values().map(order).min() {
+                entries.stored.retain(|_, stored| order(stored) != victim);
+            }
+        }
+        entries.metrics.evictions += (before - entries.stored.len()) as u64;
+    }
+
+    fn is_expired(&self, stored: &Stored<TResp>) -> bool {
+        self.policy
+            .ttl
+            .is_some_and(|ttl| stored.stored_at.elapsed() >= ttl)
+    }
+
+    pub(crate) fn invalidate(&self, which: Invalidate<TMsg>) {
+        let mut entries = self.entries.lock().unwrap();
+        match which {
+            Invalidate::All => entries.stored.clear(),
+            Invalidate::Key(msg) => {
+                entries.stored.remove(&msg);
+            }
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> CacheMetrics {
+        let entries = self.entries.lock().unwrap();
+        CacheMetrics {
+            len: entries.stored.len(),
+            ..entries.metrics
+        }
+    }
+}
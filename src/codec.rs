@@ -0,0 +1,324 @@
+//! Dispatching serialized messages by a string tag.
+//!
+//! A [`TaggedMediator`] maps string tags to the types of messages a mediator handles,
+//! so a message arriving as bytes, for example from a queue or the body of an HTTP request,
+//! can be decoded and routed to its typed handler with [`TaggedMediator::dispatch_envelope`].
+//!
+//! Messages are decoded with [`Decode`] and responses are encoded with [`Encode`], which are implemented for the types to dispatch,
+//! typically in terms of a serialization library such as `serde_json`:
+//! ```rust
+//! use noon::codec::{BoxError, Decode, Encode, TaggedMediator};
+//! use noon::mediator::MediatorBuilder;
+//!
+//! struct CreateUser { name: String }
+//! struct UserCreated(u32);
+//!
+//! impl Decode for CreateUser {
+//!     fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+//!         // serde_json::from_slice(bytes).map_err(Into::into)
+//!         Ok(CreateUser { name: String::decode(bytes)? })
+//!     }
+//! }
+//!
+//! impl Encode for UserCreated {
+//!     fn encode(&self) -> Vec<u8> {
+//!         // serde_json::to_vec(self).unwrap()
+//!         self.0.to_string().into_bytes()
+//!     }
+//! }
+//!
+//! let mediator = TaggedMediator::new(
+//!     MediatorBuilder::new()
+//!         .add_handler(|req: CreateUser| UserCreated(req.name.len() as u32))
+//!         .build(),
+//! )
+//! .handle::<CreateUser, UserCreated, _>("create_user");
+//! assert_eq!(mediator.dispatch_envelope("create_user", b"noon").unwrap(), b"4");
+//! assert!(mediator.dispatch_envelope("delete_user", b"noon").is_err());
+//! ```
+use crate::entry::{ReceiveNotification, RequestResponse};
+use crate::flavor::{Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A message which can be decoded from bytes.
+pub trait Decode: Sized {
+    fn decode(bytes: &[u8]) -> Result<Self, BoxError>;
+}
+
+/// A response which can be encoded as bytes.
+pub trait Encode {
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// Decodes UTF-8.
+impl Decode for String {
+    fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+}
+
+impl Decode for Vec<u8> {
+    fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Decodes anything, ignoring the bytes.
+impl Decode for () {
+    fn decode(_bytes: &[u8]) -> Result<Self, BoxError> {
+        Ok(())
+    }
+}
+
+impl Encode for String {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl Encode for Vec<u8> {
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+/// Encodes as no bytes.
+impl Encode for () {
+    fn encode(&self) -> Vec<u8> {
+        vec![]
+    }
+}
+
+/// The error returned when a serialized message can't be dispatched.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// No type of message is registered with the tag.
+    UnknownTag(String),
+    /// The message couldn't be decoded as the type registered with its tag.
+    Decode { tag: String, source: BoxError },
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::UnknownTag(tag) => {
+                write!(f, "no message is registered with tag `{}`", tag)
+            }
+            EnvelopeError::Decode { tag, source } => {
+                write!(f, "couldn't decode message with tag `{}`: {}", tag, source)
+            }
+        }
+    }
+}
+
+impl Error for EnvelopeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EnvelopeError::UnknownTag(_) => None,
+            EnvelopeError::Decode { source, .. } => Some(&**source),
+        }
+    }
+}
+
+/// Decodes a message and dispatches it, returning the encoded response.
+type Route<M> = fn(&M, &[u8]) -> Result<Vec<u8>, BoxError>;
+
+/// Decodes an old version of a message and encodes it as the next version.
+type Upcast = fn(&[u8]) -> Result<Vec<u8>, BoxError>;
+
+/// A mediator which dispatches serialized messages by tag, see [`crate::codec`].
+pub struct TaggedMediator<M, S: Flavor = Local> {
+    mediator: M,
+    routes: HashMap<&'static str, Route<M>>,
+    /// The tag and upcast of the next version of each old version of a message.
+    upcasts: HashMap<&'static str, (&'static str, Upcast)>,
+    _flavor: PhantomData<S>,
+}
+
+impl<M: Mediate<S>, S: Flavor> TaggedMediator<M, S> {
+    pub fn new(mediator: M) -> Self {
+        Self {
+            mediator,
+            routes: HashMap::new(),
+            upcasts: HashMap::new(),
+            _flavor: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.mediator
+    }
+
+    pub fn into_inner(self) -> M {
+        self.mediator
+    }
+
+    /// Routes messages tagged `tag` to the handler for `TMsg`.
+    ///
+    /// # Panics
+    /// If another type of message is already registered with `tag`.
+    pub fn handle<TMsg, TResp, I>(self, tag: &'static str) -> Self
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Decode,
+        TResp: Encode,
+    {
+        fn route<M, S, TMsg, TResp, I>(mediator: &M, bytes: &[u8]) -> Result<Vec<u8>, BoxError>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+            TMsg: Decode,
+            TResp: Encode,
+        {
+            let msg = TMsg::decode(bytes)?;
+            Ok(mediator.handle::<TMsg, TResp, I>(msg).encode())
+        }
+        self.route(tag, route::<M, S, TMsg, TResp, I>)
+    }
+
+    /// Routes messages tagged `tag` to the notification receivers for `TMsg`, which respond with no bytes.
+    ///
+    /// # Panics
+    /// If another type of message is already registered with `tag`.
+    pub fn notify<TMsg, I>(self, tag: &'static str) -> Self
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        TMsg: Decode,
+    {
+        fn route<M, S, TMsg, I>(mediator: &M, bytes: &[u8]) -> Result<Vec<u8>, BoxError>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+            TMsg: Decode,
+        {
+            let msg = TMsg::decode(bytes)?;
+            mediator.notify::<TMsg, I>(&msg);
+            Ok(vec![])
+        }
+        self.route(tag, route::<M, S, TMsg, I>)
+    }
+
+    /// Migrates messages tagged `from`, an old version of a message, to `TNew` tagged `to` before dispatching them.
+    ///
+    /// `to` can itself be an old version upcast to a newer one, so a chain of versions such as `V1 -> V2 -> V3`
+    /// is registered one step at a time, and a `V1` message passes through every step before reaching the handler for `V3`.
+    /// ```rust
+    /// use noon::codec::{BoxError, Decode, Encode, TaggedMediator};
+    /// use noon::mediator::MediatorBuilder;
+    ///
+    /// struct GreetV1(String);
+    /// struct GreetV2 { name: String, excited: bool }
+    ///
+    /// impl From<GreetV1> for GreetV2 {
+    ///     fn from(GreetV1(name): GreetV1) -> Self {
+    ///         GreetV2 { name, excited: false }
+    ///     }
+    /// }
+    /// # impl Decode for GreetV1 {
+    /// #     fn decode(bytes: &[u8]) -> Result<Self, BoxError> { Ok(GreetV1(String::decode(bytes)?)) }
+    /// # }
+    /// # impl Decode for GreetV2 {
+    /// #     fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+    /// #         let text = String::decode(bytes)?;
+    /// #         Ok(GreetV2 { excited: text.ends_with('!'), name: text.trim_end_matches('!').to_string() })
+    /// #     }
+    /// # }
+    /// # impl Encode for GreetV2 {
+    /// #     fn encode(&self) -> Vec<u8> { format!("{}{}", self.name, if self.excited { "!" } else { "" }).into_bytes() }
+    /// # }
+    ///
+    /// let mediator = TaggedMediator::new(
+    ///     MediatorBuilder::new()
+    ///         .add_handler(|req: GreetV2| format!("Hello, {}{}", req.name, if req.excited { "!" } else { "." }))
+    ///         .build(),
+    /// )
+    /// .handle::<GreetV2, String, _>("greet.v2")
+    /// .upcast::<GreetV1, GreetV2>("greet.v1", "greet.v2");
+    /// assert_eq!(mediator.dispatch_envelope("greet.v1", b"noon").unwrap(), b"Hello, noon.");
+    /// assert_eq!(mediator.dispatch_envelope("greet.v2", b"noon!").unwrap(), b"Hello, noon!");
+    /// ```
+    ///
+    /// # Panics
+    /// If `from` is already registered, or if the upcast would complete a cycle of versions.
+    pub fn upcast<TOld, TNew>(mut self, from: &'static str, to: &'static str) -> Self
+    where
+        TOld: Decode,
+        TNew: From<TOld> + Encode,
+    {
+        fn upcast<TOld: Decode, TNew: From<TOld> + Encode>(
+            bytes: &[u8],
+        ) -> Result<Vec<u8>, BoxError> {
+            Ok(TNew::from(TOld::decode(bytes)?).encode())
+        }
+        self.assert_unregistered(from);
+        let mut next = to;
+        while next != from {
+            match self.upcasts.get(next) {
+                Some((newer, _)) => next = newer,
+                None => {
+                    self.upcasts.insert(from, (to, upcast::<TOld, TNew>));
+                    return self;
+                }
+            }
+        }
+        panic!("upcasting `{}` to `{}` would form a cycle", from, to);
+    }
+
+    fn route(mut self, tag: &'static str, route: Route<M>) -> Self {
+        self.assert_unregistered(tag);
+        self.routes.insert(tag, route);
+        self
+    }
+
+    fn assert_unregistered(&self, tag: &str) {
+        let registered = self.routes.contains_key(tag) || self.upcasts.contains_key(tag);
+        assert!(!registered, "tag `{}` is already registered", tag);
+    }
+
+    /// The tags messages can be dispatched with, including the tags of old versions, in no particular order.
+    pub fn tags(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.routes.keys().chain(self.upcasts.keys()).copied()
+    }
+
+    /// Decodes `bytes` as the type of message registered with `tag`, dispatches it, and returns the encoded response.
+    ///
+    /// A message tagged with an old version is first upcast to the current one, see [`TaggedMediator::upcast`].
+    pub fn dispatch_envelope(&self, tag: &str, bytes: &[u8]) -> Result<Vec<u8>, EnvelopeError> {
+        let decode_error = |tag: &str| {
+            let tag = tag.to_string();
+            move |source| EnvelopeError::Decode { tag, source }
+        };
+        let mut tag = tag;
+        let mut upcast_bytes = None;
+        while let Some((newer, upcast)) = self.upcasts.get(tag) {
+            let bytes = upcast_bytes.as_deref().unwrap_or(bytes);
+            upcast_bytes = Some(upcast(bytes).map_err(decode_error(tag))?);
+            tag = newer;
+        }
+        let bytes = upcast_bytes.as_deref().unwrap_or(bytes);
+        let route = self
+            .routes
+            .get(tag)
+            .ok_or_else(|| EnvelopeError::UnknownTag(tag.to_string()))?;
+        route(&self.mediator, bytes).map_err(decode_error(tag))
+    }
+}
+
+impl<M, S: Flavor> fmt::Debug for TaggedMediator<M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedMediator")
+            .field("tags", &self.routes.keys())
+            .field("upcasts", &self.upcasts.keys())
+            .finish_non_exhaustive()
+    }
+}
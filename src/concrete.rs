@@ -1,63 +1,315 @@
-use crate::entry::RequestResponse;
-use crate::hlist::{ContainsAt, HList};
+use crate::describe::{Describer, Description};
+#[cfg(feature = "std")]
+use crate::entry::ReceiveNotificationBroadcast;
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationOwned, RequestResponse, RequestResponseFn,
+    SubscriptionId, TryReceiveNotification, TryRequestResponse,
+};
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, BoxStream, IntoAsyncHandler};
+use crate::flavor::{Flavor, IntoReceiver};
+use crate::hlist::{ContainsAt, HList, HVisit};
+#[cfg(feature = "std")]
+use crate::hooks::ReceiverPanic;
+use crate::hooks::{Dispatch, DispatchHooks, DispatchKind};
+#[cfg(feature = "async")]
+use crate::hooks::{Dispatched, Started};
 use crate::mediator::Mediate;
 
-use std::future::Future;
-use std::pin::Pin;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::Future;
 
-pub struct Mediator<H, N> {
+pub struct Mediator<H, N, S: Flavor> {
     contents: H,
     receivers: N,
+    hooks: Arc<DispatchHooks<S>>,
 }
 
-impl<H: HList, N: HList> Mediator<H, N> {
-    pub(crate) fn new(contents: H, receivers: N) -> Self {
+impl<H: Clone, N: Clone, S: Flavor> Clone for Mediator<H, N, S> {
+    fn clone(&self) -> Self {
+        Self {
+            contents: self.contents.clone(),
+            receivers: self.receivers.clone(),
+            hooks: Arc::clone(&self.hooks),
+        }
+    }
+}
+
+/// Lists the registered entries, see [`crate::describe`].
+impl<H: HVisit<Describer>, N: HVisit<Describer>, S: Flavor> fmt::Debug for Mediator<H, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Description::new(&self.contents, &self.receivers).debug_struct(f, "Mediator")
+    }
+}
+
+impl<H: HList, N: HList, S: Flavor> Mediator<H, N, S> {
+    pub(crate) fn new(contents: H, receivers: N, hooks: DispatchHooks<S>) -> Self {
         Self {
             contents,
             receivers,
+            hooks: Arc::new(hooks),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn after_dispatch<'a, T: 'a>(
+        &self,
+        fut: BoxFuture<'a, T, S>,
+        dispatch: Dispatch,
+        started: Started,
+    ) -> BoxFuture<'a, T, S> {
+        if self.hooks.has_after() {
+            S::after_dispatch(fut, Arc::clone(&self.hooks), dispatch, started)
+        } else {
+            fut
         }
     }
 }
 
-impl<H: HList, N: HList> Mediate for Mediator<H, N> {
+impl<H: HList, N: HList, S: Flavor> Mediate<S> for Mediator<H, N, S> {
     type Handlers = H;
     type NotifyReceivers = N;
 
     fn handle<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
     where
-        Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp>, I>,
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
     {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::Handle);
+        let started = self.hooks.before(&dispatch);
         let handler = self.contents.take();
-        handler.call(msg)
+        let resp = handler.call(msg);
+        self.hooks.after(&dispatch, started);
+        resp
     }
 
+    fn handle_unboxed<TMsg, TResp, F, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponseFn<TMsg, TResp, F>, I>,
+        F: Fn(TMsg) -> TResp,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::Handle);
+        let started = self.hooks.before(&dispatch);
+        let handler = self.contents.take();
+        let resp = handler.call(msg);
+        self.hooks.after(&dispatch, started);
+        resp
+    }
+
+    #[cfg(feature = "async")]
     fn handle_async<TMsg: 'static, TResp: 'static, I>(
         &self,
         msg: TMsg,
-    ) -> Pin<Box<dyn Future<Output = TResp>>>
+    ) -> BoxFuture<'static, TResp, S>
     where
-        Self::Handlers: ContainsAt<crate::entry::RequestResponseAsync<TMsg, TResp>, I>,
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
     {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::HandleAsync);
+        let started = self.hooks.before(&dispatch);
         let handler = self.contents.take();
-        Box::pin(handler.call(msg))
+        self.after_dispatch(handler.call(msg), dispatch, started)
     }
 
-    fn notify<TMsg: ?Sized, I>(&self, msg: &TMsg)
+    #[cfg(feature = "async")]
+    fn handle_async_future<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> Dispatched<BoxFuture<'static, TResp, S>, S>
     where
-        Self::NotifyReceivers: ContainsAt<crate::entry::ReceiveNotification<TMsg>, I>,
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
     {
-        let receivers = self.receivers.take();
-        receivers.call(msg)
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::HandleAsync);
+        let started = self.hooks.before(&dispatch);
+        let handler = self.contents.take();
+        Dispatched::new(handler.call(msg), &self.hooks, dispatch, started)
     }
 
-    fn notify_async<TMsg: Clone + 'static, I>(
+    fn try_handle<TMsg, TResp, TErr, I>(&self, msg: TMsg) -> Result<TResp, TErr>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::TryHandle);
+        let started = self.hooks.before(&dispatch);
+        let handler = self.contents.take();
+        let resp = handler.call(msg);
+        self.hooks.after(&dispatch, started);
+        resp
+    }
+
+    #[cfg(feature = "async")]
+    fn try_handle_async<TMsg: 'static, TResp: 'static, TErr: 'static, I>(
         &self,
         msg: TMsg,
-    ) -> Pin<Box<dyn Future<Output = ()> + '_>>
+    ) -> BoxFuture<'static, Result<TResp, TErr>, S>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::TryHandleAsync);
+        let started = self.hooks.before(&dispatch);
+        let handler = self.contents.take();
+        self.after_dispatch(handler.call(msg), dispatch, started)
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_stream<TMsg, TItem, I>(&self, msg: TMsg) -> BoxStream<TItem, S>
+    where
+        Self::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TItem>(DispatchKind::HandleStream);
+        let started = self.hooks.before(&dispatch);
+        let handler = self.contents.take();
+        let stream = handler.call(msg);
+        self.hooks.after(&dispatch, started);
+        stream
+    }
+
+    fn notify<TMsg: ?Sized, I>(&self, msg: &TMsg)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::Notify);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        #[cfg(feature = "std")]
+        if self.hooks.isolates_panics() {
+            receivers.call_isolated(msg, |payload| {
+                self.hooks.panicked(&ReceiverPanic::new(dispatch, payload))
+            });
+            self.hooks.after(&dispatch, started);
+            return;
+        }
+        receivers.call(msg);
+        self.hooks.after(&dispatch, started);
+    }
+
+    fn try_notify<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Vec<TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TErr>(DispatchKind::TryNotify);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        let errors = receivers.call(msg);
+        self.hooks.after(&dispatch, started);
+        errors
+    }
+
+    fn try_notify_each<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Result<(), TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TErr>(DispatchKind::TryNotifyEach);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        let result = receivers.call_each(msg);
+        self.hooks.after(&dispatch, started);
+        result
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_detached<TMsg: Clone + 'static, I, F>(&self, msg: TMsg, spawn: F)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        F: FnMut(BoxFuture<'static, (), S>),
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyDetached);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        receivers.futures(msg).into_iter().for_each(spawn);
+        self.hooks.after(&dispatch, started);
+    }
+
+    fn notify_owned<TMsg, I>(&self, msg: TMsg)
     where
-        Self::NotifyReceivers: ContainsAt<crate::entry::ReceiveNotificationAsync<TMsg>, I>,
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
     {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyOwned);
+        let started = self.hooks.before(&dispatch);
         let receivers = self.receivers.take();
-        Box::pin(receivers.call(msg))
+        receivers.call(msg);
+        self.hooks.after(&dispatch, started);
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_async<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyAsync);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        self.after_dispatch(receivers.call(msg), dispatch, started)
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_shared<TMsg: ?Sized + 'static, I>(&self, msg: Arc<TMsg>) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyShared);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        self.after_dispatch(receivers.call(msg), dispatch, started)
+    }
+
+    #[cfg(feature = "std")]
+    fn notify_broadcast<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> usize
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyBroadcast);
+        let started = self.hooks.before(&dispatch);
+        let receivers = self.receivers.take();
+        let sent = receivers.call(msg);
+        self.hooks.after(&dispatch, started);
+        sent
+    }
+
+    fn subscribe<TMsg: ?Sized, I, F>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: Fn(&TMsg) + IntoReceiver<S, TMsg>,
+    {
+        self.receivers.take().add(receiver)
+    }
+
+    #[cfg(feature = "async")]
+    fn subscribe_async<TMsg: Clone, I, F, Fut>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        Fut: Future<Output = ()> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, ()>,
+    {
+        self.receivers.take().add(receiver)
+    }
+
+    fn unsubscribe<TMsg: ?Sized, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+    {
+        self.receivers.take().remove(id)
+    }
+
+    #[cfg(feature = "async")]
+    fn unsubscribe_async<TMsg: Clone, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        self.receivers.take().remove(id)
+    }
+
+    fn handlers(&self) -> &H {
+        &self.contents
+    }
+
+    fn notify_receivers(&self) -> &N {
+        &self.receivers
     }
 }
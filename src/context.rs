@@ -0,0 +1,191 @@
+//! Request-scoped data accompanying a dispatch.
+//!
+//! A [`Context`] is a typed bag holding at most one value of each type, such as an authenticated principal or a trace id.
+//! Handlers opt into receiving one by taking a `(TMsg, Context)` tuple, and are invoked through [`crate::mediator::Mediate::handle_with_context`]
+//! or [`crate::mediator::Mediate::handle_async_with_context`]. Pipeline behaviors registered for the same tuple can read the context as well.
+//! ```rust
+//! use noon::context::Context;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! struct TraceId(u64);
+//! struct GetUser { id: i32 }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|(req, ctx): (GetUser, Context)| {
+//!         let trace = ctx.get::<TraceId>().map_or(0, |t| t.0);
+//!         format!("user {} (trace {})", req.id, trace)
+//!     })
+//!     .build();
+//!
+//! let ctx = Context::new().with(TraceId(7));
+//! assert_eq!(mediator.handle_with_context(GetUser { id: 5 }, &ctx), "user 5 (trace 7)");
+//! ```
+//!
+//! A long-lived mediator can be scoped to a request with [`crate::mediator::Mediate::scope`], which returns a [`Scoped`] mediator
+//! carrying the request's context. It dispatches everything to its parent, and passes its context to the handlers taking one with [`Scoped::handle_in_scope`] and friends,
+//! so request-scoped services don't need to be handed the context separately.
+//! ```rust
+//! use noon::context::Context;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! struct User(&'static str);
+//! struct Greet;
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|(_, ctx): (Greet, Context)| {
+//!         format!("hello, {}", ctx.get::<User>().map_or("stranger", |u| u.0))
+//!     })
+//!     .add_handler(|x: u32| x + 1)
+//!     .build();
+//!
+//! let request = mediator.scope(Context::new().with(User("ada")));
+//! assert_eq!(request.handle_in_scope(Greet), "hello, ada");
+//! assert_eq!(request.handle(41), 42);
+//! ```
+#[cfg(feature = "async")]
+use crate::entry::RequestResponseAsync;
+use crate::entry::{RequestResponse, TryRequestResponse};
+#[cfg(feature = "async")]
+use crate::flavor::BoxFuture;
+use crate::flavor::Flavor;
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A typed bag of request-scoped values.
+///
+/// Cloning a context is cheap, and inserting into a clone doesn't affect the original.
+#[derive(Clone, Default)]
+pub struct Context {
+    values: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `value`, replacing any value of the same type.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        Arc::make_mut(&mut self.values).insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Like [`Context::insert`], for building a context in a single expression.
+    pub fn with<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> bool {
+        Arc::make_mut(&mut self.values)
+            .remove(&TypeId::of::<T>())
+            .is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+/// A mediator scoped to a request, see [`crate::mediator::Mediate::scope`].
+///
+/// It implements [`Mediate`] by delegating to its parent, and dereferences to it.
+pub struct Scoped<'a, M: ?Sized> {
+    parent: &'a M,
+    ctx: Context,
+}
+
+impl<'a, M: ?Sized> Scoped<'a, M> {
+    pub(crate) fn new(parent: &'a M, ctx: Context) -> Self {
+        Self { parent, ctx }
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.ctx
+    }
+
+    pub fn context_mut(&mut self) -> &mut Context {
+        &mut self.ctx
+    }
+
+    /// Invokes the handler registered for `(TMsg, Context)` with this scope's context.
+    pub fn handle_in_scope<TMsg, TResp, I, S: Flavor>(&self, msg: TMsg) -> TResp
+    where
+        M: Mediate<S>,
+        M::Handlers: ContainsAt<RequestResponse<(TMsg, Context), TResp, S>, I>,
+    {
+        self.parent.handle_with_context(msg, &self.ctx)
+    }
+
+    /// Invokes the asynchronous handler registered for `(TMsg, Context)` with this scope's context.
+    #[cfg(feature = "async")]
+    pub fn handle_async_in_scope<TMsg: 'static, TResp: 'static, I, S: Flavor>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        M: Mediate<S>,
+        M::Handlers: ContainsAt<RequestResponseAsync<(TMsg, Context), TResp, S>, I>,
+    {
+        self.parent.handle_async_with_context(msg, &self.ctx)
+    }
+
+    /// Invokes the fallible handler registered for `(TMsg, Context)` with this scope's context.
+    pub fn try_handle_in_scope<TMsg, TResp, TErr, I, S: Flavor>(
+        &self,
+        msg: TMsg,
+    ) -> Result<TResp, TErr>
+    where
+        M: Mediate<S>,
+        M::Handlers: ContainsAt<TryRequestResponse<(TMsg, Context), TResp, TErr, S>, I>,
+    {
+        self.parent.try_handle_with_context(msg, &self.ctx)
+    }
+}
+
+impl<M: ?Sized> Clone for Scoped<'_, M> {
+    fn clone(&self) -> Self {
+        Self::new(self.parent, self.ctx.clone())
+    }
+}
+
+impl<M: ?Sized> Deref for Scoped<'_, M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        self.parent
+    }
+}
+
+impl<M: ?Sized> fmt::Debug for Scoped<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scoped").field("ctx", &self.ctx).finish()
+    }
+}
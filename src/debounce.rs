@@ -0,0 +1,97 @@
+//! Receivers for chatty notifications.
+//!
+//! A receiver registered with [`MediatorBuilder::add_throttled_notification_receiver`] runs at most once per interval,
+//! and skips the notifications sent in between. One registered with [`MediatorBuilder::add_debounced_notification_receiver`]
+//! only runs once notifications have stopped for a quiet period, with the last of them.
+//! Both suit expensive work triggered by frequent events, such as refreshing a view or recomputing an index.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use std::time::Duration;
+//!
+//! struct Resized;
+//!
+//! let layouts = Rc::new(Cell::new(0));
+//! let laid_out = layouts.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .listen_for::<Resized>()
+//!     .add_throttled_notification_receiver(Duration::from_secs(60), move |_: &Resized| {
+//!         laid_out.set(laid_out.get() + 1)
+//!     })
+//!     .build();
+//! for _ in 0..10 {
+//!     mediator.notify(&Resized);
+//! }
+//! assert_eq!(layouts.get(), 1);
+//! ```
+//!
+//! Debounced receivers are asynchronous, since they run after the notification: each notification's future waits out the quiet period,
+//! and only the future of the last one calls the receiver. Awaiting the notification waits for the quiet period too,
+//! so debounced notifications are typically sent with [`Mediate::notify_detached`].
+//!
+//! [`MediatorBuilder::add_throttled_notification_receiver`]: crate::mediator::MediatorBuilder::add_throttled_notification_receiver
+//! [`MediatorBuilder::add_debounced_notification_receiver`]: crate::mediator::MediatorBuilder::add_debounced_notification_receiver
+//! [`Mediate::notify_detached`]: crate::mediator::Mediate::notify_detached
+#[cfg(feature = "async")]
+use crate::time::Delay;
+
+#[cfg(feature = "async")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Lets a call through at most once per interval.
+pub(crate) struct Throttle {
+    interval: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl Throttle {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Whether a call may run now, in which case the interval restarts.
+    pub(crate) fn try_pass(&self) -> bool {
+        let mut last = self.last.lock().unwrap();
+        let now = Instant::now();
+        match *last {
+            Some(at) if now.duration_since(at) < self.interval => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Tracks the latest call, so calls superseded during their quiet period can be skipped.
+#[cfg(feature = "async")]
+pub(crate) struct Debounce {
+    quiet: Duration,
+    latest: AtomicU64,
+}
+
+#[cfg(feature = "async")]
+impl Debounce {
+    pub(crate) fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            latest: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts a call, and returns the quiet period to wait and the call's number to pass to [`Debounce::is_latest`].
+    pub(crate) fn start(&self) -> (Delay, u64) {
+        let call = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        (Delay::new(self.quiet), call)
+    }
+
+    pub(crate) fn is_latest(&self, call: u64) -> bool {
+        self.latest.load(Ordering::SeqCst) == call
+    }
+}
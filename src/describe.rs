@@ -0,0 +1,288 @@
+//! Describing the entries of a built mediator at runtime.
+//!
+//! [`Mediate::describe`] lists the types of messages a mediator has handlers for and listens for,
+//! with the number of receivers currently registered for each type of notification, for startup logging and ops tooling.
+//! Types are named by [`std::any::type_name`], so the names are meant for people rather than for parsing.
+//! ```rust
+//! use noon::describe::EntryKind;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|x: i32| x.to_string())
+//!     .listen_for::<str>()
+//!     .add_notification_receiver(|line: &str| println!("{}", line))
+//!     .build();
+//! let description = mediator.describe();
+//! assert_eq!(description.handlers[0].kind, EntryKind::Handler);
+//! assert_eq!(description.handlers[0].message, "i32");
+//! assert_eq!(description.receivers[0].receivers, Some(1));
+//! println!("{}", description);
+//! ```
+//!
+//! [`Mediate::describe`]: crate::mediator::Mediate::describe
+#[cfg(feature = "std")]
+use crate::entry::ReceiveNotificationBroadcast;
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationOwned, RequestResponse, RequestResponseFn,
+    TryReceiveNotification, TryRequestResponse,
+};
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+use crate::flavor::Flavor;
+use crate::hlist::{HVisit, Visitor};
+
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryKind {
+    Handler,
+    AsyncHandler,
+    TryHandler,
+    AsyncTryHandler,
+    StreamHandler,
+    Notification,
+    TryNotification,
+    OwnedNotification,
+    AsyncNotification,
+    SharedNotification,
+    BroadcastNotification,
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EntryKind::Handler => "handler",
+            EntryKind::AsyncHandler => "async handler",
+            EntryKind::TryHandler => "try handler",
+            EntryKind::AsyncTryHandler => "async try handler",
+            EntryKind::StreamHandler => "stream handler",
+            EntryKind::Notification => "notification",
+            EntryKind::TryNotification => "try notification",
+            EntryKind::OwnedNotification => "owned notification",
+            EntryKind::AsyncNotification => "async notification",
+            EntryKind::SharedNotification => "shared notification",
+            EntryKind::BroadcastNotification => "broadcast notification",
+        })
+    }
+}
+
+/// An entry of a mediator, see [`crate::describe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryDescription {
+    pub kind: EntryKind,
+    /// The type name of the message.
+    pub message: &'static str,
+    /// The type name of the response, for handlers, of the items, for stream handlers, or of the errors, for fallible notifications.
+    pub response: Option<&'static str>,
+    /// The number of receivers registered when the mediator was described, for notifications.
+    pub receivers: Option<usize>,
+}
+
+impl EntryDescription {
+    fn handler<TMsg: ?Sized, TResp: ?Sized>(kind: EntryKind) -> Self {
+        Self {
+            kind,
+            message: type_name::<TMsg>(),
+            response: Some(type_name::<TResp>()),
+            receivers: None,
+        }
+    }
+
+    fn notification<TMsg: ?Sized>(kind: EntryKind, receivers: usize) -> Self {
+        Self {
+            kind,
+            message: type_name::<TMsg>(),
+            response: None,
+            receivers: Some(receivers),
+        }
+    }
+}
+
+impl fmt::Display for EntryDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.message)?;
+        if let Some(response) = self.response {
+            write!(f, " -> {}", response)?;
+        }
+        if let Some(receivers) = self.receivers {
+            write!(f, " ({} receivers)", receivers)?;
+        }
+        Ok(())
+    }
+}
+
+/// The entries of a mediator, each sorted by the type name of their message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Description {
+    pub handlers: Vec<EntryDescription>,
+    pub receivers: Vec<EntryDescription>,
+}
+
+impl Description {
+    pub(crate) fn new<H: HVisit<Describer>, N: HVisit<Describer>>(
+        handlers: &H,
+        receivers: &N,
+    ) -> Self {
+        Self {
+            handlers: Describer::collect(handlers),
+            receivers: Describer::collect(receivers),
+        }
+    }
+}
+
+impl Description {
+    /// Formats the entries as the fields of a struct named `name`, for the `Debug` impls of mediators and builders.
+    pub(crate) fn debug_struct(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        f.debug_struct(name)
+            .field("handlers", &Entries(&self.handlers))
+            .field("receivers", &Entries(&self.receivers))
+            .finish()
+    }
+}
+
+/// Debug-formats entries by their `Display` representations.
+struct Entries<'a>(&'a [EntryDescription]);
+
+struct Entry<'a>(&'a EntryDescription);
+
+impl fmt::Debug for Entries<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter().map(Entry)).finish()
+    }
+}
+
+impl fmt::Debug for Entry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+/// One line per entry, handlers first.
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.handlers.iter().chain(&self.receivers) {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// An entry which can describe itself, implemented for every kind of entry.
+pub trait DescribeEntry {
+    fn describe(&self) -> EntryDescription;
+}
+
+/// Collects the descriptions of the entries of a list, see [`HVisit`].
+#[derive(Debug, Default)]
+pub struct Describer(Vec<EntryDescription>);
+
+impl Describer {
+    fn collect<L: HVisit<Self>>(list: &L) -> Vec<EntryDescription> {
+        let mut describer = Self::default();
+        list.accept(&mut describer);
+        describer
+            .0
+            .sort_by(|a, b| (a.message, a.kind).cmp(&(b.message, b.kind)));
+        describer.0
+    }
+}
+
+impl<T: DescribeEntry> Visitor<T> for Describer {
+    fn visit(&mut self, entry: &T) {
+        self.0.push(entry.describe());
+    }
+}
+
+impl<TMsg, TResp, S: Flavor> DescribeEntry for RequestResponse<TMsg, TResp, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, TResp>(EntryKind::Handler)
+    }
+}
+
+impl<TMsg, TResp, F> DescribeEntry for RequestResponseFn<TMsg, TResp, F> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, TResp>(EntryKind::Handler)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, S: Flavor> DescribeEntry for RequestResponseAsync<TMsg, TResp, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, TResp>(EntryKind::AsyncHandler)
+    }
+}
+
+impl<TMsg, TResp, TErr, S: Flavor> DescribeEntry for TryRequestResponse<TMsg, TResp, TErr, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, Result<TResp, TErr>>(EntryKind::TryHandler)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, TErr: 'static, S: Flavor> DescribeEntry
+    for TryRequestResponseAsync<TMsg, TResp, TErr, S>
+{
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, Result<TResp, TErr>>(EntryKind::AsyncTryHandler)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TItem, S: Flavor> DescribeEntry for RequestStream<TMsg, TItem, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::handler::<TMsg, TItem>(EntryKind::StreamHandler)
+    }
+}
+
+impl<TMsg: ?Sized, S: Flavor> DescribeEntry for ReceiveNotification<TMsg, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::notification::<TMsg>(EntryKind::Notification, self.receiver_count())
+    }
+}
+
+impl<TMsg: ?Sized, TErr, S: Flavor> DescribeEntry for TryReceiveNotification<TMsg, TErr, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription {
+            response: Some(type_name::<TErr>()),
+            ..EntryDescription::notification::<TMsg>(
+                EntryKind::TryNotification,
+                self.receiver_count(),
+            )
+        }
+    }
+}
+
+impl<TMsg, S: Flavor> DescribeEntry for ReceiveNotificationOwned<TMsg, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::notification::<TMsg>(EntryKind::OwnedNotification, self.receiver_count())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: Clone, S: Flavor> DescribeEntry for ReceiveNotificationAsync<TMsg, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::notification::<TMsg>(EntryKind::AsyncNotification, self.receiver_count())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: ?Sized, S: Flavor> DescribeEntry for ReceiveNotificationShared<TMsg, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::notification::<TMsg>(EntryKind::SharedNotification, self.receiver_count())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<TMsg: Clone, S: Flavor> DescribeEntry for ReceiveNotificationBroadcast<TMsg, S> {
+    fn describe(&self) -> EntryDescription {
+        EntryDescription::notification::<TMsg>(
+            EntryKind::BroadcastNotification,
+            self.receiver_count(),
+        )
+    }
+}
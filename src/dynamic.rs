@@ -0,0 +1,237 @@
+//! A mediator whose receivers are registered at runtime.
+//!
+//! Unlike a mediator built with [`crate::mediator::MediatorBuilder`], a [`DynamicMediator`] doesn't track its receivers in its type,
+//! so receivers can be added after it is created, for example by plugins. It stores the same [`crate::entry`] types, keyed by [`TypeId`].
+//! Dispatching a message without a registered receiver isn't a compile-time error, so [`DynamicMediator::handle`] returns an [`Option`].
+//! ```rust
+//! use noon::dynamic::DynamicMediator;
+//!
+//! let mut mediator = DynamicMediator::new();
+//! assert_eq!(mediator.handle::<i32, i32>(21), None);
+//!
+//! mediator.add_handler(|x: i32| x * 2);
+//! assert_eq!(mediator.handle::<i32, i32>(21), Some(42));
+//! ```
+//! A dynamic mediator implements [`DynMediate`], using the handler registered last for each type of message.
+//! Messages without a handler can be passed to a fallback, see [`DynamicMediator::set_fallback`].
+use crate::entry::{ReceiveNotification, RequestResponse, SubscriptionId};
+#[cfg(feature = "async")]
+use crate::entry::{ReceiveNotificationAsync, RequestResponseAsync};
+use crate::erased::{DynMediate, Fallback};
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, IntoAsyncHandler};
+use crate::flavor::{Flavor, IntoErased, IntoHandler, IntoReceiver, Local, Shared};
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+type HandleFn<S> = fn(&DynamicMediator<S>, Box<dyn Any>) -> Box<dyn Any>;
+type NotifyFn<S> = fn(&DynamicMediator<S>, &dyn Any);
+
+pub struct DynamicMediator<S: Flavor = Local> {
+    entries: HashMap<TypeId, Box<S::Erased>>,
+    handlers: HashMap<TypeId, HandleFn<S>>,
+    receivers: HashMap<TypeId, NotifyFn<S>>,
+    fallback: Fallback<S>,
+}
+
+impl DynamicMediator {
+    pub fn new() -> Self {
+        Self::empty()
+    }
+}
+
+impl Default for DynamicMediator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicMediator<Shared> {
+    /// Creates a thread-safe dynamic mediator, with the same bounds as [`crate::mediator::MediatorBuilder::new_shared`].
+    pub fn new_shared() -> Self {
+        Self::empty()
+    }
+}
+
+impl<S: Flavor> DynamicMediator<S> {
+    fn empty() -> Self {
+        Self {
+            entries: HashMap::new(),
+            handlers: HashMap::new(),
+            receivers: HashMap::new(),
+            fallback: Fallback::new(),
+        }
+    }
+
+    fn entry<T: 'static>(&self) -> Option<&T> {
+        self.entries
+            .get(&TypeId::of::<T>())
+            .and_then(|entry| S::downcast_ref(entry))
+    }
+
+    fn entry_or_default<T: Default + IntoErased<S> + 'static>(&mut self) -> &T {
+        let entry = self
+            .entries
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| T::default().into_erased());
+        S::downcast_ref(entry).expect("entries are keyed by their type")
+    }
+
+    /// Registers a handler, replacing any handler for the same message and response types.
+    pub fn add_handler<TMsg: 'static, TResp: 'static>(
+        &mut self,
+        f: impl IntoHandler<S, TMsg, TResp>,
+    ) -> &mut Self
+    where
+        RequestResponse<TMsg, TResp, S>: IntoErased<S>,
+    {
+        let entry = RequestResponse::<TMsg, TResp, S>::new(f);
+        self.entries.insert(
+            TypeId::of::<RequestResponse<TMsg, TResp, S>>(),
+            entry.into_erased(),
+        );
+        self.handlers.insert(TypeId::of::<TMsg>(), |mediator, msg| {
+            let msg = *msg
+                .downcast::<TMsg>()
+                .expect("handlers are keyed by message type");
+            Box::new(
+                mediator
+                    .handle::<TMsg, TResp>(msg)
+                    .expect("handler was registered"),
+            )
+        });
+        self
+    }
+
+    /// Registers an asynchronous handler, replacing any asynchronous handler for the same message and response types.
+    #[cfg(feature = "async")]
+    pub fn add_async_handler<TMsg: 'static, TResp: 'static>(
+        &mut self,
+        f: impl IntoAsyncHandler<S, TMsg, TResp>,
+    ) -> &mut Self
+    where
+        RequestResponseAsync<TMsg, TResp, S>: IntoErased<S>,
+    {
+        let entry = RequestResponseAsync::<TMsg, TResp, S>::new(f);
+        self.entries.insert(
+            TypeId::of::<RequestResponseAsync<TMsg, TResp, S>>(),
+            entry.into_erased(),
+        );
+        self
+    }
+
+    pub fn add_notification_receiver<TMsg: 'static>(
+        &mut self,
+        f: impl IntoReceiver<S, TMsg>,
+    ) -> SubscriptionId
+    where
+        ReceiveNotification<TMsg, S>: IntoErased<S>,
+    {
+        self.receivers
+            .insert(TypeId::of::<TMsg>(), |mediator, msg| {
+                let msg = msg
+                    .downcast_ref::<TMsg>()
+                    .expect("receivers are keyed by message type");
+                mediator.notify(msg)
+            });
+        self.entry_or_default::<ReceiveNotification<TMsg, S>>()
+            .add(f)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn add_async_notification_receiver<TMsg: Clone + 'static>(
+        &mut self,
+        f: impl IntoAsyncHandler<S, TMsg, ()>,
+    ) -> SubscriptionId
+    where
+        ReceiveNotificationAsync<TMsg, S>: IntoErased<S>,
+    {
+        self.entry_or_default::<ReceiveNotificationAsync<TMsg, S>>()
+            .add(f)
+    }
+
+    /// Registers a handler for messages dispatched through [`DynMediate::handle_any`] without a handler, replacing any previous fallback.
+    pub fn set_fallback(
+        &mut self,
+        f: impl IntoHandler<S, Box<dyn Any>, Box<dyn Any>>,
+    ) -> &mut Self {
+        self.fallback.set_handler(f);
+        self
+    }
+
+    /// Registers a receiver for notifications dispatched through [`DynMediate::notify_any`] without a receiver, replacing any previous fallback.
+    pub fn set_notify_fallback(&mut self, f: impl IntoReceiver<S, dyn Any>) -> &mut Self {
+        self.fallback.set_receiver(f);
+        self
+    }
+
+    /// Removes a notification receiver, returning whether it was registered.
+    pub fn unsubscribe<TMsg: 'static>(&self, id: SubscriptionId) -> bool {
+        self.entry::<ReceiveNotification<TMsg, S>>()
+            .is_some_and(|entry| entry.remove(id))
+    }
+
+    /// Removes an asynchronous notification receiver, returning whether it was registered.
+    #[cfg(feature = "async")]
+    pub fn unsubscribe_async<TMsg: Clone + 'static>(&self, id: SubscriptionId) -> bool {
+        self.entry::<ReceiveNotificationAsync<TMsg, S>>()
+            .is_some_and(|entry| entry.remove(id))
+    }
+
+    /// Invokes the handler for `TMsg` and `TResp`, or returns `None` if there isn't one.
+    pub fn handle<TMsg: 'static, TResp: 'static>(&self, msg: TMsg) -> Option<TResp> {
+        self.entry::<RequestResponse<TMsg, TResp, S>>()
+            .map(|entry| entry.call(msg))
+    }
+
+    /// Invokes the asynchronous handler for `TMsg` and `TResp`, or returns `None` if there isn't one.
+    #[cfg(feature = "async")]
+    pub fn handle_async<TMsg: 'static, TResp: 'static>(
+        &self,
+        msg: TMsg,
+    ) -> Option<BoxFuture<'static, TResp, S>> {
+        self.entry::<RequestResponseAsync<TMsg, TResp, S>>()
+            .map(|entry| entry.call(msg))
+    }
+
+    pub fn notify<TMsg: 'static>(&self, msg: &TMsg) {
+        if let Some(entry) = self.entry::<ReceiveNotification<TMsg, S>>() {
+            entry.call(msg);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn notify_async<TMsg: Clone + 'static>(&self, msg: TMsg) -> BoxFuture<'_, (), S> {
+        match self.entry::<ReceiveNotificationAsync<TMsg, S>>() {
+            Some(entry) => entry.call(msg),
+            None => S::sequence(vec![]),
+        }
+    }
+}
+
+impl<S: Flavor> DynMediate for DynamicMediator<S> {
+    fn handle_any(&self, msg: Box<dyn Any>) -> Result<Box<dyn Any>, Box<dyn Any>> {
+        match self.handlers.get(&(*msg).type_id()) {
+            Some(handle) => Ok(handle(self, msg)),
+            None => self.fallback.handle(msg),
+        }
+    }
+
+    fn notify_any(&self, msg: &dyn Any) -> bool {
+        match self.receivers.get(&msg.type_id()) {
+            Some(notify) => {
+                notify(self, msg);
+                true
+            }
+            None => {
+                self.fallback.notify(msg);
+                false
+            }
+        }
+    }
+
+    fn can_handle(&self, msg: TypeId) -> bool {
+        self.handlers.contains_key(&msg)
+    }
+}
@@ -1,88 +1,739 @@
-use std::future::Future;
-use std::pin::Pin;
+#[cfg(feature = "std")]
+use crate::broadcast::{self, BroadcastReceiver};
+#[cfg(feature = "async")]
+use crate::flavor::{
+    AsyncCallback, BoxFuture, BoxStream, IntoAsyncBehavior, IntoAsyncHandler, IntoStreamHandler,
+};
+use crate::flavor::{
+    Flavor, IntoBehavior, IntoErased, IntoHandler, IntoReceiver, IntoTryReceiver, Local, Shared,
+};
+use crate::lock::Mutex;
+#[cfg(feature = "async")]
+use crate::pipeline::{AsyncPipeline, NextAsync};
 
-pub struct RequestResponse<TMsg, TResp> {
-    cb: Box<dyn Fn(TMsg) -> TResp>,
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::any::Any;
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use std::panic::{self, AssertUnwindSafe};
+
+pub struct RequestResponse<TMsg, TResp, S: Flavor = Local> {
+    cb: Arc<S::Handler<TMsg, TResp>>,
+    behaviors: Vec<Arc<S::Behavior<TMsg, TResp>>>,
+}
+
+impl<TMsg, TResp, S: Flavor> Clone for RequestResponse<TMsg, TResp, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cb: Arc::clone(&self.cb),
+            behaviors: self.behaviors.clone(),
+        }
+    }
 }
 
-impl<F, TMsg, TResp> From<F> for RequestResponse<TMsg, TResp>
+impl<F, TMsg, TResp> From<F> for RequestResponse<TMsg, TResp, Local>
 where
     F: Fn(TMsg) -> TResp + 'static,
 {
     fn from(f: F) -> Self {
-        Self { cb: Box::new(f) }
+        Self::new(f)
+    }
+}
+
+impl<F, TMsg, TResp> From<F> for RequestResponse<TMsg, TResp, Shared>
+where
+    F: Fn(TMsg) -> TResp + Send + Sync + 'static,
+{
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+impl<TMsg, TResp, S: Flavor> RequestResponse<TMsg, TResp, S> {
+    pub fn new(f: impl IntoHandler<S, TMsg, TResp>) -> Self {
+        Self {
+            cb: f.into_handler().into(),
+            behaviors: vec![],
+        }
+    }
+
+    pub(crate) fn add_behavior(&mut self, f: impl IntoBehavior<S, TMsg, TResp>) {
+        self.behaviors.push(f.into_behavior().into())
+    }
+
+    /// Swaps the handler, keeping the pipeline behaviors.
+    pub(crate) fn with_handler(self, f: impl IntoHandler<S, TMsg, TResp>) -> Self {
+        Self {
+            cb: f.into_handler().into(),
+            behaviors: self.behaviors,
+        }
+    }
+
+    pub fn call(&self, msg: TMsg) -> TResp {
+        self.call_from(0, msg)
+    }
+
+    fn call_from(&self, index: usize, msg: TMsg) -> TResp {
+        match self.behaviors.get(index) {
+            Some(behavior) => behavior(msg, &|msg| self.call_from(index + 1, msg)),
+            None => (self.cb)(msg),
+        }
+    }
+}
+
+/// A handler stored without boxing, so calling it is statically dispatched.
+///
+/// The type of the handler is part of the entry's type, see [`crate::mediator::MediatorBuilder::add_handler_unboxed`].
+pub struct RequestResponseFn<TMsg, TResp, F> {
+    cb: F,
+    _marker: PhantomData<fn(TMsg) -> TResp>,
+}
+
+impl<TMsg, TResp, F: Clone> Clone for RequestResponseFn<TMsg, TResp, F> {
+    fn clone(&self) -> Self {
+        Self {
+            cb: self.cb.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<TMsg, TResp> RequestResponse<TMsg, TResp> {
+impl<TMsg, TResp, F: Fn(TMsg) -> TResp> RequestResponseFn<TMsg, TResp, F> {
+    pub fn new(f: F) -> Self {
+        Self {
+            cb: f,
+            _marker: PhantomData,
+        }
+    }
+
     pub fn call(&self, msg: TMsg) -> TResp {
         (self.cb)(msg)
     }
 }
 
-pub struct RequestResponseAsync<TMsg, TResp> {
-    cb: Box<dyn Fn(TMsg) -> Pin<Box<dyn Future<Output = TResp>>>>,
+#[cfg(feature = "async")]
+pub struct RequestResponseAsync<TMsg, TResp: 'static, S: Flavor = Local> {
+    pipeline: Arc<AsyncPipeline<TMsg, TResp, S>>,
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, S: Flavor> Clone for RequestResponseAsync<TMsg, TResp, S> {
+    fn clone(&self) -> Self {
+        Self {
+            pipeline: Arc::clone(&self.pipeline),
+        }
+    }
 }
 
-impl<F, Fut, TMsg, TResp> From<F> for RequestResponseAsync<TMsg, TResp>
+#[cfg(feature = "async")]
+impl<F, Fut, TMsg, TResp: 'static> From<F> for RequestResponseAsync<TMsg, TResp, Local>
 where
     Fut: Future<Output = TResp> + 'static,
     F: (Fn(TMsg) -> Fut) + 'static,
 {
     fn from(f: F) -> Self {
-        let f = move |msg| Box::pin(f(msg)) as _;
-        Self { cb: Box::new(f) }
+        Self::new(f)
     }
 }
 
-impl<TMsg, TResp> RequestResponseAsync<TMsg, TResp> {
-    pub fn call(&self, msg: TMsg) -> impl Future<Output = TResp> {
+#[cfg(feature = "async")]
+impl<F, Fut, TMsg, TResp: 'static> From<F> for RequestResponseAsync<TMsg, TResp, Shared>
+where
+    Fut: Future<Output = TResp> + Send + 'static,
+    F: (Fn(TMsg) -> Fut) + Send + Sync + 'static,
+{
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, S: Flavor> RequestResponseAsync<TMsg, TResp, S> {
+    pub fn new(f: impl IntoAsyncHandler<S, TMsg, TResp>) -> Self {
+        let pipeline = AsyncPipeline {
+            handler: f.into_async_handler().into(),
+            behaviors: vec![],
+        };
+        Self {
+            pipeline: Arc::new(pipeline),
+        }
+    }
+
+    /// Adds a behavior to this entry's pipeline, copying the pipeline if it's shared with other entries,
+    /// such as those of a parent builder.
+    pub(crate) fn add_behavior(&mut self, f: impl IntoAsyncBehavior<S, TMsg, TResp>) {
+        Arc::make_mut(&mut self.pipeline)
+            .behaviors
+            .push(f.into_async_behavior().into())
+    }
+
+    pub fn call(&self, msg: TMsg) -> BoxFuture<'static, TResp, S> {
+        NextAsync::new(Arc::clone(&self.pipeline)).call(msg)
+    }
+}
+
+pub struct TryRequestResponse<TMsg, TResp, TErr, S: Flavor = Local> {
+    inner: RequestResponse<TMsg, Result<TResp, TErr>, S>,
+}
+
+impl<TMsg, TResp, TErr, S: Flavor> Clone for TryRequestResponse<TMsg, TResp, TErr, S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<TMsg, TResp, TErr, S: Flavor> TryRequestResponse<TMsg, TResp, TErr, S> {
+    pub fn new(f: impl IntoHandler<S, TMsg, Result<TResp, TErr>>) -> Self {
+        Self {
+            inner: RequestResponse::new(f),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn add_behavior(&mut self, f: impl IntoBehavior<S, TMsg, Result<TResp, TErr>>) {
+        self.inner.add_behavior(f)
+    }
+
+    pub fn call(&self, msg: TMsg) -> Result<TResp, TErr> {
+        self.inner.call(msg)
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct TryRequestResponseAsync<TMsg, TResp: 'static, TErr: 'static, S: Flavor = Local> {
+    inner: RequestResponseAsync<TMsg, Result<TResp, TErr>, S>,
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, TErr: 'static, S: Flavor> Clone
+    for TryRequestResponseAsync<TMsg, TResp, TErr, S>
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TResp: 'static, TErr: 'static, S: Flavor> TryRequestResponseAsync<TMsg, TResp, TErr, S> {
+    pub fn new(f: impl IntoAsyncHandler<S, TMsg, Result<TResp, TErr>>) -> Self {
+        Self {
+            inner: RequestResponseAsync::new(f),
+        }
+    }
+
+    pub fn call(&self, msg: TMsg) -> BoxFuture<'static, Result<TResp, TErr>, S> {
+        self.inner.call(msg)
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct RequestStream<TMsg, TItem, S: Flavor = Local> {
+    cb: Arc<S::Handler<TMsg, BoxStream<TItem, S>>>,
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TItem, S: Flavor> Clone for RequestStream<TMsg, TItem, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cb: Arc::clone(&self.cb),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg, TItem, S: Flavor> RequestStream<TMsg, TItem, S> {
+    pub fn new(f: impl IntoStreamHandler<S, TMsg, TItem>) -> Self {
+        Self {
+            cb: f.into_stream_handler().into(),
+        }
+    }
+
+    pub fn call(&self, msg: TMsg) -> BoxStream<TItem, S> {
         (self.cb)(msg)
     }
 }
 
-pub struct ReceiveNotification<TMsg: ?Sized> {
-    cbs: Vec<Box<dyn Fn(&TMsg)>>,
+/// Identifies a notification receiver, so it can be removed later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+impl SubscriptionId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// The receivers for a type of message.
+///
+/// Receivers are called in the order they were added. They can be added and removed while a notification is in flight,
+/// which only affects subsequent notifications.
+///
+/// Clones share the same receivers, so a receiver added through one clone is called by all of them.
+struct Subscribers<T: ?Sized> {
+    /// Replaced rather than mutated when a receiver is added or removed, so notifying only clones the [`Arc`],
+    /// and receivers can add and remove receivers while being called.
+    cbs: Arc<Mutex<Receivers<T>>>,
 }
 
-impl<TMsg: ?Sized> ReceiveNotification<TMsg> {
+/// The receivers registered when a notification was sent.
+type Receivers<T> = Arc<[(SubscriptionId, Arc<T>)]>;
+
+impl<T: ?Sized> Clone for Subscribers<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cbs: Arc::clone(&self.cbs),
+        }
+    }
+}
+
+impl<T: ?Sized> Subscribers<T> {
+    fn new() -> Self {
+        Self {
+            cbs: Arc::new(Mutex::new(Arc::new([]))),
+        }
+    }
+
+    fn add(&self, cb: Box<T>) -> SubscriptionId {
+        self.add_shared(cb.into())
+    }
+
+    fn add_shared(&self, cb: Arc<T>) -> SubscriptionId {
+        let id = SubscriptionId::next();
+        let mut cbs = self.cbs.lock();
+        *cbs = cbs.iter().cloned().chain([(id, cb)]).collect();
+        id
+    }
+
+    fn remove(&self, id: SubscriptionId) -> bool {
+        let mut cbs = self.cbs.lock();
+        if !cbs.iter().any(|(cb_id, _)| *cb_id == id) {
+            return false;
+        }
+        *cbs = cbs
+            .iter()
+            .filter(|(cb_id, _)| *cb_id != id)
+            .cloned()
+            .collect();
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.cbs.lock().len()
+    }
+
+    fn snapshot(&self) -> Receivers<T> {
+        Arc::clone(&self.cbs.lock())
+    }
+}
+
+/// The last notifications of a type, replayed to the receivers added later.
+struct Replay<TMsg: ?Sized, S: Flavor> {
+    capacity: usize,
+    /// Locked while the receivers are listed for a notification, so each receiver gets each message once, live or replayed.
+    messages: Mutex<VecDeque<Arc<S::Erased>>>,
+    erase: fn(&TMsg) -> Box<S::Erased>,
+    restore: fn(&S::Erased) -> &TMsg,
+}
+
+pub struct ReceiveNotification<TMsg: ?Sized, S: Flavor = Local> {
+    cbs: Subscribers<S::Receiver<TMsg>>,
+    replay: Option<Arc<Replay<TMsg, S>>>,
+}
+
+impl<TMsg: ?Sized, S: Flavor> ReceiveNotification<TMsg, S> {
     pub fn new() -> Self {
-        Self { cbs: vec![] }
+        Self {
+            cbs: Subscribers::new(),
+            replay: None,
+        }
+    }
+
+    /// Keeps copies of the last `capacity` notifications, which are sent to each receiver as it's added.
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn with_replay(capacity: usize) -> Self
+    where
+        TMsg: Clone + IntoErased<S> + 'static,
+    {
+        fn erase<TMsg: Clone + IntoErased<S>, S: Flavor>(msg: &TMsg) -> Box<S::Erased> {
+            msg.clone().into_erased()
+        }
+        fn restore<TMsg: 'static, S: Flavor>(erased: &S::Erased) -> &TMsg {
+            S::downcast_ref(erased).expect("replayed a message of another type")
+        }
+        assert!(capacity > 0, "replay capacity must be positive");
+        Self {
+            cbs: Subscribers::new(),
+            replay: Some(Arc::new(Replay {
+                capacity,
+                messages: Mutex::new(VecDeque::with_capacity(capacity)),
+                erase: erase::<TMsg, S>,
+                restore: restore::<TMsg, S>,
+            })),
+        }
+    }
+
+    /// The number of receivers currently registered.
+    pub fn receiver_count(&self) -> usize {
+        self.cbs.len()
     }
 
-    pub fn add(&mut self, f: impl Fn(&TMsg) + 'static) {
-        let cb = Box::new(f);
-        self.cbs.push(cb)
+    pub fn add(&self, f: impl IntoReceiver<S, TMsg>) -> SubscriptionId {
+        let Some(replay) = &self.replay else {
+            return self.cbs.add(f.into_receiver());
+        };
+        let cb: Arc<S::Receiver<TMsg>> = f.into_receiver().into();
+        let (id, replayed) = {
+            let messages = replay.messages.lock();
+            let id = self.cbs.add_shared(Arc::clone(&cb));
+            (id, messages.iter().cloned().collect::<Vec<_>>())
+        };
+        for msg in replayed {
+            cb((replay.restore)(&msg));
+        }
+        id
+    }
+
+    /// Removes a receiver, returning whether it was registered.
+    pub fn remove(&self, id: SubscriptionId) -> bool {
+        self.cbs.remove(id)
     }
 
     pub fn call(&self, msg: &TMsg) {
-        for cb in &self.cbs {
+        for (_, cb) in self.receivers_for(msg).iter() {
             cb(msg);
         }
     }
+
+    /// The receivers to call with `msg`, after recording it for replay.
+    fn receivers_for(&self, msg: &TMsg) -> Receivers<S::Receiver<TMsg>> {
+        let Some(replay) = &self.replay else {
+            return self.cbs.snapshot();
+        };
+        let mut messages = replay.messages.lock();
+        if messages.len() == replay.capacity {
+            messages.pop_front();
+        }
+        messages.push_back((replay.erase)(msg).into());
+        self.cbs.snapshot()
+    }
+
+    /// Like [`ReceiveNotification::call`], but catches a panic from each receiver and passes it to `on_panic`,
+    /// so the remaining receivers still run.
+    #[cfg(feature = "std")]
+    pub fn call_isolated(&self, msg: &TMsg, on_panic: impl Fn(Box<dyn Any + Send>)) {
+        for (_, cb) in self.receivers_for(msg).iter() {
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| cb(msg))) {
+                on_panic(payload);
+            }
+        }
+    }
 }
 
-pub struct ReceiveNotificationAsync<TMsg: ?Sized> {
-    cbs: Vec<Box<dyn Fn(TMsg) -> Pin<Box<dyn Future<Output = ()>>>>>,
+/// Clones share the same receivers.
+impl<TMsg: ?Sized, S: Flavor> Clone for ReceiveNotification<TMsg, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cbs: self.cbs.clone(),
+            replay: self.replay.clone(),
+        }
+    }
 }
 
-impl<TMsg: Clone> ReceiveNotificationAsync<TMsg> {
+impl<TMsg: ?Sized, S: Flavor> Default for ReceiveNotification<TMsg, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Notification receivers which can fail.
+pub struct TryReceiveNotification<TMsg: ?Sized, TErr, S: Flavor = Local> {
+    cbs: Subscribers<S::TryReceiver<TMsg, TErr>>,
+}
+
+impl<TMsg: ?Sized, TErr, S: Flavor> TryReceiveNotification<TMsg, TErr, S> {
     pub fn new() -> Self {
-        Self { cbs: vec![] }
+        Self {
+            cbs: Subscribers::new(),
+        }
     }
 
-    pub fn add<F, Fut>(&mut self, f: F)
-    where
-        Fut: Future<Output = ()> + 'static,
-        F: Fn(TMsg) -> Fut + 'static,
-    {
-        let f = move |msg| Box::pin(f(msg)) as _;
-        self.cbs.push(Box::new(f));
+    /// The number of receivers currently registered.
+    pub fn receiver_count(&self) -> usize {
+        self.cbs.len()
+    }
+
+    pub fn add(&self, f: impl IntoTryReceiver<S, TMsg, TErr>) -> SubscriptionId {
+        self.cbs.add(f.into_try_receiver())
+    }
+
+    /// Removes a receiver, returning whether it was registered.
+    pub fn remove(&self, id: SubscriptionId) -> bool {
+        self.cbs.remove(id)
+    }
+
+    /// Calls every receiver, returning the errors of those which failed.
+    pub fn call(&self, msg: &TMsg) -> Vec<TErr> {
+        self.cbs
+            .snapshot()
+            .iter()
+            .filter_map(|(_, cb)| cb(msg).err())
+            .collect()
+    }
+
+    /// Calls the receivers in order until one fails, returning its error.
+    pub fn call_each(&self, msg: &TMsg) -> Result<(), TErr> {
+        self.cbs.snapshot().iter().try_for_each(|(_, cb)| cb(msg))
     }
+}
+
+/// Clones share the same receivers.
+impl<TMsg: ?Sized, TErr, S: Flavor> Clone for TryReceiveNotification<TMsg, TErr, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cbs: self.cbs.clone(),
+        }
+    }
+}
+
+impl<TMsg: ?Sized, TErr, S: Flavor> Default for TryReceiveNotification<TMsg, TErr, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Notification receivers which take ownership of the message.
+///
+/// Observers receive a reference to the message, and are called first, in the order they were added.
+/// The consumer, if there is one, is then given the message itself, so notifying doesn't require `TMsg: Clone`.
+pub struct ReceiveNotificationOwned<TMsg, S: Flavor = Local> {
+    observers: Subscribers<S::Receiver<TMsg>>,
+    consumer: Option<Arc<S::Handler<TMsg, ()>>>,
+}
+
+impl<TMsg, S: Flavor> ReceiveNotificationOwned<TMsg, S> {
+    pub fn new() -> Self {
+        Self {
+            observers: Subscribers::new(),
+            consumer: None,
+        }
+    }
+
+    pub fn add_observer(&self, f: impl IntoReceiver<S, TMsg>) -> SubscriptionId {
+        self.observers.add(f.into_receiver())
+    }
+
+    /// Removes an observer, returning whether it was registered.
+    pub fn remove_observer(&self, id: SubscriptionId) -> bool {
+        self.observers.remove(id)
+    }
+
+    /// The number of observers currently registered, plus one if there is a consumer.
+    pub fn receiver_count(&self) -> usize {
+        self.observers.len() + usize::from(self.consumer.is_some())
+    }
+
+    /// Sets the consumer, replacing any previous one.
+    pub fn set_consumer(&mut self, f: impl IntoHandler<S, TMsg, ()>) {
+        self.consumer = Some(f.into_handler().into());
+    }
+
+    pub fn call(&self, msg: TMsg) {
+        for (_, observer) in self.observers.snapshot().iter() {
+            observer(&msg);
+        }
+        if let Some(consumer) = &self.consumer {
+            consumer(msg);
+        }
+    }
+}
+
+/// Clones share the same observers.
+impl<TMsg, S: Flavor> Clone for ReceiveNotificationOwned<TMsg, S> {
+    fn clone(&self) -> Self {
+        Self {
+            observers: self.observers.clone(),
+            consumer: self.consumer.clone(),
+        }
+    }
+}
+
+impl<TMsg, S: Flavor> Default for ReceiveNotificationOwned<TMsg, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+pub struct ReceiveNotificationAsync<TMsg, S: Flavor = Local> {
+    cbs: Subscribers<AsyncCallback<TMsg, (), S>>,
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: Clone, S: Flavor> ReceiveNotificationAsync<TMsg, S> {
+    pub fn new() -> Self {
+        Self {
+            cbs: Subscribers::new(),
+        }
+    }
+
+    /// The number of receivers currently registered.
+    pub fn receiver_count(&self) -> usize {
+        self.cbs.len()
+    }
+
+    pub fn add(&self, f: impl IntoAsyncHandler<S, TMsg, ()>) -> SubscriptionId {
+        self.cbs.add(f.into_async_handler())
+    }
+
+    /// Removes a receiver, returning whether it was registered.
+    pub fn remove(&self, id: SubscriptionId) -> bool {
+        self.cbs.remove(id)
+    }
+
+    pub fn call(&self, msg: TMsg) -> BoxFuture<'_, (), S> {
+        S::sequence(self.futures(msg))
+    }
+
+    /// The future of each receiver, without awaiting them.
+    pub fn futures(&self, msg: TMsg) -> Vec<BoxFuture<'static, (), S>> {
+        self.cbs
+            .snapshot()
+            .iter()
+            .map(|(_, cb)| cb(msg.clone()))
+            .collect()
+    }
+}
+
+/// Clones share the same receivers.
+#[cfg(feature = "async")]
+impl<TMsg, S: Flavor> Clone for ReceiveNotificationAsync<TMsg, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cbs: self.cbs.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: Clone, S: Flavor> Default for ReceiveNotificationAsync<TMsg, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Asynchronous notification receivers which share a single copy of the message.
+///
+/// Each receiver is given an [`Arc`] of the message, so `TMsg` needn't be `Clone`, and can be unsized.
+#[cfg(feature = "async")]
+pub struct ReceiveNotificationShared<TMsg: ?Sized, S: Flavor = Local> {
+    cbs: Subscribers<AsyncCallback<Arc<TMsg>, (), S>>,
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: ?Sized, S: Flavor> ReceiveNotificationShared<TMsg, S> {
+    pub fn new() -> Self {
+        Self {
+            cbs: Subscribers::new(),
+        }
+    }
+
+    /// The number of receivers currently registered.
+    pub fn receiver_count(&self) -> usize {
+        self.cbs.len()
+    }
+
+    pub fn add(&self, f: impl IntoAsyncHandler<S, Arc<TMsg>, ()>) -> SubscriptionId {
+        self.cbs.add(f.into_async_handler())
+    }
+
+    /// Removes a receiver, returning whether it was registered.
+    pub fn remove(&self, id: SubscriptionId) -> bool {
+        self.cbs.remove(id)
+    }
+
+    pub fn call(&self, msg: Arc<TMsg>) -> BoxFuture<'_, (), S> {
+        let futures = self
+            .cbs
+            .snapshot()
+            .iter()
+            .map(|(_, cb)| cb(Arc::clone(&msg)))
+            .collect();
+        S::sequence(futures)
+    }
+}
+
+/// Clones share the same receivers.
+#[cfg(feature = "async")]
+impl<TMsg: ?Sized, S: Flavor> Clone for ReceiveNotificationShared<TMsg, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cbs: self.cbs.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TMsg: ?Sized, S: Flavor> Default for ReceiveNotificationShared<TMsg, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Notifications sent to a bounded channel, which any number of receivers can subscribe to at runtime, see [`crate::broadcast`].
+#[cfg(feature = "std")]
+pub struct ReceiveNotificationBroadcast<TMsg, S: Flavor = Local> {
+    sender: Arc<broadcast::Sender<TMsg>>,
+    _flavor: PhantomData<S>,
+}
+
+#[cfg(feature = "std")]
+impl<TMsg: Clone, S: Flavor> ReceiveNotificationBroadcast<TMsg, S> {
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::Sender::new(capacity),
+            _flavor: PhantomData,
+        }
+    }
+
+    /// The number of receivers currently subscribed.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    pub fn subscribe(&self) -> BroadcastReceiver<TMsg> {
+        self.sender.subscribe()
+    }
+
+    /// Sends `msg` to every receiver, returning how many there are.
+    pub fn call(&self, msg: TMsg) -> usize {
+        self.sender.send(msg)
+    }
+}
 
-    pub async fn call(&self, msg: TMsg) {
-        for cb in &self.cbs {
-            cb(msg.clone()).await;
+/// Clones share the same channel.
+#[cfg(feature = "std")]
+impl<TMsg, S: Flavor> Clone for ReceiveNotificationBroadcast<TMsg, S> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: Arc::clone(&self.sender),
+            _flavor: PhantomData,
         }
     }
 }
@@ -0,0 +1,119 @@
+//! Messages carrying correlation metadata.
+//!
+//! An [`Envelope`] wraps a message along with [`Metadata`] identifying it, the dispatch it's part of, and the message which caused it.
+//! Handlers opt into receiving the metadata by taking an `Envelope<TMsg>`, and are invoked through [`Mediate::handle_enveloped`],
+//! which starts a new correlation, or by dispatching an envelope created with [`Envelope::child`] from a handler,
+//! which propagates the correlation to the nested dispatch.
+//! ```rust
+//! use noon::envelope::Envelope;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! struct PlaceOrder(u32);
+//! struct ReserveStock(u32);
+//!
+//! let stock = MediatorBuilder::new()
+//!     .add_handler(|req: Envelope<ReserveStock>| req.metadata.correlation_id)
+//!     .build();
+//! let orders = MediatorBuilder::new()
+//!     .add_handler(move |req: Envelope<PlaceOrder>| {
+//!         let reserved = stock.handle(req.child(ReserveStock(req.message.0)));
+//!         assert_eq!(reserved, req.metadata.correlation_id);
+//!         req.metadata.id
+//!     })
+//!     .build();
+//! let id = orders.handle_enveloped(PlaceOrder(3));
+//! ```
+//!
+//! [`Mediate::handle_enveloped`]: crate::mediator::Mediate::handle_enveloped
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+/// Identifies a message, unique within the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MessageId(pub u64);
+
+impl MessageId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Identifies the messages dispatched on behalf of the same original message.
+///
+/// A new correlation is identified by its first message's id, unless it is given, for example by an incoming request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorrelationId(pub u64);
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub id: MessageId,
+    pub correlation_id: CorrelationId,
+    /// The message whose handler dispatched this one, if any.
+    pub causation_id: Option<MessageId>,
+    /// When the envelope was created.
+    pub timestamp: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct Envelope<TMsg> {
+    pub message: TMsg,
+    pub metadata: Metadata,
+}
+
+impl<TMsg> Envelope<TMsg> {
+    /// Wraps `message`, starting a new correlation.
+    pub fn new(message: TMsg) -> Self {
+        let id = MessageId::next();
+        Self::with_metadata(message, id, CorrelationId(id.0), None)
+    }
+
+    /// Wraps `message` as part of an existing correlation.
+    pub fn correlated(message: TMsg, correlation_id: CorrelationId) -> Self {
+        Self::with_metadata(message, MessageId::next(), correlation_id, None)
+    }
+
+    /// Wraps `message` as caused by this envelope's message, with the same correlation.
+    pub fn child<U>(&self, message: U) -> Envelope<U> {
+        Envelope::with_metadata(
+            message,
+            MessageId::next(),
+            self.metadata.correlation_id,
+            Some(self.metadata.id),
+        )
+    }
+
+    pub fn into_inner(self) -> TMsg {
+        self.message
+    }
+
+    fn with_metadata(
+        message: TMsg,
+        id: MessageId,
+        correlation_id: CorrelationId,
+        causation_id: Option<MessageId>,
+    ) -> Self {
+        Self {
+            message,
+            metadata: Metadata {
+                id,
+                correlation_id,
+                causation_id,
+                timestamp: SystemTime::now(),
+            },
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Object-safe access to a mediator.
+//!
+//! [`Mediate`] can't be used as a trait object, since its methods are generic over the types of messages.
+//! [`DynMediate`] dispatches type-erased messages instead, for plugin-style code that stores mediators as `Box<dyn DynMediate>`.
+//! An [`Erased`] mediator is created from any mediator whose receivers are stored in lists, see [`Erased::new`].
+//!
+//! Only synchronous handlers and notification receivers for `'static`, sized types of messages can be reached through [`DynMediate`].
+//! Other kinds of receivers are skipped.
+//!
+//! Messages of other types are returned to the caller, unless a fallback is registered with [`Erased::with_fallback`],
+//! for example to log unknown messages decoded from a wire format.
+//! ```rust
+//! use noon::erased::{DynMediate, Erased};
+//! use noon::mediator::MediatorBuilder;
+//! use std::any::Any;
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|x: i32| x * 2)
+//!     .build();
+//! let erased: Box<dyn DynMediate> = Box::new(Erased::new(mediator));
+//!
+//! let resp = erased.handle_any(Box::new(21i32)).unwrap();
+//! assert_eq!(*resp.downcast::<i32>().unwrap(), 42);
+//! assert!(erased.handle_any(Box::new("unknown")).is_err());
+//! ```
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationBroadcast, ReceiveNotificationOwned, RequestResponse,
+    RequestResponseFn, TryReceiveNotification, TryRequestResponse,
+};
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+use crate::flavor::{Flavor, IntoHandler, IntoReceiver, Local};
+use crate::hlist::{Cons, ContainsAt, Nil, Succ, Z};
+use crate::mediator::Mediate;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+pub trait DynMediate {
+    /// Invokes the handler for the type of `msg`, returning its response.
+    ///
+    /// If there is no handler for its type, the fallback is invoked instead, or `msg` is returned back if there isn't one.
+    fn handle_any(&self, msg: Box<dyn Any>) -> Result<Box<dyn Any>, Box<dyn Any>>;
+
+    /// Notifies the receivers for the type of `msg`, returning whether there were any.
+    ///
+    /// If there weren't, the notification fallback is invoked, if there is one.
+    fn notify_any(&self, msg: &dyn Any) -> bool;
+
+    /// Whether a handler is registered for the type of message `msg`.
+    fn can_handle(&self, msg: TypeId) -> bool;
+}
+
+type HandleFn<M> = fn(&M, Box<dyn Any>) -> Box<dyn Any>;
+type NotifyFn<M> = fn(&M, &dyn Any);
+
+/// A handler for any message, responding with any value.
+type AnyHandler<S> = <S as Flavor>::Handler<Box<dyn Any>, Box<dyn Any>>;
+
+/// Catch-all receivers for messages without a registered receiver.
+pub(crate) struct Fallback<S: Flavor> {
+    handler: Option<Box<AnyHandler<S>>>,
+    receiver: Option<Box<S::Receiver<dyn Any>>>,
+}
+
+impl<S: Flavor> Fallback<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            handler: None,
+            receiver: None,
+        }
+    }
+
+    pub(crate) fn set_handler(&mut self, f: impl IntoHandler<S, Box<dyn Any>, Box<dyn Any>>) {
+        self.handler = Some(f.into_handler());
+    }
+
+    pub(crate) fn set_receiver(&mut self, f: impl IntoReceiver<S, dyn Any>) {
+        self.receiver = Some(f.into_receiver());
+    }
+
+    pub(crate) fn handle(&self, msg: Box<dyn Any>) -> Result<Box<dyn Any>, Box<dyn Any>> {
+        match &self.handler {
+            Some(handler) => Ok(handler(msg)),
+            None => Err(msg),
+        }
+    }
+
+    pub(crate) fn notify(&self, msg: &dyn Any) {
+        if let Some(receiver) = &self.receiver {
+            receiver(msg);
+        }
+    }
+}
+
+/// A mediator implementing [`DynMediate`].
+pub struct Erased<M, S: Flavor = Local> {
+    mediator: M,
+    handlers: HashMap<TypeId, HandleFn<M>>,
+    receivers: HashMap<TypeId, NotifyFn<M>>,
+    fallback: Fallback<S>,
+}
+
+impl<M: Mediate<S>, S: Flavor> Erased<M, S> {
+    /// Erases `mediator`.
+    ///
+    /// If several handlers are registered for the same type of message, the one registered last is used.
+    pub fn new(mediator: M) -> Self
+    where
+        M::Handlers: EraseHandlers<M, S, Z>,
+        M::NotifyReceivers: EraseHandlers<M, S, Z>,
+    {
+        let mut erased = Self {
+            mediator,
+            handlers: HashMap::new(),
+            receivers: HashMap::new(),
+            fallback: Fallback::new(),
+        };
+        <M::Handlers as EraseHandlers<M, S, Z>>::collect(&mut erased.tables());
+        <M::NotifyReceivers as EraseHandlers<M, S, Z>>::collect(&mut erased.tables());
+        erased
+    }
+
+    /// Registers a handler for messages without a handler, replacing any previous fallback.
+    pub fn with_fallback(mut self, f: impl IntoHandler<S, Box<dyn Any>, Box<dyn Any>>) -> Self {
+        self.fallback.set_handler(f);
+        self
+    }
+
+    /// Registers a receiver for notifications without a receiver, replacing any previous fallback.
+    pub fn with_notify_fallback(mut self, f: impl IntoReceiver<S, dyn Any>) -> Self {
+        self.fallback.set_receiver(f);
+        self
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.mediator
+    }
+
+    pub fn into_inner(self) -> M {
+        self.mediator
+    }
+
+    fn tables(&mut self) -> Tables<'_, M> {
+        Tables {
+            handlers: &mut self.handlers,
+            receivers: &mut self.receivers,
+        }
+    }
+}
+
+impl<M: Mediate<S>, S: Flavor> DynMediate for Erased<M, S> {
+    fn handle_any(&self, msg: Box<dyn Any>) -> Result<Box<dyn Any>, Box<dyn Any>> {
+        match self.handlers.get(&(*msg).type_id()) {
+            Some(handle) => Ok(handle(&self.mediator, msg)),
+            None => self.fallback.handle(msg),
+        }
+    }
+
+    fn notify_any(&self, msg: &dyn Any) -> bool {
+        match self.receivers.get(&msg.type_id()) {
+            Some(notify) => {
+                notify(&self.mediator, msg);
+                true
+            }
+            None => {
+                self.fallback.notify(msg);
+                false
+            }
+        }
+    }
+
+    fn can_handle(&self, msg: TypeId) -> bool {
+        self.handlers.contains_key(&msg)
+    }
+}
+
+/// The dispatch tables of an [`Erased`] mediator, filled in by [`EraseEntry::register`].
+pub struct Tables<'a, M> {
+    handlers: &'a mut HashMap<TypeId, HandleFn<M>>,
+    receivers: &'a mut HashMap<TypeId, NotifyFn<M>>,
+}
+
+/// Registers each entry of a list, starting at index `I` of the mediator's list.
+pub trait EraseHandlers<M, S, I> {
+    fn collect(tables: &mut Tables<'_, M>);
+}
+
+impl<M, S, I> EraseHandlers<M, S, I> for Nil {
+    fn collect(_tables: &mut Tables<'_, M>) {}
+}
+
+impl<M, S, I, T, Tail> EraseHandlers<M, S, I> for Cons<T, Tail>
+where
+    T: EraseEntry<M, S, I>,
+    Tail: EraseHandlers<M, S, Succ<I>>,
+{
+    fn collect(tables: &mut Tables<'_, M>) {
+        T::register(tables);
+        Tail::collect(tables);
+    }
+}
+
+/// Registers an entry found at index `I` of one of the mediator's lists.
+pub trait EraseEntry<M, S, I> {
+    fn register(tables: &mut Tables<'_, M>);
+}
+
+impl<M, S, I, TMsg: 'static, TResp: 'static> EraseEntry<M, S, I> for RequestResponse<TMsg, TResp, S>
+where
+    M: Mediate<S>,
+    S: Flavor,
+    M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+{
+    fn register(tables: &mut Tables<'_, M>) {
+        tables
+            .handlers
+            .entry(TypeId::of::<TMsg>())
+            .or_insert(|mediator, msg| {
+                let msg = *msg
+                    .downcast::<TMsg>()
+                    .expect("handlers are keyed by message type");
+                Box::new(mediator.handle::<TMsg, TResp, I>(msg))
+            });
+    }
+}
+
+impl<M, S, I, TMsg: 'static> EraseEntry<M, S, I> for ReceiveNotification<TMsg, S>
+where
+    M: Mediate<S>,
+    S: Flavor,
+    M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+{
+    fn register(tables: &mut Tables<'_, M>) {
+        tables
+            .receivers
+            .entry(TypeId::of::<TMsg>())
+            .or_insert(|mediator, msg| {
+                let msg = msg
+                    .downcast_ref::<TMsg>()
+                    .expect("receivers are keyed by message type");
+                mediator.notify::<TMsg, I>(msg)
+            });
+    }
+}
+
+/// Skips entries which can't be reached through [`DynMediate`].
+macro_rules! skip_entry {
+    ($($entry:ty => [$($param:tt)*]),* $(,)?) => {$(
+        impl<M, S: Flavor, I, $($param)*> EraseEntry<M, S, I> for $entry {
+            fn register(_tables: &mut Tables<'_, M>) {}
+        }
+    )*};
+}
+
+skip_entry! {
+    TryRequestResponse<TMsg, TResp, TErr, S> => [TMsg, TResp, TErr],
+    ReceiveNotificationOwned<TMsg, S> => [TMsg],
+    ReceiveNotificationBroadcast<TMsg, S> => [TMsg],
+    TryReceiveNotification<TMsg, TErr, S> => [TMsg: ?Sized, TErr],
+}
+
+#[cfg(feature = "async")]
+skip_entry! {
+    RequestResponseAsync<TMsg, TResp, S> => [TMsg, TResp: 'static],
+    TryRequestResponseAsync<TMsg, TResp, TErr, S> => [TMsg, TResp: 'static, TErr: 'static],
+    RequestStream<TMsg, TItem, S> => [TMsg, TItem],
+    ReceiveNotificationAsync<TMsg, S> => [TMsg],
+    ReceiveNotificationShared<TMsg, S> => [TMsg: ?Sized],
+}
+
+impl<M, S, I, TMsg, TResp, F> EraseEntry<M, S, I> for RequestResponseFn<TMsg, TResp, F> {
+    fn register(_tables: &mut Tables<'_, M>) {}
+}
@@ -0,0 +1,80 @@
+//! Persisting notifications to an event store.
+//!
+//! [`MediatorBuilder::store_events_in`] registers a receiver which encodes every notification of a type with [`Encode`],
+//! and appends it to an [`EventStore`] under a tag, so the mediator can serve as the in-process event bus of an event-sourced system.
+//! The same store can be installed for any number of event types.
+//! ```rust
+//! use noon::events::{MemoryEventStore, StoredEvent};
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let store = MemoryEventStore::new();
+//! let mediator = MediatorBuilder::new()
+//!     .listen_for::<String>()
+//!     .store_events_in::<String, _, _>("renamed", store.clone())
+//!     .build();
+//! mediator.notify(&"ada".to_string());
+//! assert_eq!(store.events(), [StoredEvent::new("renamed", b"ada".to_vec())]);
+//! ```
+//!
+//! Stored events keep the tags they were appended with, so they can be replayed into a [`crate::codec::TaggedMediator`] registered with the same tags.
+//! Events are appended from within the notification, in the order the receivers were added.
+//!
+//! [`MediatorBuilder::store_events_in`]: crate::mediator::MediatorBuilder::store_events_in
+//! [`Encode`]: crate::codec::Encode
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// Receives the encoded notifications to persist, see [`crate::events`].
+pub trait EventStore {
+    /// Appends an event. A store which can fail should report its own errors, since notifications can't.
+    fn append(&self, event: StoredEvent);
+}
+
+impl<T: EventStore + ?Sized> EventStore for Rc<T> {
+    fn append(&self, event: StoredEvent) {
+        (**self).append(event)
+    }
+}
+
+impl<T: EventStore + ?Sized> EventStore for Arc<T> {
+    fn append(&self, event: StoredEvent) {
+        (**self).append(event)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredEvent {
+    pub tag: &'static str,
+    pub payload: Vec<u8>,
+}
+
+impl StoredEvent {
+    pub fn new(tag: &'static str, payload: Vec<u8>) -> Self {
+        Self { tag, payload }
+    }
+}
+
+/// An event store holding its events in memory, for tests and prototypes.
+///
+/// Clones share the same events.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryEventStore {
+    events: Arc<Mutex<Vec<StoredEvent>>>,
+}
+
+impl MemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The events appended so far, in order.
+    pub fn events(&self) -> Vec<StoredEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl EventStore for MemoryEventStore {
+    fn append(&self, event: StoredEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
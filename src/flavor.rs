@@ -0,0 +1,312 @@
+//! Thread-safety flavors for mediators and their entries.
+//!
+//! A [`Local`] mediator accepts any `'static` closure, but can't be sent across threads.
+//! A [`Shared`] mediator requires every registered closure (and every future it produces) to be `Send + Sync`,
+//! which makes the built mediator itself `Send + Sync`, so it can be stored in an `Arc` and used from many tasks.
+//! The flavor is the last type parameter of [`crate::mediator::MediatorBuilder`] and of the entries, and is picked by
+//! [`crate::mediator::MediatorBuilder::new`] or [`crate::mediator::MediatorBuilder::new_shared`], so both flavors share the same builder methods.
+//!
+//! The bounds are checked as each receiver is registered, so a shared builder rejects a closure which isn't thread-safe.
+//! ```rust,compile_fail
+//! use noon::mediator::MediatorBuilder;
+//! use std::rc::Rc;
+//!
+//! let prefix = Rc::new("user ".to_string());
+//! let mediator = MediatorBuilder::new_shared()
+//!     .add_handler(move |id: u32| format!("{}{}", prefix, id))
+//!     .build();
+//! ```
+//! And a local mediator can't be moved to another thread.
+//! ```rust,compile_fail
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|id: u32| id + 1)
+//!     .build();
+//! std::thread::spawn(move || mediator.handle(1u32));
+//! ```
+//!
+//! The futures returned by a shared mediator's [`crate::mediator::Mediate::handle_async`] and [`crate::mediator::Mediate::notify_async`]
+//! are `Send`, so they can be moved to another thread or passed to an executor's `spawn`, such as `tokio::spawn`.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::sync::Arc;
+//!
+//! # #[cfg(feature = "async")] {
+//! let mediator = Arc::new(
+//!     MediatorBuilder::new_shared()
+//!         .add_async_handler(|x: i32| async move { x * 2 })
+//!         .build(),
+//! );
+//! let fut = mediator.handle_async(21);
+//! let worker = std::thread::spawn(move || {
+//!     // drive `fut` with any executor on this thread
+//!     drop(fut);
+//! });
+//! worker.join().unwrap();
+//! # }
+//! ```
+//!
+//! The `Into*` traits in this module convert closures into the boxed form stored by each flavor, and are implemented for any closure meeting the flavor's bounds.
+#[cfg(feature = "async")]
+use crate::hooks::{Dispatch, DispatchHooks, Started};
+#[cfg(feature = "async")]
+use crate::pipeline::NextAsync;
+#[cfg(feature = "async")]
+use crate::stream::Stream;
+#[cfg(feature = "async")]
+use crate::time::{self, Elapsed};
+
+use alloc::boxed::Box;
+#[cfg(feature = "async")]
+use alloc::sync::Arc;
+#[cfg(feature = "async")]
+use alloc::vec::Vec;
+use core::any::Any;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::time::Duration;
+
+pub trait Flavor: Sized + 'static {
+    type Handler<TMsg, TResp>: ?Sized + Fn(TMsg) -> TResp;
+    type Receiver<TMsg: ?Sized>: ?Sized + Fn(&TMsg);
+    type TryReceiver<TMsg: ?Sized, TErr>: ?Sized + Fn(&TMsg) -> Result<(), TErr>;
+    type Behavior<TMsg, TResp>: ?Sized + Fn(TMsg, &dyn Fn(TMsg) -> TResp) -> TResp;
+    #[cfg(feature = "async")]
+    type Future<'a, T>: ?Sized + Future<Output = T>
+    where
+        T: 'a;
+    #[cfg(feature = "async")]
+    type Stream<T>: ?Sized + Stream<Item = T>;
+    /// A type-erased entry, see [`crate::dynamic::DynamicMediator`].
+    type Erased: ?Sized + 'static;
+
+    /// Awaits each of `futures` in order.
+    #[cfg(feature = "async")]
+    fn sequence<'a, 'b: 'a>(futures: Vec<BoxFuture<'b, (), Self>>) -> BoxFuture<'a, (), Self>;
+
+    /// Runs the after-dispatch `hooks` once `fut` completes.
+    #[cfg(feature = "async")]
+    fn after_dispatch<'a, T: 'a>(
+        fut: BoxFuture<'a, T, Self>,
+        hooks: Arc<DispatchHooks<Self>>,
+        dispatch: Dispatch,
+        started: Started,
+    ) -> BoxFuture<'a, T, Self>;
+
+    /// Fails with [`Elapsed`] if `fut` doesn't complete within `duration`.
+    #[cfg(feature = "async")]
+    fn timeout<'a, T: 'a>(
+        fut: BoxFuture<'a, T, Self>,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<T, Elapsed>, Self>;
+
+    /// Drops the output of `fut` once it completes.
+    #[cfg(feature = "async")]
+    fn discard<'a, 'b: 'a, T: 'b>(fut: BoxFuture<'b, T, Self>) -> BoxFuture<'a, (), Self>;
+
+    fn downcast_ref<T: 'static>(erased: &Self::Erased) -> Option<&T>;
+}
+
+/// Single-threaded flavor, the default.
+pub struct Local;
+
+/// Thread-safe flavor, see [`crate::mediator::MediatorBuilder::new_shared`].
+pub struct Shared;
+
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T, S = Local> = Pin<Box<<S as Flavor>::Future<'a, T>>>;
+
+#[cfg(feature = "async")]
+pub type BoxStream<T, S = Local> = Pin<Box<<S as Flavor>::Stream<T>>>;
+
+#[cfg(feature = "async")]
+pub type AsyncCallback<TMsg, TResp, S = Local> =
+    <S as Flavor>::Handler<TMsg, BoxFuture<'static, TResp, S>>;
+
+/// An asynchronous pipeline behavior, called with each message and the remainder of its pipeline.
+#[cfg(feature = "async")]
+pub type AsyncBehavior<TMsg, TResp, S = Local> =
+    AsyncCallback<(TMsg, NextAsync<TMsg, TResp, S>), TResp, S>;
+
+pub trait IntoHandler<S: Flavor, TMsg, TResp> {
+    fn into_handler(self) -> Box<S::Handler<TMsg, TResp>>;
+}
+
+#[cfg(feature = "async")]
+pub trait IntoAsyncHandler<S: Flavor, TMsg, TResp: 'static> {
+    fn into_async_handler(self) -> Box<AsyncCallback<TMsg, TResp, S>>;
+}
+
+pub trait IntoReceiver<S: Flavor, TMsg: ?Sized> {
+    fn into_receiver(self) -> Box<S::Receiver<TMsg>>;
+}
+
+pub trait IntoTryReceiver<S: Flavor, TMsg: ?Sized, TErr> {
+    fn into_try_receiver(self) -> Box<S::TryReceiver<TMsg, TErr>>;
+}
+
+pub trait IntoBehavior<S: Flavor, TMsg, TResp> {
+    fn into_behavior(self) -> Box<S::Behavior<TMsg, TResp>>;
+}
+
+#[cfg(feature = "async")]
+pub trait IntoAsyncBehavior<S: Flavor, TMsg, TResp: 'static> {
+    fn into_async_behavior(self) -> Box<AsyncBehavior<TMsg, TResp, S>>;
+}
+
+#[cfg(feature = "async")]
+pub trait IntoStreamHandler<S: Flavor, TMsg, TItem> {
+    fn into_stream_handler(self) -> Box<S::Handler<TMsg, BoxStream<TItem, S>>>;
+}
+
+pub trait IntoErased<S: Flavor> {
+    fn into_erased(self) -> Box<S::Erased>;
+}
+
+/// Implements a flavor, given the extra bounds on its closures and on its futures.
+macro_rules! flavor {
+    ($flavor:ident, ($($sync:tt)*), ($($send:tt)*)) => {
+        impl Flavor for $flavor {
+            type Handler<TMsg, TResp> = dyn Fn(TMsg) -> TResp $($sync)*;
+            type Receiver<TMsg: ?Sized> = dyn Fn(&TMsg) $($sync)*;
+            type TryReceiver<TMsg: ?Sized, TErr> = dyn Fn(&TMsg) -> Result<(), TErr> $($sync)*;
+            type Behavior<TMsg, TResp> = dyn Fn(TMsg, &dyn Fn(TMsg) -> TResp) -> TResp $($sync)*;
+            #[cfg(feature = "async")]
+            type Future<'a, T>
+                = dyn Future<Output = T> $($send)* + 'a
+            where
+                T: 'a;
+            #[cfg(feature = "async")]
+            type Stream<T> = dyn Stream<Item = T> $($send)*;
+            type Erased = dyn Any $($sync)*;
+
+            #[cfg(feature = "async")]
+            fn sequence<'a, 'b: 'a>(
+                futures: Vec<BoxFuture<'b, (), Self>>,
+            ) -> BoxFuture<'a, (), Self> {
+                Box::pin(async move {
+                    for fut in futures {
+                        fut.await;
+                    }
+                })
+            }
+
+            #[cfg(feature = "async")]
+            fn after_dispatch<'a, T: 'a>(
+                fut: BoxFuture<'a, T, Self>,
+                hooks: Arc<DispatchHooks<Self>>,
+                dispatch: Dispatch,
+                started: Started,
+            ) -> BoxFuture<'a, T, Self> {
+                Box::pin(async move {
+                    let resp = fut.await;
+                    hooks.after(&dispatch, started);
+                    resp
+                })
+            }
+
+            #[cfg(feature = "async")]
+            fn timeout<'a, T: 'a>(
+                fut: BoxFuture<'a, T, Self>,
+                duration: Duration,
+            ) -> BoxFuture<'a, Result<T, Elapsed>, Self> {
+                Box::pin(time::timeout(duration, fut))
+            }
+
+            #[cfg(feature = "async")]
+            fn discard<'a, 'b: 'a, T: 'b>(fut: BoxFuture<'b, T, Self>) -> BoxFuture<'a, (), Self> {
+                Box::pin(async move {
+                    fut.await;
+                })
+            }
+
+            fn downcast_ref<T: 'static>(erased: &Self::Erased) -> Option<&T> {
+                erased.downcast_ref()
+            }
+        }
+
+        impl<T: Any $($sync)*> IntoErased<$flavor> for T {
+            fn into_erased(self) -> Box<<$flavor as Flavor>::Erased> {
+                Box::new(self)
+            }
+        }
+
+        impl<F, TMsg, TResp> IntoHandler<$flavor, TMsg, TResp> for F
+        where
+            F: Fn(TMsg) -> TResp $($sync)* + 'static,
+        {
+            fn into_handler(self) -> Box<<$flavor as Flavor>::Handler<TMsg, TResp>> {
+                Box::new(self)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<F, Fut, TMsg, TResp: 'static> IntoAsyncHandler<$flavor, TMsg, TResp> for F
+        where
+            Fut: Future<Output = TResp> $($send)* + 'static,
+            F: Fn(TMsg) -> Fut $($sync)* + 'static,
+        {
+            fn into_async_handler(self) -> Box<AsyncCallback<TMsg, TResp, $flavor>> {
+                Box::new(move |msg| Box::pin(self(msg)) as _)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<F, St, TMsg, TItem> IntoStreamHandler<$flavor, TMsg, TItem> for F
+        where
+            St: Stream<Item = TItem> $($send)* + 'static,
+            F: Fn(TMsg) -> St $($sync)* + 'static,
+        {
+            fn into_stream_handler(
+                self,
+            ) -> Box<<$flavor as Flavor>::Handler<TMsg, BoxStream<TItem, $flavor>>> {
+                Box::new(move |msg| Box::pin(self(msg)) as _)
+            }
+        }
+
+        impl<F, TMsg: ?Sized> IntoReceiver<$flavor, TMsg> for F
+        where
+            F: Fn(&TMsg) $($sync)* + 'static,
+        {
+            fn into_receiver(self) -> Box<<$flavor as Flavor>::Receiver<TMsg>> {
+                Box::new(self)
+            }
+        }
+
+        impl<F, TMsg: ?Sized, TErr> IntoTryReceiver<$flavor, TMsg, TErr> for F
+        where
+            F: Fn(&TMsg) -> Result<(), TErr> $($sync)* + 'static,
+        {
+            fn into_try_receiver(self) -> Box<<$flavor as Flavor>::TryReceiver<TMsg, TErr>> {
+                Box::new(self)
+            }
+        }
+
+        impl<F, TMsg, TResp> IntoBehavior<$flavor, TMsg, TResp> for F
+        where
+            F: Fn(TMsg, &dyn Fn(TMsg) -> TResp) -> TResp $($sync)* + 'static,
+        {
+            fn into_behavior(self) -> Box<<$flavor as Flavor>::Behavior<TMsg, TResp>> {
+                Box::new(self)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<F, Fut, TMsg, TResp: 'static> IntoAsyncBehavior<$flavor, TMsg, TResp> for F
+        where
+            Fut: Future<Output = TResp> $($send)* + 'static,
+            F: Fn(TMsg, NextAsync<TMsg, TResp, $flavor>) -> Fut $($sync)* + 'static,
+        {
+            fn into_async_behavior(self) -> Box<AsyncBehavior<TMsg, TResp, $flavor>> {
+                Box::new(move |(msg, next)| Box::pin(self(msg, next)) as _)
+            }
+        }
+    };
+}
+
+flavor!(Local, (), ());
+flavor!(Shared, (+ Send + Sync), (+ Send));
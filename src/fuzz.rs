@@ -0,0 +1,306 @@
+//! Property testing handler interactions with random messages, behind the `fuzz` feature.
+//!
+//! A [`Fuzzer`] generates random sequences of messages of the types registered with it, dispatches them through a mediator,
+//! and checks the invariants supplied by the user after each dispatch. A violated invariant is reported as [`Falsified`],
+//! along with the seed which reproduces the failure and the messages dispatched up to it.
+//!
+//! Messages are generated by implementing [`Arbitrary`], which is implemented for primitives, `String`, `Option`, `Vec` and tuples.
+//! Types with an existing generator, such as a `proptest` strategy or an `arbitrary::Arbitrary` impl, can implement it by seeding that generator from the given [`Rng`].
+//! Failing sequences aren't shrunk.
+//! ```rust
+//! use noon::fuzz::{Arbitrary, Fuzzer, Rng};
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! #[derive(Debug)]
+//! struct Deposit(u8);
+//! #[derive(Debug)]
+//! struct Withdraw(u8);
+//! struct Balance;
+//!
+//! impl Arbitrary for Deposit {
+//!     fn arbitrary(rng: &mut Rng) -> Self {
+//!         Deposit(u8::arbitrary(rng))
+//!     }
+//! }
+//!
+//! impl Arbitrary for Withdraw {
+//!     fn arbitrary(rng: &mut Rng) -> Self {
+//!         Withdraw(u8::arbitrary(rng))
+//!     }
+//! }
+//!
+//! let balance = Rc::new(Cell::new(0i64));
+//! let (deposits, withdrawals, reads) = (balance.clone(), balance.clone(), balance.clone());
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(move |req: Deposit| deposits.set(deposits.get() + i64::from(req.0)))
+//!     .add_handler(move |req: Withdraw| {
+//!         let left = withdrawals.get() - i64::from(req.0);
+//!         if left >= 0 {
+//!             withdrawals.set(left);
+//!         }
+//!     })
+//!     .add_handler(move |_: Balance| reads.get())
+//!     .build();
+//!
+//! let result = Fuzzer::for_mediator(&mediator)
+//!     .handle::<Deposit, (), _>()
+//!     .handle::<Withdraw, (), _>()
+//!     .invariant("balance is never negative", |m| m.handle::<Balance, i64, _>(Balance) >= 0)
+//!     .run(&mediator, 42, 100);
+//! assert!(result.is_ok());
+//! ```
+use crate::entry::{ReceiveNotification, RequestResponse};
+use crate::flavor::{Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A small, seedable pseudo-random number generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        // splitmix64
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`.
+    ///
+    /// # Panics
+    /// If `bound` is zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be positive");
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// A type whose values can be generated randomly.
+pub trait Arbitrary: Sized {
+    fn arbitrary(rng: &mut Rng) -> Self;
+}
+
+macro_rules! arbitrary_int {
+    ($($ty:ty),*) => {$(
+        impl Arbitrary for $ty {
+            fn arbitrary(rng: &mut Rng) -> Self {
+                rng.next_u64() as $ty
+            }
+        }
+    )*};
+}
+
+arbitrary_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        rng.next_u64() & 1 == 1
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        char::from_u32(rng.below(0xd800) as u32).unwrap_or('\0')
+    }
+}
+
+/// The longest `String` or `Vec` generated.
+const MAX_LEN: usize = 16;
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        let len = rng.below(MAX_LEN + 1);
+        (0..len).map(|_| char::arbitrary(rng)).collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        bool::arbitrary(rng).then(|| T::arbitrary(rng))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        let len = rng.below(MAX_LEN + 1);
+        (0..len).map(|_| T::arbitrary(rng)).collect()
+    }
+}
+
+macro_rules! arbitrary_tuple {
+    ($($name:ident)*) => {
+        impl<$($name: Arbitrary),*> Arbitrary for ($($name,)*) {
+            fn arbitrary(rng: &mut Rng) -> Self {
+                ($($name::arbitrary(rng),)*)
+            }
+        }
+    };
+}
+
+impl Arbitrary for () {
+    fn arbitrary(_: &mut Rng) -> Self {}
+}
+
+arbitrary_tuple!(A);
+arbitrary_tuple!(A B);
+arbitrary_tuple!(A B C);
+arbitrary_tuple!(A B C D);
+
+/// A violated invariant, see [`Fuzzer::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Falsified {
+    /// The name of the violated invariant.
+    pub invariant: &'static str,
+    /// The seed of the failing run, which reproduces it.
+    pub seed: u64,
+    /// The [`fmt::Debug`] representations of the messages dispatched, up to and including the one after which the invariant was violated.
+    pub messages: Vec<String>,
+}
+
+impl fmt::Display for Falsified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invariant `{}` was violated after {} message(s) with seed {}",
+            self.invariant,
+            self.messages.len(),
+            self.seed
+        )
+    }
+}
+
+impl Error for Falsified {}
+
+/// Generates a message and dispatches it, returning its `Debug` representation.
+type Dispatch<M> = fn(&M, &mut Rng) -> String;
+
+/// A named property of the mediator, checked after each dispatch.
+type Invariant<M> = (&'static str, Box<dyn Fn(&M) -> bool>);
+
+pub struct Fuzzer<M, S: Flavor = Local> {
+    dispatches: Vec<Dispatch<M>>,
+    invariants: Vec<Invariant<M>>,
+    _flavor: PhantomData<S>,
+}
+
+impl<M: Mediate<S>, S: Flavor> Fuzzer<M, S> {
+    pub fn new() -> Self {
+        Self {
+            dispatches: vec![],
+            invariants: vec![],
+            _flavor: PhantomData,
+        }
+    }
+
+    /// Like [`Fuzzer::new`], for mediators of the same type as `mediator`,
+    /// which lets the closures passed to [`Fuzzer::invariant`] infer their argument's type.
+    pub fn for_mediator(_mediator: &M) -> Self {
+        Self::new()
+    }
+
+    /// Generates `TMsg`s to dispatch with [`Mediate::handle`], discarding the responses.
+    pub fn handle<TMsg, TResp, I>(mut self) -> Self
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Arbitrary + fmt::Debug,
+    {
+        fn dispatch<M, S, TMsg, TResp, I>(mediator: &M, rng: &mut Rng) -> String
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+            TMsg: Arbitrary + fmt::Debug,
+        {
+            let msg = TMsg::arbitrary(rng);
+            let debug = format!("{:?}", msg);
+            mediator.handle(msg);
+            debug
+        }
+        self.dispatches.push(dispatch::<M, S, TMsg, TResp, I>);
+        self
+    }
+
+    /// Generates `TMsg`s to dispatch with [`Mediate::notify`].
+    pub fn notify<TMsg, I>(mut self) -> Self
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        TMsg: Arbitrary + fmt::Debug,
+    {
+        fn dispatch<M, S, TMsg, I>(mediator: &M, rng: &mut Rng) -> String
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+            TMsg: Arbitrary + fmt::Debug,
+        {
+            let msg = TMsg::arbitrary(rng);
+            mediator.notify(&msg);
+            format!("{:?}", msg)
+        }
+        self.dispatches.push(dispatch::<M, S, TMsg, I>);
+        self
+    }
+
+    /// Adds an invariant, which must hold after every dispatch.
+    pub fn invariant(mut self, name: &'static str, holds: impl Fn(&M) -> bool + 'static) -> Self {
+        self.invariants.push((name, Box::new(holds)));
+        self
+    }
+
+    /// Dispatches `steps` random messages through `mediator`, generated from `seed`, checking the invariants after each.
+    ///
+    /// # Panics
+    /// If no types of messages were registered.
+    pub fn run(&self, mediator: &M, seed: u64, steps: usize) -> Result<(), Falsified> {
+        assert!(
+            !self.dispatches.is_empty(),
+            "no types of messages to generate"
+        );
+        let mut rng = Rng::new(seed);
+        let mut messages = vec![];
+        for _ in 0..steps {
+            let dispatch = self.dispatches[rng.below(self.dispatches.len())];
+            messages.push(dispatch(mediator, &mut rng));
+            let violated = self.invariants.iter().find(|(_, holds)| !holds(mediator));
+            if let Some(&(invariant, _)) = violated {
+                return Err(Falsified {
+                    invariant,
+                    seed,
+                    messages,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Fuzzer::run`], for each of `cases` seeds, with a new mediator for each.
+    pub fn check(
+        &self,
+        cases: u64,
+        steps: usize,
+        mut mediator: impl FnMut() -> M,
+    ) -> Result<(), Falsified> {
+        (0..cases).try_for_each(|seed| self.run(&mediator(), seed, steps))
+    }
+}
+
+impl<M: Mediate<S>, S: Flavor> Default for Fuzzer<M, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
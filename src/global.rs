@@ -0,0 +1,347 @@
+//! A process-wide mediator.
+//!
+//! [`install`] stores a thread-safe mediator for the rest of the process, along with the [`Capabilities`] it's installed for,
+//! and [`get`] retrieves it anywhere as a [`Global`], which can only dispatch the messages listed in the requested capabilities,
+//! like a [`crate::view::View`]. This suits applications with one mediator, which don't want to pass it through every constructor.
+//! ```rust
+//! use noon::global;
+//! use noon::mediator::MediatorBuilder;
+//! use noon::view::{Handle, Notify};
+//!
+//! struct GetUser(u32);
+//! struct UserSeen(u32);
+//!
+//! type App = (Handle<GetUser, String>, Notify<UserSeen>);
+//!
+//! let mediator = MediatorBuilder::new_shared()
+//!     .add_handler(|req: GetUser| format!("user {}", req.0))
+//!     .listen_for::<UserSeen>()
+//!     .build();
+//! assert!(global::install::<App, _, _>(mediator).is_ok());
+//!
+//! fn show_user(id: u32) -> String {
+//!     let mediator = global::get::<(Handle<GetUser, String>,)>();
+//!     mediator.handle(GetUser(id))
+//! }
+//! assert_eq!(show_user(7), "user 7");
+//! ```
+//!
+//! Installing checks at compile time that the mediator has a receiver for every capability, and retrieving checks at runtime
+//! that the requested capabilities are among the installed ones, since the installed mediator's type is erased.
+//! Only one mediator can be installed, and it's never dropped.
+use crate::entry::{ReceiveNotification, RequestResponse, TryRequestResponse};
+#[cfg(feature = "async")]
+use crate::entry::{ReceiveNotificationAsync, RequestResponseAsync, RequestStream};
+use crate::flavor::Shared;
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, BoxStream};
+use crate::hlist::{Cons, ContainsAt, Nil};
+use crate::mediator::Mediate;
+use crate::view::{Capabilities, Handle, Notify, TryHandle};
+#[cfg(feature = "async")]
+use crate::view::{HandleAsync, HandleStream, NotifyAsync};
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+type Erased = dyn Any + Send + Sync;
+
+/// Dispatches a message to the installed mediator, whose type is known when the capability is installed.
+type Thunk<TMsg, TResp> = fn(&'static Erased, TMsg) -> TResp;
+
+struct Installed {
+    mediator: Box<Erased>,
+    thunks: HashMap<TypeId, Box<Erased>>,
+}
+
+static INSTALLED: OnceLock<Installed> = OnceLock::new();
+
+/// The dispatch functions of the capabilities being installed, see [`Installable`].
+pub struct Thunks {
+    thunks: HashMap<TypeId, Box<Erased>>,
+}
+
+impl Thunks {
+    fn insert<C: 'static, T: Any + Send + Sync>(&mut self, thunk: T) {
+        self.thunks.insert(TypeId::of::<C>(), Box::new(thunk));
+    }
+}
+
+/// A capability which a mediator of type `M` has, so it can be installed along with it.
+pub trait Installable<M, I> {
+    fn install(thunks: &mut Thunks);
+}
+
+fn downcast<M: 'static>(mediator: &'static Erased) -> &'static M {
+    mediator
+        .downcast_ref()
+        .expect("thunks are installed along with their mediator")
+}
+
+impl<M, TMsg: 'static, TResp: 'static, I> Installable<M, I> for Handle<TMsg, TResp>
+where
+    M: Mediate<Shared> + 'static,
+    M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: Thunk<TMsg, TResp> = |mediator, msg| downcast::<M>(mediator).handle(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg: 'static, TResp: 'static, I> Installable<M, I> for HandleAsync<TMsg, TResp>
+where
+    M: Mediate<Shared> + 'static,
+    M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: Thunk<TMsg, BoxFuture<'static, TResp, Shared>> =
+            |mediator, msg| downcast::<M>(mediator).handle_async(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+impl<M, TMsg: 'static, TResp: 'static, TErr: 'static, I> Installable<M, I>
+    for TryHandle<TMsg, TResp, TErr>
+where
+    M: Mediate<Shared> + 'static,
+    M::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: Thunk<TMsg, Result<TResp, TErr>> =
+            |mediator, msg| downcast::<M>(mediator).try_handle(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg: 'static, TItem: 'static, I> Installable<M, I> for HandleStream<TMsg, TItem>
+where
+    M: Mediate<Shared> + 'static,
+    M::Handlers: ContainsAt<RequestStream<TMsg, TItem, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: Thunk<TMsg, BoxStream<TItem, Shared>> =
+            |mediator, msg| downcast::<M>(mediator).handle_stream(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+impl<M, TMsg: ?Sized + 'static, I> Installable<M, I> for Notify<TMsg>
+where
+    M: Mediate<Shared> + 'static,
+    M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: fn(&'static Erased, &TMsg) = |mediator, msg| downcast::<M>(mediator).notify(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg: Clone + 'static, I> Installable<M, I> for NotifyAsync<TMsg>
+where
+    M: Mediate<Shared> + 'static,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, Shared>, I>,
+{
+    fn install(thunks: &mut Thunks) {
+        let thunk: Thunk<TMsg, BoxFuture<'static, (), Shared>> =
+            |mediator, msg| downcast::<M>(mediator).notify_async(msg);
+        thunks.insert::<Self, _>(thunk);
+    }
+}
+
+/// Installs each capability of a list, `I` holding the index of each one's receiver.
+pub trait InstallAll<M, I> {
+    fn install_all(thunks: &mut Thunks);
+}
+
+impl<M> InstallAll<M, Nil> for Nil {
+    fn install_all(_thunks: &mut Thunks) {}
+}
+
+impl<M, C: Installable<M, I>, Tail: InstallAll<M, J>, I, J> InstallAll<M, Cons<I, J>>
+    for Cons<C, Tail>
+{
+    fn install_all(thunks: &mut Thunks) {
+        C::install(thunks);
+        Tail::install_all(thunks);
+    }
+}
+
+/// Lists the types of the capabilities of a list, so they can be looked up.
+pub trait CapabilityIds {
+    fn ids() -> Vec<TypeId>;
+}
+
+impl CapabilityIds for Nil {
+    fn ids() -> Vec<TypeId> {
+        vec![]
+    }
+}
+
+impl<C: 'static, Tail: CapabilityIds> CapabilityIds for Cons<C, Tail> {
+    fn ids() -> Vec<TypeId> {
+        let mut ids = Tail::ids();
+        ids.push(TypeId::of::<C>());
+        ids
+    }
+}
+
+/// Installs `mediator` as the process-wide mediator with the capabilities `P`, see [`crate::global`].
+///
+/// `mediator` is returned back if a mediator was already installed.
+pub fn install<P, M, I>(mediator: M) -> Result<(), M>
+where
+    P: Capabilities,
+    P::List: InstallAll<M, I>,
+    M: Mediate<Shared> + Send + Sync + 'static,
+{
+    let mut slot = Some(mediator);
+    INSTALLED.get_or_init(|| {
+        let mut thunks = Thunks {
+            thunks: HashMap::new(),
+        };
+        <P::List as InstallAll<M, I>>::install_all(&mut thunks);
+        Installed {
+            mediator: Box::new(slot.take().expect("initialized once")),
+            thunks: thunks.thunks,
+        }
+    });
+    match slot {
+        Some(mediator) => Err(mediator),
+        None => Ok(()),
+    }
+}
+
+/// The process-wide mediator, restricted to the capabilities `P`.
+///
+/// # Panics
+/// If no mediator is installed, or it wasn't installed with every capability in `P`.
+pub fn get<P: Capabilities>() -> Global<P>
+where
+    P::List: CapabilityIds,
+{
+    let installed = INSTALLED.get().expect("no global mediator is installed");
+    assert!(
+        P::List::ids()
+            .iter()
+            .all(|id| installed.thunks.contains_key(id)),
+        "the global mediator wasn't installed with the requested capabilities"
+    );
+    Global {
+        installed,
+        _capabilities: PhantomData,
+    }
+}
+
+/// Like [`get`], but returns `None` instead of panicking.
+pub fn try_get<P: Capabilities>() -> Option<Global<P>>
+where
+    P::List: CapabilityIds,
+{
+    let installed = INSTALLED.get()?;
+    P::List::ids()
+        .iter()
+        .all(|id| installed.thunks.contains_key(id))
+        .then_some(Global {
+            installed,
+            _capabilities: PhantomData,
+        })
+}
+
+/// The process-wide mediator restricted to the capabilities `P`, see [`get`].
+///
+/// Its methods take the index `I` of the capability in `P`, which is inferred.
+pub struct Global<P> {
+    installed: &'static Installed,
+    _capabilities: PhantomData<fn() -> P>,
+}
+
+impl<P> Clone for Global<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Global<P> {}
+
+impl<P: Capabilities> Global<P> {
+    fn thunk<C: 'static, T: Copy + 'static>(&self) -> T {
+        *self.installed.thunks[&TypeId::of::<C>()]
+            .downcast_ref::<T>()
+            .expect("thunks are keyed by capability")
+    }
+
+    pub fn handle<TMsg: 'static, TResp: 'static, I>(&self, msg: TMsg) -> TResp
+    where
+        P::List: ContainsAt<Handle<TMsg, TResp>, I>,
+    {
+        self.thunk::<Handle<TMsg, TResp>, Thunk<TMsg, TResp>>()(&*self.installed.mediator, msg)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn handle_async<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, Shared>
+    where
+        P::List: ContainsAt<HandleAsync<TMsg, TResp>, I>,
+    {
+        self.thunk::<HandleAsync<TMsg, TResp>, Thunk<TMsg, BoxFuture<'static, TResp, Shared>>>()(
+            &*self.installed.mediator,
+            msg,
+        )
+    }
+
+    pub fn try_handle<TMsg: 'static, TResp: 'static, TErr: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> Result<TResp, TErr>
+    where
+        P::List: ContainsAt<TryHandle<TMsg, TResp, TErr>, I>,
+    {
+        self.thunk::<TryHandle<TMsg, TResp, TErr>, Thunk<TMsg, Result<TResp, TErr>>>()(
+            &*self.installed.mediator,
+            msg,
+        )
+    }
+
+    #[cfg(feature = "async")]
+    pub fn handle_stream<TMsg: 'static, TItem: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxStream<TItem, Shared>
+    where
+        P::List: ContainsAt<HandleStream<TMsg, TItem>, I>,
+    {
+        self.thunk::<HandleStream<TMsg, TItem>, Thunk<TMsg, BoxStream<TItem, Shared>>>()(
+            &*self.installed.mediator,
+            msg,
+        )
+    }
+
+    pub fn notify<TMsg: ?Sized + 'static, I>(&self, msg: &TMsg)
+    where
+        P::List: ContainsAt<Notify<TMsg>, I>,
+    {
+        self.thunk::<Notify<TMsg>, fn(&'static Erased, &TMsg)>()(&*self.installed.mediator, msg)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn notify_async<TMsg: Clone + 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, (), Shared>
+    where
+        P::List: ContainsAt<NotifyAsync<TMsg>, I>,
+    {
+        self.thunk::<NotifyAsync<TMsg>, Thunk<TMsg, BoxFuture<'static, (), Shared>>>()(
+            &*self.installed.mediator,
+            msg,
+        )
+    }
+}
@@ -0,0 +1,156 @@
+//! Exporting the topology of a mediator as a graph, to visualize message flow.
+//!
+//! [`Mediate::to_dot`] renders a mediator's [`Description`] in Graphviz's DOT language, and [`Mediate::to_mermaid`] as a Mermaid flowchart.
+//! Each type of message or response is a node, each handler is an edge from its message to its response,
+//! and the receivers of each type of notification are a node with an edge from the message.
+//! A type handled by one handler and returned by another appears once, which shows how requests chain.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|x: i32| x.to_string())
+//!     .listen_for::<str>()
+//!     .build();
+//! assert_eq!(
+//!     mediator.to_dot(),
+//!     "digraph mediator {
+//!     rankdir=LR;
+//!     n0 [label=\"i32\", shape=box];
+//!     n1 [label=\"alloc::string::String\", shape=box];
+//!     n2 [label=\"str\", shape=box];
+//!     n3 [label=\"0 receivers\", shape=ellipse];
+//!     n0 -> n1 [label=\"handler\"];
+//!     n2 -> n3 [label=\"notification\"];
+//! }
+//! "
+//! );
+//! ```
+//!
+//! [`Mediate::to_dot`]: crate::mediator::Mediate::to_dot
+//! [`Mediate::to_mermaid`]: crate::mediator::Mediate::to_mermaid
+use crate::describe::Description;
+
+use std::fmt::Write;
+
+enum Shape {
+    Type,
+    Receivers,
+}
+
+struct Graph {
+    nodes: Vec<(String, Shape)>,
+    edges: Vec<(usize, usize, String)>,
+}
+
+impl Graph {
+    fn new(description: &Description) -> Self {
+        let mut graph = Self {
+            nodes: vec![],
+            edges: vec![],
+        };
+        for entry in &description.handlers {
+            let message = graph.type_node(entry.message);
+            let response = graph.type_node(entry.response.unwrap_or("()"));
+            graph
+                .edges
+                .push((message, response, entry.kind.to_string()));
+        }
+        for entry in &description.receivers {
+            let message = graph.type_node(entry.message);
+            let receivers = graph.nodes.len();
+            let label = format!("{} receivers", entry.receivers.unwrap_or(0));
+            graph.nodes.push((label, Shape::Receivers));
+            let kind = match entry.response {
+                Some(error) => format!("{} ({})", entry.kind, error),
+                None => entry.kind.to_string(),
+            };
+            graph.edges.push((message, receivers, kind));
+        }
+        graph
+    }
+
+    /// The node for a type, added if it isn't in the graph yet.
+    fn type_node(&mut self, name: &str) -> usize {
+        let existing = self
+            .nodes
+            .iter()
+            .position(|(label, shape)| matches!(shape, Shape::Type) && label == name);
+        existing.unwrap_or_else(|| {
+            self.nodes.push((name.to_string(), Shape::Type));
+            self.nodes.len() - 1
+        })
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+impl Description {
+    /// Renders the entries as a Graphviz graph, see [`crate::graph`].
+    pub fn to_dot(&self) -> String {
+        let graph = Graph::new(self);
+        let mut dot = String::from("digraph mediator {\n    rankdir=LR;\n");
+        for (id, (label, shape)) in graph.nodes.iter().enumerate() {
+            let shape = match shape {
+                Shape::Type => "box",
+                Shape::Receivers => "ellipse",
+            };
+            let _ = writeln!(
+                dot,
+                "    n{} [label=\"{}\", shape={}];",
+                id,
+                escape_dot(label),
+                shape
+            );
+        }
+        for (from, to, label) in &graph.edges {
+            let _ = writeln!(
+                dot,
+                "    n{} -> n{} [label=\"{}\"];",
+                from,
+                to,
+                escape_dot(label)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the entries as a Mermaid flowchart, see [`crate::graph`].
+    pub fn to_mermaid(&self) -> String {
+        let graph = Graph::new(self);
+        let mut mermaid = String::from("flowchart LR\n");
+        for (id, (label, shape)) in graph.nodes.iter().enumerate() {
+            let (open, close) = match shape {
+                Shape::Type => ("[", "]"),
+                Shape::Receivers => ("([", "])"),
+            };
+            let _ = writeln!(
+                mermaid,
+                "    n{}{}\"{}\"{}",
+                id,
+                open,
+                escape_mermaid(label),
+                close
+            );
+        }
+        for (from, to, label) in &graph.edges {
+            let _ = writeln!(
+                mermaid,
+                "    n{} -->|\"{}\"| n{}",
+                from,
+                escape_mermaid(label),
+                to
+            );
+        }
+        mermaid
+    }
+}
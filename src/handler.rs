@@ -0,0 +1,112 @@
+//! Struct-based handlers.
+//!
+//! Services that carry their own dependencies (clients, pools, configuration) can implement [`Handler`] or [`AsyncHandler`] instead of being wrapped into closures,
+//! and be registered with [`crate::mediator::MediatorBuilder::add_handler_struct`] or [`crate::mediator::MediatorBuilder::add_async_handler_struct`].
+//! A tuple of [`Handler`]s can be registered at once with [`crate::mediator::MediatorBuilder::add_handlers`].
+use crate::entry::RequestResponse;
+use crate::flavor::{Flavor, Local, Shared};
+use crate::hlist::{Cons, HList, NotContains};
+use crate::mediator::MediatorBuilder;
+
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+pub trait Handler<TMsg> {
+    type Response;
+
+    fn handle(&self, msg: TMsg) -> Self::Response;
+}
+
+/// An asynchronous handler.
+///
+/// The returned future can't borrow the handler, so clone whatever it needs out of `self` before moving it into the future.
+#[cfg(feature = "async")]
+pub trait AsyncHandler<TMsg> {
+    type Response;
+    type Future: Future<Output = Self::Response>;
+
+    fn handle(&self, msg: TMsg) -> Self::Future;
+}
+
+/// A tuple of [`Handler`]s, see [`crate::mediator::MediatorBuilder::add_handlers`].
+///
+/// `M` holds the message type and index of each handler, and is inferred, so each struct must implement [`Handler`] for a single message type.
+pub trait Handlers<H, N, S: Flavor, M> {
+    type Output: HList;
+
+    fn register(self, builder: MediatorBuilder<H, N, S>) -> MediatorBuilder<Self::Output, N, S>;
+}
+
+impl<H: HList, N, S: Flavor> Handlers<H, N, S, ()> for () {
+    type Output = H;
+
+    fn register(self, builder: MediatorBuilder<H, N, S>) -> MediatorBuilder<H, N, S> {
+        builder
+    }
+}
+
+macro_rules! tuple_handlers {
+    ($flavor:ident [$($bound:tt)*]) => {};
+    (
+        $flavor:ident [$($bound:tt)*]
+        $head:ident $msg:ident $index:ident $(, $tail:ident $tmsg:ident $tindex:ident)*
+    ) => {
+        impl<H, N, $head, $msg, $index $(, $tail, $tmsg, $tindex)*>
+            Handlers<H, N, $flavor, (($msg, $index), $(($tmsg, $tindex),)*)> for ($head, $($tail,)*)
+        where
+            H: HList + NotContains<RequestResponse<$msg, <$head as Handler<$msg>>::Response, $flavor>, $index>,
+            N: HList,
+            $head: Handler<$msg> + $($bound)*,
+            ($($tail,)*): Handlers<
+                Cons<RequestResponse<$msg, <$head as Handler<$msg>>::Response, $flavor>, H>,
+                N,
+                $flavor,
+                ($(($tmsg, $tindex),)*),
+            >,
+        {
+            type Output = <($($tail,)*) as Handlers<
+                Cons<RequestResponse<$msg, <$head as Handler<$msg>>::Response, $flavor>, H>,
+                N,
+                $flavor,
+                ($(($tmsg, $tindex),)*),
+            >>::Output;
+
+            #[allow(non_snake_case)]
+            fn register(
+                self,
+                builder: MediatorBuilder<H, N, $flavor>,
+            ) -> MediatorBuilder<Self::Output, N, $flavor> {
+                let ($head, $($tail,)*) = self;
+                ($($tail,)*).register(builder.add_handler_struct($head))
+            }
+        }
+        tuple_handlers!($flavor [$($bound)*] $($tail $tmsg $tindex),*);
+    };
+}
+
+tuple_handlers!(
+    Local ['static]
+    T0 M0 I0, T1 M1 I1, T2 M2 I2, T3 M3 I3, T4 M4 I4, T5 M5 I5,
+    T6 M6 I6, T7 M7 I7, T8 M8 I8, T9 M9 I9, T10 M10 I10, T11 M11 I11
+);
+tuple_handlers!(
+    Shared [Send + Sync + 'static]
+    T0 M0 I0, T1 M1 I1, T2 M2 I2, T3 M3 I3, T4 M4 I4, T5 M5 I5,
+    T6 M6 I6, T7 M7 I7, T8 M8 I8, T9 M9 I9, T10 M10 I10, T11 M11 I11
+);
+
+/// The error returned by a handler registered with [`crate::mediator::MediatorBuilder::add_once_handler`] after its first call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyHandled;
+
+impl fmt::Display for AlreadyHandled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the one-shot handler was already called")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for AlreadyHandled {}
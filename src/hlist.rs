@@ -1,9 +1,9 @@
 pub trait HList {}
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct Nil;
 impl HList for Nil {}
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct Cons<T, Tail>(T, Tail);
 impl<T, Tail: HList> HList for Cons<T, Tail> {}
 
@@ -17,12 +17,325 @@ impl<H: HList> HListExt for H {
     }
 }
 
+/// A function which can be applied to values of type `T`, for any `T` it is implemented for.
+///
+/// Used with [`HMap`], which applies it to each element of a list.
+pub trait Poly<T> {
+    type Output;
+
+    fn call(&mut self, t: T) -> Self::Output;
+}
+
+/// Applies a [`Poly`] function to each element of a list, producing a list of the results.
+pub trait HMap<F>: HList {
+    type Output: HList;
+
+    fn map(self, f: &mut F) -> Self::Output;
+}
+
+impl<F> HMap<F> for Nil {
+    type Output = Nil;
+
+    fn map(self, _f: &mut F) -> Nil {
+        Nil
+    }
+}
+
+impl<T, Tail: HMap<F>, F: Poly<T>> HMap<F> for Cons<T, Tail> {
+    type Output = Cons<F::Output, Tail::Output>;
+
+    fn map(self, f: &mut F) -> Self::Output {
+        let head = f.call(self.0);
+        Cons(head, self.1.map(f))
+    }
+}
+
+/// A step of a fold, combining an accumulator of type `Acc` with a value of type `T`.
+pub trait Folder<Acc, T> {
+    type Output;
+
+    fn fold(&mut self, acc: Acc, t: T) -> Self::Output;
+}
+
+/// Folds each element of a list into an accumulator, from head to tail.
+///
+/// The type of the accumulator can change at each step.
+pub trait HFold<F, Acc>: HList {
+    type Output;
+
+    fn fold(self, acc: Acc, f: &mut F) -> Self::Output;
+}
+
+impl<F, Acc> HFold<F, Acc> for Nil {
+    type Output = Acc;
+
+    fn fold(self, acc: Acc, _f: &mut F) -> Acc {
+        acc
+    }
+}
+
+impl<T, Tail, F, Acc> HFold<F, Acc> for Cons<T, Tail>
+where
+    F: Folder<Acc, T>,
+    Tail: HFold<F, F::Output>,
+{
+    type Output = Tail::Output;
+
+    fn fold(self, acc: Acc, f: &mut F) -> Self::Output {
+        let acc = f.fold(acc, self.0);
+        self.1.fold(acc, f)
+    }
+}
+
+/// Inspects values of type `T` by reference.
+pub trait Visitor<T: ?Sized> {
+    fn visit(&mut self, t: &T);
+}
+
+/// Calls a [`Visitor`] on each element of a list by reference, from head to tail.
+///
+/// Unlike [`HMap`] and [`HFold`], this doesn't consume the list, so it can be used on the lists of a built mediator.
+pub trait HVisit<V>: HList {
+    fn accept(&self, visitor: &mut V);
+}
+
+impl<V> HVisit<V> for Nil {
+    fn accept(&self, _visitor: &mut V) {}
+}
+
+impl<T, Tail: HVisit<V>, V: Visitor<T>> HVisit<V> for Cons<T, Tail> {
+    fn accept(&self, visitor: &mut V) {
+        visitor.visit(&self.0);
+        self.1.accept(visitor);
+    }
+}
+
+/// Appends another type-level list to the end of this one.
+pub trait Concat<Rhs: HList>: HList {
+    type Output: HList;
+
+    fn concat(self, rhs: Rhs) -> Self::Output;
+}
+
+impl<Rhs: HList> Concat<Rhs> for Nil {
+    type Output = Rhs;
+
+    fn concat(self, rhs: Rhs) -> Rhs {
+        rhs
+    }
+}
+
+impl<T, Tail: Concat<Rhs>, Rhs: HList> Concat<Rhs> for Cons<T, Tail> {
+    type Output = Cons<T, Tail::Output>;
+
+    fn concat(self, rhs: Rhs) -> Self::Output {
+        Cons(self.0, self.1.concat(rhs))
+    }
+}
+
+/// Replaces the element of type `T` at index `I` of a list with a `U` computed from it.
+pub trait Replace<T, U, I>: HList {
+    type Output: HList;
+
+    fn replace(self, f: impl FnOnce(T) -> U) -> Self::Output;
+}
+
+impl<T, U, Tail: HList> Replace<T, U, Z> for Cons<T, Tail> {
+    type Output = Cons<U, Tail>;
+
+    fn replace(self, f: impl FnOnce(T) -> U) -> Self::Output {
+        Cons(f(self.0), self.1)
+    }
+}
+
+impl<T, U, Head, Tail: Replace<T, U, I>, I> Replace<T, U, Succ<I>> for Cons<Head, Tail> {
+    type Output = Cons<Head, Tail::Output>;
+
+    fn replace(self, f: impl FnOnce(T) -> U) -> Self::Output {
+        Cons(self.0, self.1.replace(f))
+    }
+}
+
+/// Removes the element of type `T` at index `I` from a list, returning it along with the rest of the list.
+pub trait Pluck<T, I>: HList {
+    type Remainder: HList;
+
+    fn pluck(self) -> (T, Self::Remainder);
+}
+
+impl<T, Tail: HList> Pluck<T, Z> for Cons<T, Tail> {
+    type Remainder = Tail;
+
+    fn pluck(self) -> (T, Tail) {
+        (self.0, self.1)
+    }
+}
+
+impl<T, Head, Tail: Pluck<T, I>, I> Pluck<T, Succ<I>> for Cons<Head, Tail> {
+    type Remainder = Cons<Head, Tail::Remainder>;
+
+    fn pluck(self) -> (T, Self::Remainder) {
+        let (t, rest) = self.1.pluck();
+        (t, Cons(self.0, rest))
+    }
+}
+
+/// Implemented when a list doesn't already contain a `T`.
+///
+/// `I` is always [`Z`] when the bound holds. If the list already contains a `T`, `I` can't be inferred,
+/// which turns registering a duplicate into a compile error.
+/// ```rust,compile_fail
+/// use noon::mediator::MediatorBuilder;
+///
+/// // Compile-time error, there's already a handler from `i32` to `i32`.
+/// let mediator = MediatorBuilder::new()
+///     .add_handler(|x: i32| x)
+///     .add_handler(|x: i32| x + 1)
+///     .build();
+/// ```
+/// Naming the index of the existing `T` doesn't get around it either, since `I` can only be [`Z`].
+/// ```rust,compile_fail
+/// use noon::hlist::{Succ, Z};
+/// use noon::mediator::MediatorBuilder;
+///
+/// // Compile-time error, `Succ<Z>` isn't the index of the new handler.
+/// let mediator = MediatorBuilder::new()
+///     .add_handler(|x: i32| x)
+///     .add_handler::<i32, i32, Succ<Z>, _>(|x: i32| x + 1)
+///     .build();
+/// ```
+/// The same goes for fallible handlers, which are told apart by their error type as well.
+/// ```rust,compile_fail
+/// use noon::mediator::MediatorBuilder;
+///
+/// // Compile-time error, there's already a fallible handler from `i32` to `Result<i32, String>`.
+/// let mediator = MediatorBuilder::new()
+///     .add_try_handler(|x: i32| Ok::<_, String>(x))
+///     .add_try_handler(|x: i32| Ok::<_, String>(x + 1))
+///     .build();
+/// ```
+pub trait NotContains<T, I> {}
+
+impl<T, H: HList, I: sealed::Head> NotContains<T, I> for H where Cons<T, H>: ContainsAt<T, I> {}
+
+/// Implemented when a list shares no element type with `Rhs`, by each of its elements being [`NotContains`] in `Rhs`.
+///
+/// `I` holds the index of each of those bounds, and is always a list of [`Z`] when the bound holds.
+pub trait Disjoint<Rhs, I> {}
+
+impl<Rhs> Disjoint<Rhs, Nil> for Nil {}
+
+impl<T, Tail, Rhs, I, Is> Disjoint<Rhs, Cons<I, Is>> for Cons<T, Tail>
+where
+    Rhs: NotContains<T, I>,
+    Tail: Disjoint<Rhs, Is>,
+{
+}
+
+mod sealed {
+    /// Implemented only by [`super::Z`], so the index of [`super::NotContains`] can't be chosen to skip past an existing element.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be the index of a new registration",
+        note = "leave the index to be inferred, registering a duplicate is a compile-time error"
+    )]
+    pub trait Head {}
+
+    impl Head for super::Z {}
+}
+
+/// An empty balanced tree.
+///
+/// Lists can be converted into balanced trees with [`Balance`], which keeps the depth of lookups through [`ContainsAt`]
+/// logarithmic in the number of elements instead of linear.
+#[derive(Default, Clone, Copy)]
+pub struct Leaf;
+impl HList for Leaf {}
+
+/// A balanced tree holding a `T` between two subtrees.
+#[derive(Clone, Copy)]
+pub struct Node<L, T, R>(L, T, R);
+impl<L: HList, T, R: HList> HList for Node<L, T, R> {}
+
+/// Inserts an element into a balanced tree.
+///
+/// Trees are kept as [Braun trees](https://en.wikipedia.org/wiki/Braun_tree), whose left and right subtrees differ in size by at most one element.
+pub trait TreePush<T>: HList {
+    type Output: HList;
+
+    fn tree_push(self, t: T) -> Self::Output;
+}
+
+impl<T> TreePush<T> for Leaf {
+    type Output = Node<Leaf, T, Leaf>;
+
+    fn tree_push(self, t: T) -> Self::Output {
+        Node(Leaf, t, Leaf)
+    }
+}
+
+impl<T, L: HList, Y, R: TreePush<Y>> TreePush<T> for Node<L, Y, R> {
+    type Output = Node<R::Output, T, L>;
+
+    fn tree_push(self, t: T) -> Self::Output {
+        Node(self.2.tree_push(self.1), t, self.0)
+    }
+}
+
+/// Converts a list into a balanced tree holding the same elements.
+pub trait Balance: HList {
+    type Output: HList;
+
+    fn balance(self) -> Self::Output;
+}
+
+impl Balance for Nil {
+    type Output = Leaf;
+
+    fn balance(self) -> Leaf {
+        Leaf
+    }
+}
+
+impl<T, Tail: Balance> Balance for Cons<T, Tail>
+where
+    Tail::Output: TreePush<T>,
+{
+    type Output = <Tail::Output as TreePush<T>>::Output;
+
+    fn balance(self) -> Self::Output {
+        self.1.balance().tree_push(self.0)
+    }
+}
+
+impl<V> HVisit<V> for Leaf {
+    fn accept(&self, _visitor: &mut V) {}
+}
+
+/// Visits the element at the root of the tree, then each subtree.
+impl<L: HVisit<V>, T, R: HVisit<V>, V: Visitor<T>> HVisit<V> for Node<L, T, R> {
+    fn accept(&self, visitor: &mut V) {
+        visitor.visit(&self.1);
+        self.0.accept(visitor);
+        self.2.accept(visitor);
+    }
+}
+
+/// A type-level index into a list, counting from its head.
+///
+/// Indices are encoded as Peano numbers rather than `const` generics, since stepping from one `const` index to the next
+/// in a trait bound (`ContainsAt<T, { I + 1 }>`) requires the unstable `generic_const_exprs` feature.
 pub trait Index {}
 pub struct Z;
 pub struct Succ<T>(T);
 impl Index for Z {}
 impl<T: Index> Index for Succ<T> {}
 
+#[diagnostic::on_unimplemented(
+    message = "no `{T}` is registered with this mediator",
+    label = "this mediator has no `{T}`",
+    note = "handlers are registered with `MediatorBuilder::add_handler` and its variants, such as `add_async_handler` or `add_try_handler`",
+    note = "notification receivers are registered by calling `MediatorBuilder::listen_for` or `MediatorBuilder::listen_for_async` for the type of message"
+)]
 pub trait ContainsAt<T, I> {
     fn take(&self) -> &T;
     fn take_mut(&mut self) -> &mut T;
@@ -49,3 +362,40 @@ where
         self.1.take_mut()
     }
 }
+
+/// The index of the element at the root of a balanced tree.
+pub struct Here;
+/// An index into the left subtree of a balanced tree.
+pub struct InLeft<I>(I);
+/// An index into the right subtree of a balanced tree.
+pub struct InRight<I>(I);
+
+impl<L, T, R> ContainsAt<T, Here> for Node<L, T, R> {
+    fn take(&self) -> &T {
+        &self.1
+    }
+
+    fn take_mut(&mut self) -> &mut T {
+        &mut self.1
+    }
+}
+
+impl<L: ContainsAt<T, I>, Y, R, T, I> ContainsAt<T, InLeft<I>> for Node<L, Y, R> {
+    fn take(&self) -> &T {
+        self.0.take()
+    }
+
+    fn take_mut(&mut self) -> &mut T {
+        self.0.take_mut()
+    }
+}
+
+impl<L, Y, R: ContainsAt<T, I>, T, I> ContainsAt<T, InRight<I>> for Node<L, Y, R> {
+    fn take(&self) -> &T {
+        self.2.take()
+    }
+
+    fn take_mut(&mut self) -> &mut T {
+        self.2.take_mut()
+    }
+}
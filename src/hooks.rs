@@ -0,0 +1,267 @@
+//! Global dispatch hooks.
+//!
+//! Hooks registered with [`crate::mediator::MediatorBuilder::on_before_dispatch`] and [`crate::mediator::MediatorBuilder::on_after_dispatch`]
+//! run for every dispatch through the mediator, regardless of the type of message.
+//! They receive a [`Dispatch`] describing the call, which is useful for centralized logging and metrics.
+//!
+//! For asynchronous dispatches, before-dispatch hooks run when the future is created, and after-dispatch hooks run when it completes.
+//! For streaming dispatches, after-dispatch hooks run once the stream has been created.
+//!
+//! Hooks measuring how long each dispatch takes are installed with a [`crate::metrics::MetricsSink`].
+//!
+//! A panic hook registered with [`crate::mediator::MediatorBuilder::on_receiver_panic`] isolates notification receivers from each other's panics, see [`ReceiverPanic`].
+use crate::flavor::{Flavor, IntoReceiver, Local};
+
+use alloc::boxed::Box;
+#[cfg(feature = "async")]
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::any::Any;
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{ready, Context, Poll};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DispatchKind {
+    Handle,
+    HandleAsync,
+    TryHandle,
+    TryHandleAsync,
+    HandleStream,
+    Notify,
+    TryNotify,
+    TryNotifyEach,
+    NotifyOwned,
+    NotifyAsync,
+    NotifyShared,
+    NotifyBroadcast,
+    NotifyDetached,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dispatch {
+    pub kind: DispatchKind,
+    /// The type name of the message, see [`core::any::type_name`].
+    pub message: &'static str,
+    /// The type name of the response, for handler dispatches, of the items, for streaming dispatches, or of the errors, for fallible notifications.
+    pub response: Option<&'static str>,
+}
+
+impl Dispatch {
+    pub(crate) fn handle<TMsg: ?Sized, TResp>(kind: DispatchKind) -> Self {
+        Self {
+            kind,
+            message: core::any::type_name::<TMsg>(),
+            response: Some(core::any::type_name::<TResp>()),
+        }
+    }
+
+    pub(crate) fn notify<TMsg: ?Sized>(kind: DispatchKind) -> Self {
+        Self {
+            kind,
+            message: core::any::type_name::<TMsg>(),
+            response: None,
+        }
+    }
+}
+
+/// When a dispatch started, returned by [`DispatchHooks::before`] and passed back to [`DispatchHooks::after`].
+///
+/// The time is only read if there are hooks measuring how long dispatches take, which require the `std` feature.
+#[derive(Debug, Clone, Copy)]
+pub struct Started {
+    #[cfg(feature = "std")]
+    at: Option<Instant>,
+}
+
+/// The future of an asynchronous dispatch, which runs the after-dispatch hooks once `F` completes.
+///
+/// Returned by [`crate::mediator::Mediate::handle_async_future`], so the hooks run without boxing the handler's future again.
+#[cfg(feature = "async")]
+pub struct Dispatched<F, S: Flavor = Local> {
+    fut: F,
+    after: Option<(Arc<DispatchHooks<S>>, Dispatch, Started)>,
+}
+
+#[cfg(feature = "async")]
+impl<F, S: Flavor> Dispatched<F, S> {
+    pub(crate) fn new(
+        fut: F,
+        hooks: &Arc<DispatchHooks<S>>,
+        dispatch: Dispatch,
+        started: Started,
+    ) -> Self {
+        let after = hooks
+            .has_after()
+            .then(|| (Arc::clone(hooks), dispatch, started));
+        Self { fut, after }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<F: Future + Unpin, S: Flavor> Future for Dispatched<F, S> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let output = ready!(Pin::new(&mut self.fut).poll(cx));
+        if let Some((hooks, dispatch, started)) = self.after.take() {
+            hooks.after(&dispatch, started);
+        }
+        Poll::Ready(output)
+    }
+}
+
+/// A panic caught from a notification receiver.
+#[cfg(feature = "std")]
+pub struct ReceiverPanic {
+    pub dispatch: Dispatch,
+    payload: Box<dyn Any + Send>,
+}
+
+#[cfg(feature = "std")]
+impl ReceiverPanic {
+    pub(crate) fn new(dispatch: Dispatch, payload: Box<dyn Any + Send>) -> Self {
+        Self { dispatch, payload }
+    }
+
+    /// The message passed to `panic!`, if it was a string.
+    pub fn message(&self) -> Option<&str> {
+        self.payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| self.payload.downcast_ref::<String>().map(String::as_str))
+    }
+
+    /// The value the receiver panicked with, see [`std::panic::catch_unwind`].
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        &*self.payload
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for ReceiverPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceiverPanic")
+            .field("dispatch", &self.dispatch)
+            .field("message", &self.message())
+            .finish()
+    }
+}
+
+pub struct DispatchHooks<S: Flavor = Local> {
+    before: Vec<Box<S::Receiver<Dispatch>>>,
+    after: Vec<Box<S::Receiver<Dispatch>>>,
+    #[cfg(feature = "std")]
+    timed: Vec<Box<S::Receiver<(Dispatch, Duration)>>>,
+    #[cfg(feature = "std")]
+    on_panic: Option<Box<S::Receiver<ReceiverPanic>>>,
+}
+
+impl<S: Flavor> DispatchHooks<S> {
+    pub fn new() -> Self {
+        Self {
+            before: vec![],
+            after: vec![],
+            #[cfg(feature = "std")]
+            timed: vec![],
+            #[cfg(feature = "std")]
+            on_panic: None,
+        }
+    }
+
+    pub fn add_before(&mut self, f: impl IntoReceiver<S, Dispatch>) {
+        self.before.push(f.into_receiver())
+    }
+
+    pub fn add_after(&mut self, f: impl IntoReceiver<S, Dispatch>) {
+        self.after.push(f.into_receiver())
+    }
+
+    /// Adds a hook which runs after each dispatch with how long it took.
+    #[cfg(feature = "std")]
+    pub fn add_timed(&mut self, f: impl IntoReceiver<S, (Dispatch, Duration)>) {
+        self.timed.push(f.into_receiver())
+    }
+
+    /// Sets the hook for panics caught from notification receivers, replacing any previous one.
+    #[cfg(feature = "std")]
+    pub fn set_on_panic(&mut self, f: impl IntoReceiver<S, ReceiverPanic>) {
+        self.on_panic = Some(f.into_receiver())
+    }
+
+    /// Appends the hooks from `other`, which run after the hooks already registered.
+    ///
+    /// The panic hook from `other` is only used if this has none.
+    pub fn extend(&mut self, other: Self) {
+        self.before.extend(other.before);
+        self.after.extend(other.after);
+        #[cfg(feature = "std")]
+        {
+            self.timed.extend(other.timed);
+            if self.on_panic.is_none() {
+                self.on_panic = other.on_panic;
+            }
+        }
+    }
+
+    /// Whether notification receivers should be isolated from each other's panics.
+    #[cfg(feature = "std")]
+    pub fn isolates_panics(&self) -> bool {
+        self.on_panic.is_some()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn panicked(&self, panic: &ReceiverPanic) {
+        if let Some(hook) = &self.on_panic {
+            hook(panic);
+        }
+    }
+
+    pub fn has_after(&self) -> bool {
+        #[cfg(feature = "std")]
+        if !self.timed.is_empty() {
+            return true;
+        }
+        !self.after.is_empty()
+    }
+
+    pub fn before(&self, dispatch: &Dispatch) -> Started {
+        for hook in &self.before {
+            hook(dispatch);
+        }
+        Started {
+            #[cfg(feature = "std")]
+            at: (!self.timed.is_empty()).then(Instant::now),
+        }
+    }
+
+    pub fn after(&self, dispatch: &Dispatch, started: Started) {
+        for hook in &self.after {
+            hook(dispatch);
+        }
+        #[cfg(feature = "std")]
+        if let Some(started) = started.at {
+            let timed = (*dispatch, started.elapsed());
+            for hook in &self.timed {
+                hook(&timed);
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = started;
+    }
+}
+
+impl<S: Flavor> Default for DispatchHooks<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
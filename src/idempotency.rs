@@ -0,0 +1,162 @@
+//! Deduplicating messages by an idempotency key.
+//!
+//! Transports which deliver messages at least once can deliver the same message twice.
+//! A handler registered with [`MediatorBuilder::add_idempotent_handler`] extracts a key from each message,
+//! and remembers the response for each key in an [`IdempotencyStore`], so a redelivered message returns the remembered response
+//! without calling the handler again. A receiver registered with [`MediatorBuilder::add_idempotent_notification_receiver`]
+//! similarly skips the notifications whose key it has already seen.
+//! ```rust
+//! use noon::idempotency::MemoryIdempotencyStore;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! struct Charge { request_id: u64, amount: u32 }
+//!
+//! let charges = Rc::new(Cell::new(0));
+//! let charged = charges.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .add_idempotent_handler(
+//!         |req: &Charge| req.request_id,
+//!         MemoryIdempotencyStore::new(),
+//!         move |req: Charge| {
+//!             charged.set(charged.get() + 1);
+//!             format!("charged {}", req.amount)
+//!         },
+//!     )
+//!     .build();
+//!
+//! let first = mediator.handle::<_, String, _>(Charge { request_id: 1, amount: 5 });
+//! let retried = mediator.handle::<_, String, _>(Charge { request_id: 1, amount: 5 });
+//! assert_eq!(first, retried);
+//! assert_eq!(charges.get(), 1);
+//! ```
+//!
+//! Stores check and remember each key in one step with [`IdempotencyStore::get_or_insert_with`].
+//! With a [`MemoryIdempotencyStore`], a duplicate dispatched from another thread while the first is still running waits for it,
+//! so even [`Shared`](crate::flavor::Shared) mediators call the handler once per key. A handler dispatching its own key deadlocks.
+//! Stores relying on the default implementation don't wait, and concurrent duplicates may each call the handler.
+//! Messages with the same key are assumed to be the same message, whatever their other fields.
+//!
+//! [`MediatorBuilder::add_idempotent_handler`]: crate::mediator::MediatorBuilder::add_idempotent_handler
+//! [`MediatorBuilder::add_idempotent_notification_receiver`]: crate::mediator::MediatorBuilder::add_idempotent_notification_receiver
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Remembers what was returned for each idempotency key, see [`crate::idempotency`].
+///
+/// Stores backed by a database or a cache let keys survive restarts and be shared between processes.
+pub trait IdempotencyStore<K, V> {
+    fn get(&self, key: &K) -> Option<V>;
+
+    fn insert(&self, key: K, value: V);
+
+    /// Returns the value remembered for `key`, or remembers and returns the value of `f`.
+    ///
+    /// The default implementation calls [`IdempotencyStore::get`] and then [`IdempotencyStore::insert`], so concurrent calls
+    /// with the same key may each call `f`. Stores which can lock a key should override it, so `f` runs once per key.
+    fn get_or_insert_with<F: FnOnce() -> V>(&self, key: K, f: F) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f();
+        self.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K, V, T: IdempotencyStore<K, V> + ?Sized> IdempotencyStore<K, V> for Rc<T> {
+    fn get(&self, key: &K) -> Option<V> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: K, value: V) {
+        (**self).insert(key, value)
+    }
+    fn get_or_insert_with<F: FnOnce() -> V>(&self, key: K, f: F) -> V
+    where
+        V: Clone,
+    {
+        (**self).get_or_insert_with(key, f)
+    }
+}
+
+impl<K, V, T: IdempotencyStore<K, V> + ?Sized> IdempotencyStore<K, V> for Arc<T> {
+    fn get(&self, key: &K) -> Option<V> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: K, value: V) {
+        (**self).insert(key, value)
+    }
+    fn get_or_insert_with<F: FnOnce() -> V>(&self, key: K, f: F) -> V
+    where
+        V: Clone,
+    {
+        (**self).get_or_insert_with(key, f)
+    }
+}
+
+/// An idempotency store holding every key in memory, which is never pruned.
+///
+/// [`IdempotencyStore::get_or_insert_with`] makes concurrent calls with the same key wait for the first one,
+/// without blocking calls with other keys.
+pub struct MemoryIdempotencyStore<K, V = ()> {
+    /// The slot of a key is shared by the calls with that key while its value is computed.
+    entries: Mutex<HashMap<K, Arc<OnceLock<V>>>>,
+}
+
+impl<K, V> MemoryIdempotencyStore<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The number of keys with a remembered value.
+    pub fn len(&self) -> usize {
+        let entries = self.entries.lock().unwrap();
+        entries.values().filter(|slot| slot.get().is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, V> Default for MemoryIdempotencyStore<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> IdempotencyStore<K, V> for MemoryIdempotencyStore<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(key).and_then(|slot| slot.get().cloned())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let slot = Arc::new(OnceLock::from(value));
+        self.entries.lock().unwrap().insert(key, slot);
+    }
+
+    fn get_or_insert_with<F: FnOnce() -> V>(&self, key: K, f: F) -> V {
+        let slot = Arc::clone(self.entries.lock().unwrap().entry(key).or_default());
+        slot.get_or_init(f).clone()
+    }
+}
+
+impl<K, V> fmt::Debug for MemoryIdempotencyStore<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryIdempotencyStore")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,97 @@
+//! Feeding a stream of external events to a mediator, behind the `ingest` feature.
+//!
+//! [`Mediate::ingest`] notifies the asynchronous receivers for every item of a [`Stream`], such as the messages from a websocket or a file watcher.
+//! Each notification completes before the next item is polled, so a stream which produces items faster than they're received is held back.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::stream;
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! # use std::sync::Arc;
+//! # use std::task::{Context, Poll, Wake, Waker};
+//! # struct Noop;
+//! # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+//! # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+//! #     let waker = Waker::from(Arc::new(Noop));
+//! #     let mut fut = std::pin::pin!(fut);
+//! #     loop {
+//! #         if let Poll::Ready(out) = fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+//! #             return out;
+//! #         }
+//! #     }
+//! # }
+//!
+//! let total = Rc::new(Cell::new(0));
+//! let counted = total.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .listen_for_async::<u32>()
+//!     .add_async_notification_receiver(move |x: u32| {
+//!         counted.set(counted.get() + x);
+//!         async {}
+//!     })
+//!     .build();
+//! assert_eq!(block_on(mediator.ingest(stream::iter([1, 2, 3]))), 3);
+//! assert_eq!(total.get(), 6);
+//! ```
+//!
+//! [`Mediate::ingest`]: crate::mediator::Mediate::ingest
+use crate::entry::ReceiveNotificationAsync;
+use crate::flavor::{BoxFuture, Flavor};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+use crate::stream::Stream;
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// Resolves to the number of items notified once the stream is exhausted, see [`crate::ingest`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Ingest<'a, M: ?Sized, St, I, S: Flavor> {
+    mediator: &'a M,
+    stream: St,
+    notifying: Option<BoxFuture<'a, (), S>>,
+    notified: usize,
+    _index: PhantomData<fn() -> I>,
+}
+
+impl<'a, M: ?Sized, St, I, S: Flavor> Ingest<'a, M, St, I, S> {
+    pub(crate) fn new(mediator: &'a M, stream: St) -> Self {
+        Self {
+            mediator,
+            stream,
+            notifying: None,
+            notified: 0,
+            _index: PhantomData,
+        }
+    }
+}
+
+impl<'a, M, St, I, S> Future for Ingest<'a, M, St, I, S>
+where
+    M: Mediate<S> + ?Sized,
+    S: Flavor,
+    St: Stream + Unpin,
+    St::Item: Clone + 'static,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<St::Item, S>, I>,
+{
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        let this = self.get_mut();
+        loop {
+            if let Some(notifying) = &mut this.notifying {
+                ready!(notifying.as_mut().poll(cx));
+                this.notifying = None;
+            }
+            match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(msg) => {
+                    this.notifying = Some(this.mediator.notify_async(msg));
+                    this.notified += 1;
+                }
+                None => return Poll::Ready(this.notified),
+            }
+        }
+    }
+}
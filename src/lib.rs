@@ -14,12 +14,23 @@
 //!
 //! **Handlers** are used to perform some behaviour when presented a message of type `TMsg` and produce a response of type `TResp`.
 //! A mediator typically has a single handler for a given type of message.
+//! Handlers producing a sequence of responses can be registered as streaming handlers, which return a [`stream::Stream`] and are invoked through [`mediator::Mediate::handle_stream`].
+//! Message types implementing [`message::Message`] declare their response type, so their handlers can be invoked through [`mediator::Mediate::send`] without annotating it.
+//! Handlers that can fail should be registered as fallible handlers, which return a `Result<TResp, TErr>` and are invoked through [`mediator::Mediate::try_handle`].
 //!
 //! **Notification receivers** are used to perform some behaviour when presented a message of type `TMsg` without returning anything in response.
 //! A mediator might have multiple notification receivers for a given type of message, all of which are called in sequence whenever a message of that type is presented to the mediator.
 //!
 //! In noon, both handlers are notification receivers may be either synchronous or asynchronous.
 //!
+//! Handlers can additionally be wrapped by **pipeline behaviors**, which run before and after the handler for a given type of message, see the [`pipeline`] module.
+//! Request-scoped data can be passed along with a message in a [`context::Context`].
+//!
+//! ## Thread safety
+//! Mediators created with [`mediator::MediatorBuilder::new`] accept any `'static` receiver, but can't be shared between threads.
+//! Mediators created with [`mediator::MediatorBuilder::new_shared`] require every receiver to be `Send + Sync` (and every asynchronous receiver to produce `Send` futures), and are themselves `Send + Sync`.
+//! Shared mediators implement [`mediator::Mediate<flavor::Shared>`], see the [`flavor`] module.
+//!
 //! The types of messages that can be presented to the mediator are part of the mediator's type, including whether a type of message is able to be used to invoke a handler, send notifications, or both. Likewise, whether the receivers for a type of message are synchronous, asynchronous, or both is also tracked as part of the mediator's type.
 //!
 //! ## Conventions
@@ -38,6 +49,8 @@
 //! ## Usage with generic constraints
 //! If you'd like to use a generic mediator and place constraints on the kinds of receivers it must contain, you're likely looking to require the [`hlist::ContainsAt<T, I>`] trait on the [`mediator::Mediate`]'s associated `Handlers` or `NotificationReceivers` associated types.
 //!
+//! The traits in the [`spec`] module express the same constraints without naming the entry types, and [`mediator_spec!`] groups several of them into a single trait.
+//!
 //! These type-level lists implement different traits with generics populated from the [`entry`] module depending on the receivers that are registered with the mediator.
 //! For example, a mediator with a synchronous handler accepting a `NewUserRequest` and producing a `NewUserResponse` would have an [`entry::RequestResponse<NewUserRequest,NewUserResponse>`] in its associated `Handlers` type-level list. Concretely, this means the associated `Handlers` type implements [`hlist::ContainsAt<entry:RequestResponse<NewUserRequest,NewUserResponse>, I>`] for some `I`.
 //! ## Example
@@ -55,6 +68,7 @@
 //! struct NewUserMessage { id: i32 }
 //! struct SendUserEmail { id: i32, msg: String };
 //!
+//! # #[cfg(feature = "async")]
 //! async fn foo() {
 //!     let mediator = MediatorBuilder::new()
 //!         .add_handler(|req: NewUserMessage| {
@@ -74,6 +88,16 @@
 //!     }).await;
 //! }
 //! ```
+//! Asynchronous handlers and receivers, and the modules built on them, are behind the `async` feature, which is enabled by default.
+//! Everything else needing the standard library, such as clocks, threads, panic isolation and the modules built on them,
+//! is behind the `std` feature, also enabled by default and implied by `async`.
+//! Applications which only dispatch synchronously can use `default-features = false, features = ["std"]` for a leaner build.
+//!
+//! Without `std`, noon is `no_std` and only needs `alloc`: the builder, the mediator, and its synchronous handlers and receivers still work,
+//! so firmware can route messages through them.
+//!
+//! Long lists of registrations can also be written declaratively with the [`mediator!`] macro.
+//!
 //! You can't ask a mediator to handle a message it doesn't have a receiver for.
 //! ```rust,compile_fail
 //! use noon::mediator::{Mediate, MediatorBuilder}
@@ -85,6 +109,7 @@
 //! // Compile-time error, this mediator has no handler for that message.
 //! mediator.handle(NewUserMessage { id: 10 });
 //! ```
+//! Likewise, registering two handlers for the same message and response types is a compile-time error, see [`hlist::NotContains`].
 //! You can register multiple notification receivers for a single message type
 //! ```rust
 //! use noon::mediator::{Mediate, MediatorBuilder};
@@ -106,10 +131,179 @@
 //! // prints both messages in sequence
 //! mediator.notify(&NewUserMessage { id: 5 });
 //! ```
+//! Where the types of messages are only known at runtime, a mediator can be used as a trait object through [`erased::DynMediate`],
+//! or its receivers can be registered at runtime with a [`dynamic::DynamicMediator`].
+//! ## Web frameworks
+//! A shared mediator behind an [`std::sync::Arc`] can be used as the state of a web framework such as `axum`,
+//! since dispatching only needs a shared reference and the futures of shared mediators are `Send`.
+//! noon has no integration of its own: each route handler extracts the request, dispatches it, and converts the response.
+//! The built mediator's type can't be written out, so the router is built by a function generic over it,
+//! whose capabilities are required through [`spec`].
+//! ```rust,ignore
+//! use axum::{extract::State, routing::post, Json, Router};
+//! use noon::flavor::Shared;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::spec::CanHandleAsync;
+//! use serde::{Deserialize, Serialize};
+//! use std::sync::Arc;
+//!
+//! #[derive(Deserialize)]
+//! struct CreateUser { name: String }
+//!
+//! #[derive(Serialize)]
+//! struct UserCreated { id: u64 }
+//!
+//! fn app<M, I>(mediator: Arc<M>) -> Router
+//! where
+//!     M: CanHandleAsync<CreateUser, UserCreated, I, Shared> + Send + Sync + 'static,
+//!     I: 'static,
+//! {
+//!     Router::new()
+//!         .route(
+//!             "/users",
+//!             post(|State(mediator): State<Arc<M>>, Json(req): Json<CreateUser>| async move {
+//!                 Json(mediator.handle_async::<_, UserCreated, I>(req).await)
+//!             }),
+//!         )
+//!         .with_state(mediator)
+//! }
+//!
+//! let mediator = MediatorBuilder::new_shared()
+//!     .add_async_handler(|req: CreateUser| async move { UserCreated { id: req.name.len() as u64 } })
+//!     .build();
+//! let app = app(Arc::new(mediator));
+//! ```
+//! Request-scoped data, such as the authenticated user, can be passed to the handlers through [`mediator::Mediate::scope`].
+//! ## Runtimes
+//! noon doesn't depend on an async runtime. The features which need to wait or to run work in the background bring their own threads:
+//! timeouts, retries and debouncing wait on the timer thread of `noon::time`, blocking handlers run on threads started by
+//! `noon::offload::spawn_blocking`, and recurring notifications run on a thread of their own.
+//! The futures returned by a mediator can be awaited, or spawned, on any executor, such as `async-std`.
+//! ```rust,ignore
+//! let mediator = Arc::new(
+//!     MediatorBuilder::new_shared()
+//!         .add_async_handler(|x: u32| async move { x * 2 })
+//!         .build(),
+//! );
+//! let task = async_std::task::spawn({
+//!     let mediator = Arc::clone(&mediator);
+//!     async move { mediator.handle_async::<u32, u32, _>(21).await }
+//! });
+//! assert_eq!(async_std::task::block_on(task), 42);
+//! ```
+//! Lightweight executors such as `smol` and `futures-executor` work the same way, for example `smol::block_on(mediator.handle_async(21))`
+//! or `futures::executor::block_on(mediator.notify_async(event))`. Tools which don't want an executor at all can enable the `blocking` feature,
+//! whose `noon::blocking::block_on` and `noon::blocking::spawn` drive futures on plain threads.
+//! Detached and recurring notifications can also be run as tasks of an executor, through the traits of [`runtime`].
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+// Lets the derive macros refer to `::noon` from within this crate's own tests.
+extern crate self as noon;
+
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod actor;
+#[cfg(feature = "std")]
+pub mod auth;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "std")]
+pub mod breaker;
+#[cfg(feature = "std")]
+pub mod broadcast;
+#[cfg(feature = "async")]
+pub mod buffered;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod codec;
 pub(crate) mod concrete;
+#[cfg(feature = "std")]
+pub mod context;
+#[cfg(feature = "std")]
+pub mod debounce;
+pub mod describe;
+#[cfg(feature = "std")]
+pub mod dynamic;
 pub mod entry;
+#[cfg(feature = "std")]
+pub mod envelope;
+#[cfg(feature = "std")]
+pub mod erased;
+#[cfg(feature = "std")]
+pub mod events;
+pub mod flavor;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "std")]
+pub mod global;
+#[cfg(feature = "std")]
+pub mod graph;
+pub mod handler;
 pub mod hlist;
+pub mod hooks;
+#[cfg(feature = "std")]
+pub mod idempotency;
+#[cfg(feature = "ingest")]
+pub mod ingest;
+#[cfg(feature = "std")]
+pub mod limit;
+mod lock;
+mod macros;
 pub mod mediator;
+pub mod message;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod mock;
+#[cfg(feature = "async")]
+pub mod offload;
+#[cfg(feature = "std")]
+pub mod outbox;
+#[cfg(feature = "async")]
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod recording;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "async")]
+pub mod replay;
+#[cfg(feature = "async")]
+pub mod retry;
+#[cfg(feature = "async")]
+pub mod runtime;
+#[cfg(feature = "std")]
+pub mod schedule;
+#[cfg(feature = "async")]
+pub mod service;
+#[cfg(feature = "std")]
+pub mod sink;
+pub mod spec;
+#[cfg(feature = "std")]
+pub mod spy;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "async")]
+pub mod subscription;
+#[cfg(feature = "async")]
+pub mod time;
+#[cfg(feature = "std")]
+pub mod transport;
+#[cfg(feature = "std")]
+pub mod unit;
+#[cfg(feature = "std")]
+pub mod validate;
+#[cfg(feature = "std")]
+pub mod view;
+
+/// Registers a free function as a handler, to be collected at link time, see [`registry`].
+///
+/// Requires the `registry` feature.
+#[cfg(feature = "registry")]
+pub use noon_derive::handler;
 
 #[cfg(test)]
 mod test {
@@ -118,6 +312,40 @@ mod test {
     use hlist::ContainsAt;
     use mediator::{Mediate, MediatorBuilder};
 
+    #[cfg(feature = "async")]
+    use std::future::Future;
+    #[cfg(feature = "async")]
+    use std::pin::pin;
+    use std::sync::Arc;
+    #[cfg(feature = "async")]
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread;
+    #[cfg(feature = "async")]
+    use std::thread::Thread;
+
+    #[cfg(feature = "async")]
+    struct ThreadWaker(Thread);
+
+    #[cfg(feature = "async")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<T>(fut: impl Future<Output = T>) -> T {
+        let mut fut = pin!(fut);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(t) => return t,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
     #[test]
     fn should_typecheck() {
         fn _typecheck<M, IntIndex, BoolIndex>(mediator: M)
@@ -166,29 +394,3439 @@ mod test {
         assert_eq!(val.load(Ordering::SeqCst), 1)
     }
 
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_send_messages() {
+        use message::Message;
+
+        struct Ping;
+        struct Lookup(&'static str);
+
+        impl Message for Ping {
+            type Response = &'static str;
+        }
+
+        impl Message for Lookup {
+            type Response = Option<usize>;
+        }
+
+        let mediator = MediatorBuilder::new()
+            .add_message_handler(|_: Ping| "pong")
+            .add_async_message_handler(|req: Lookup| async move { req.0.find('o') })
+            .add_handler(|_: Ping| 0)
+            .build();
+
+        assert_eq!(mediator.send(Ping), "pong");
+        assert_eq!(block_on(mediator.send_async(Lookup("noon"))), Some(1));
+    }
+
+    #[test]
+    fn should_traverse_hlists() {
+        use hlist::{Folder, HFold, HListExt, HMap, HVisit, Nil, Poly, Visitor};
+        use std::fmt::Debug;
+
+        struct Describe;
+
+        impl<T: Debug> Poly<T> for Describe {
+            type Output = String;
+
+            fn call(&mut self, t: T) -> String {
+                format!("{:?}", t)
+            }
+        }
+
+        struct Count;
+
+        impl<T> Folder<usize, T> for Count {
+            type Output = usize;
+
+            fn fold(&mut self, acc: usize, _t: T) -> usize {
+                acc + 1
+            }
+        }
+
+        struct Collect(Vec<String>);
+
+        impl<T: Debug> Visitor<T> for Collect {
+            fn visit(&mut self, t: &T) {
+                self.0.push(format!("{:?}", t));
+            }
+        }
+
+        let list = Nil.push(1).push("two").push(3.5);
+        let mut collect = Collect(vec![]);
+        list.accept(&mut collect);
+        assert_eq!(collect.0, ["3.5", "\"two\"", "1"]);
+
+        let described = list.map(&mut Describe);
+        assert_eq!(described.fold(0, &mut Count), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_build_balanced() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let count = Rc::new(Cell::new(0));
+
+        let receiver_count = Rc::clone(&count);
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i8| x)
+            .add_handler(|x: i16| x)
+            .add_handler(|x: i32| x)
+            .add_handler(|x: i64| x)
+            .add_async_handler(|x: u8| async move { x })
+            .add_try_handler(|x: u16| x.checked_sub(1).ok_or(()))
+            .listen_for::<str>()
+            .add_notification_receiver(move |_: &str| receiver_count.set(receiver_count.get() + 1))
+            .listen_for::<bool>()
+            .build_balanced();
+
+        assert_eq!(mediator.handle(1i8), 1);
+        assert_eq!(mediator.handle(2i16), 2);
+        assert_eq!(mediator.handle(3i32), 3);
+        assert_eq!(mediator.handle(4i64), 4);
+        assert_eq!(block_on(mediator.handle_async(5u8)), 5);
+        assert_eq!(mediator.try_handle(0u16), Err(()));
+        mediator.notify("hello");
+        mediator.notify(&true);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_merge_builders() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let count = Rc::new(Cell::new(0));
+
+        let (users_count, hook_count) = (Rc::clone(&count), Rc::clone(&count));
+        let users = MediatorBuilder::new()
+            .add_handler(|id: i32| id + 1)
+            .listen_for::<str>()
+            .add_notification_receiver(move |_: &str| users_count.set(users_count.get() + 1));
+        let billing = MediatorBuilder::new()
+            .add_async_handler(|cents: u64| async move { cents / 100 })
+            .on_before_dispatch(move |_: &hooks::Dispatch| hook_count.set(hook_count.get() + 10));
+
+        let mediator = users.merge(billing).build();
+        assert_eq!(mediator.handle(1), 2);
+        assert_eq!(block_on(mediator.handle_async(250u64)), 2);
+        mediator.notify("hello");
+        assert_eq!(count.get(), 31);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_expand_mediator_macro() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        fn count(_: &&'static str) {
+            COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mediator = mediator! {
+            shared;
+            handle i32 -> i32 = |x: i32| x * 2;
+            handle async u8 -> u8 = |x: u8| async move { x + 1 };
+            notify &'static str => [count, count];
+            notify async bool => [];
+        };
+
+        assert_eq!(mediator.handle(4), 8);
+        assert_eq!(block_on(mediator.handle_async(1u8)), 2);
+        mediator.notify(&"event");
+        block_on(mediator.notify_async(true));
+        assert_eq!(COUNT.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_check_specs() {
+        mediator_spec! {
+            trait Counter {
+                handle i32 -> i32;
+                handle async u8 -> u8;
+                notify str;
+                notify async bool;
+            }
+        }
+
+        fn exercise<M, I0, I1, I2, I3>(mediator: &M) -> (i32, u8)
+        where
+            M: Counter<I0, I1, I2, I3, flavor::Shared>,
+        {
+            mediator.notify("hello");
+            block_on(mediator.notify_async(true));
+            (mediator.handle(1), block_on(mediator.handle_async(2u8)))
+        }
+
+        let mediator = mediator! {
+            shared;
+            handle i32 -> i32 = |x: i32| x + 1;
+            handle async u8 -> u8 = |x: u8| async move { x * 2 };
+            notify str => [];
+            notify async bool => [];
+        };
+        assert_eq!(exercise(&mediator), (2, 4));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn should_derive_messages() {
+        use message::Message;
+
+        #[derive(Message)]
+        #[message(response = Vec<T>)]
+        struct Repeat<T: Clone = u8>(T, usize);
+
+        #[derive(Message)]
+        struct Shutdown;
+
+        #[derive(Message)]
+        #[message(response = usize)]
+        #[allow(dead_code)]
+        struct Apply<F: Fn() -> usize> {
+            f: F,
+        }
+
+        let mediator = MediatorBuilder::new()
+            .add_message_handler(|req: Repeat<char>| vec![req.0; req.1])
+            .add_message_handler(|_: Shutdown| {})
+            .build();
+
+        assert_eq!(mediator.send(Repeat('a', 2)), ['a', 'a']);
+        mediator.send(Shutdown);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_pass_context() {
+        use context::Context;
+
+        #[derive(Debug, PartialEq)]
+        struct Principal(&'static str);
+
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler(|(x, ctx): (i32, Context)| ctx.get::<Principal>().map(|p| (p.0, x)))
+            .add_pipeline(
+                |(x, ctx): (i32, Context),
+                 next: &dyn Fn((i32, Context)) -> Option<(&'static str, i32)>| {
+                    if ctx.contains::<Principal>() {
+                        next((x, ctx))
+                    } else {
+                        next((x, ctx.with(Principal("anonymous"))))
+                    }
+                },
+            )
+            .add_async_handler(|(x, ctx): (u8, Context)| async move { ctx.len() + x as usize })
+            .build();
+
+        let ctx = Context::new().with(Principal("admin"));
+        assert_eq!(mediator.handle_with_context(1, &ctx), Some(("admin", 1)));
+        assert_eq!(
+            mediator.handle_with_context(2, &Context::new()),
+            Some(("anonymous", 2))
+        );
+        assert_eq!(ctx.get(), Some(&Principal("admin")));
+        assert_eq!(block_on(mediator.handle_async_with_context(1u8, &ctx)), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_share_handler_state() {
+        struct Config {
+            greeting: String,
+        }
+
+        let config = Arc::new(Config {
+            greeting: "Hello".to_string(),
+        });
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler_with_state(Arc::clone(&config), |config: &Config, name: &str| {
+                format!("{}, {}!", config.greeting, name)
+            })
+            .add_async_handler_with_state(config, |config: Arc<Config>, n: usize| async move {
+                config.greeting.repeat(n)
+            })
+            .build();
+
+        assert_eq!(mediator.handle("noon"), "Hello, noon!");
+        assert_eq!(block_on(mediator.handle_async(2)), "HelloHello");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_register_mut() {
+        let mut total = 0;
+        let mut calls = 0;
+        let mediator = MediatorBuilder::new()
+            .add_handler_mut(move |x: i32| {
+                total += x;
+                total
+            })
+            .add_async_handler_mut(move |_x: bool| {
+                calls += 1;
+                async move { calls }
+            })
+            .build();
+        assert_eq!(mediator.handle(2), 2);
+        assert_eq!(mediator.handle(3), 5);
+        assert_eq!(block_on(mediator.handle_async(true)), 1);
+        assert_eq!(block_on(mediator.handle_async(true)), 2);
+
+        let mut seen = vec![];
+        let shared = MediatorBuilder::new_shared()
+            .add_handler_mut(move |x: &'static str| {
+                seen.push(x);
+                seen.len()
+            })
+            .build();
+        let shared = Arc::new(shared);
+        let thread_shared = Arc::clone(&shared);
+        thread::spawn(move || thread_shared.handle("a"))
+            .join()
+            .unwrap();
+        assert_eq!(shared.handle("b"), 2);
+    }
+
+    #[test]
+    fn should_keep_calling_mut_handlers_after_a_panic() {
+        let mut calls = 0;
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler_mut(move |fail: bool| {
+                calls += 1;
+                assert!(!fail, "handler failed");
+                calls
+            })
+            .build();
+        let failed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mediator.handle::<bool, u32, _>(true)
+        }));
+        assert!(failed.is_err());
+        assert_eq!(mediator.handle(false), 2);
+    }
+
+    #[test]
+    fn should_filter_notifications() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let val = Arc::new(AtomicUsize::new(0));
+
+        let receiver_val = Arc::clone(&val);
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<i32>()
+            .add_filtered_notification_receiver(
+                |x: &i32| *x > 0,
+                move |x: &i32| {
+                    receiver_val.fetch_add(*x as usize, Ordering::SeqCst);
+                },
+            )
+            .build();
+        mediator.notify(&2);
+        mediator.notify(&-5);
+        mediator.notify(&3);
+
+        assert_eq!(val.load(Ordering::SeqCst), 5);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_unsubscribe() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let builder_log = Rc::clone(&log);
+        let mediator = MediatorBuilder::new()
+            .listen_for::<i32>()
+            .add_notification_receiver(move |x: &i32| {
+                builder_log.borrow_mut().push(("builder", *x))
+            })
+            .listen_for_async::<bool>()
+            .build();
+
+        let runtime_log = Rc::clone(&log);
+        let id = mediator.subscribe(move |x: &i32| runtime_log.borrow_mut().push(("runtime", *x)));
+        mediator.notify(&1);
+        assert!(mediator.unsubscribe::<i32, _>(id));
+        assert!(!mediator.unsubscribe::<i32, _>(id));
+        mediator.notify(&2);
+        assert_eq!(
+            *log.borrow(),
+            [("builder", 1), ("runtime", 1), ("builder", 2)]
+        );
+
+        let async_log = Rc::clone(&log);
+        let id = mediator.subscribe_async(move |_x: bool| {
+            async_log.borrow_mut().push(("async", 0));
+            async {}
+        });
+        block_on(mediator.notify_async(true));
+        assert!(mediator.unsubscribe_async::<bool, _>(id));
+        block_on(mediator.notify_async(true));
+        assert_eq!(log.borrow().len(), 4);
+    }
+
+    #[test]
+    fn should_change_receivers_while_notifying() {
+        use entry::ReceiveNotification;
+        use std::cell::{Cell, RefCell};
+        use std::rc::Rc;
+
+        let receivers = ReceiveNotification::<u32>::new();
+        let log = Rc::new(RefCell::new(vec![]));
+        let id = Rc::new(Cell::new(None));
+        let (once_receivers, once_log, once_id) =
+            (receivers.clone(), Rc::clone(&log), Rc::clone(&id));
+        id.set(Some(receivers.add(move |x: &u32| {
+            once_log.borrow_mut().push(("once", *x));
+            once_receivers.remove(once_id.get().unwrap());
+            let added_log = Rc::clone(&once_log);
+            once_receivers.add(move |x: &u32| added_log.borrow_mut().push(("added", *x)));
+        })));
+
+        receivers.call(&1);
+        receivers.call(&2);
+        assert_eq!(*log.borrow(), [("once", 1), ("added", 2)]);
+        assert_eq!(receivers.receiver_count(), 1);
+    }
+
+    #[test]
+    fn should_handle_unboxed() {
+        let offset = 10;
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler_unboxed(move |x: i32| x + offset)
+            .add_handler(|x: i32| x)
+            .add_handler_unboxed(|x: bool| !x)
+            .build();
+
+        assert_eq!(mediator.handle_unboxed(1), 11);
+        assert_eq!(mediator.handle(1), 1);
+        assert!(mediator.handle_unboxed(false));
+    }
+
+    #[cfg(feature = "async")]
     #[test]
     fn should_register_async() {
-        let _ = async {
+        block_on(async {
             let mediator = MediatorBuilder::new()
                 .add_async_handler(|x: i32| async move { x })
                 .build();
             mediator.handle_async(5).await;
-        };
+        });
     }
 
+    #[cfg(feature = "async")]
     #[test]
     fn should_notify_async() {
-        let _ = async {
+        block_on(async {
             let mediator = MediatorBuilder::new()
                 .listen_for_async::<bool>()
                 .add_async_notification_receiver(|_x: bool| async move {})
                 .build();
             mediator.notify_async(true).await;
+        });
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_register_structs() {
+        use handler::{AsyncHandler, Handler};
+        use std::future::Ready;
+
+        struct Doubler {
+            factor: i32,
+        }
+
+        impl Handler<i32> for Doubler {
+            type Response = i32;
+
+            fn handle(&self, msg: i32) -> i32 {
+                msg * self.factor
+            }
+        }
+
+        struct Greeter {
+            greeting: Arc<String>,
+        }
+
+        impl AsyncHandler<&'static str> for Greeter {
+            type Response = String;
+            type Future = std::pin::Pin<Box<dyn Future<Output = String>>>;
+
+            fn handle(&self, msg: &'static str) -> Self::Future {
+                let greeting = Arc::clone(&self.greeting);
+                Box::pin(async move { format!("{}, {}!", greeting, msg) })
+            }
+        }
+
+        struct Negate;
+
+        impl AsyncHandler<bool> for Negate {
+            type Response = bool;
+            type Future = Ready<bool>;
+
+            fn handle(&self, msg: bool) -> Ready<bool> {
+                std::future::ready(!msg)
+            }
+        }
+
+        let mediator = MediatorBuilder::new()
+            .add_handler_struct(Doubler { factor: 2 })
+            .add_async_handler_struct(Greeter {
+                greeting: Arc::new("Hello".to_string()),
+            })
+            .build();
+        assert_eq!(mediator.handle(4), 8);
+        assert_eq!(block_on(mediator.handle_async("noon")), "Hello, noon!");
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler_struct(Doubler { factor: 3 })
+            .add_async_handler_struct(Negate)
+            .build();
+        assert_eq!(shared.handle(4), 12);
+        assert!(block_on(shared.handle_async(false)));
+    }
+
+    #[test]
+    fn should_run_pipelines() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let (outer_log, inner_log) = (Rc::clone(&log), Rc::clone(&log));
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x + 1)
+            .add_handler(|x: bool| !x)
+            .add_pipeline(move |x: i32, next: &dyn Fn(i32) -> i32| {
+                outer_log.borrow_mut().push("outer");
+                let resp = next(x * 10);
+                outer_log.borrow_mut().push("outer done");
+                resp
+            })
+            .add_pipeline(move |x: i32, next: &dyn Fn(i32) -> i32| {
+                inner_log.borrow_mut().push("inner");
+                if x < 0 {
+                    return 0;
+                }
+                next(x)
+            })
+            .build();
+
+        assert_eq!(mediator.handle(2), 21);
+        assert_eq!(*log.borrow(), ["outer", "inner", "outer done"]);
+        assert_eq!(mediator.handle(-2), 0);
+        assert!(mediator.handle(false));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_run_async_pipelines() {
+        use pipeline::NextAsync;
+
+        let mediator = MediatorBuilder::new_shared()
+            .add_async_handler(|x: i32| async move { x + 1 })
+            .add_async_pipeline(
+                |x: i32, next: NextAsync<i32, i32, flavor::Shared>| async move {
+                    next.call(x * 10).await * 2
+                },
+            )
+            .add_async_pipeline(
+                |x: i32, next: NextAsync<i32, i32, flavor::Shared>| async move {
+                    if x < 0 {
+                        0
+                    } else {
+                        next.call(x).await
+                    }
+                },
+            )
+            .build();
+
+        assert_eq!(block_on(mediator.handle_async(2)), 42);
+        assert_eq!(block_on(mediator.handle_async(-2)), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_try_handle() {
+        #[derive(Debug, PartialEq)]
+        struct Negative(i32);
+
+        let mediator = MediatorBuilder::new()
+            .add_try_handler(|x: i32| {
+                if x < 0 {
+                    Err(Negative(x))
+                } else {
+                    Ok(x as u32)
+                }
+            })
+            .add_async_try_handler(|x: u8| async move { x.checked_mul(2).ok_or("overflow") })
+            .build();
+
+        assert_eq!(mediator.try_handle(3), Ok(3u32));
+        assert_eq!(mediator.try_handle(-3), Err(Negative(-3)));
+        assert_eq!(block_on(mediator.try_handle_async(4u8)), Ok(8));
+        assert_eq!(block_on(mediator.try_handle_async(200u8)), Err("overflow"));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_handle_stream() {
+        use stream::StreamExt;
+
+        struct ListUsers {
+            page_size: usize,
+        }
+
+        let mediator = MediatorBuilder::new_shared()
+            .add_stream_handler(|req: ListUsers| {
+                stream::unfold(0, move |page| async move {
+                    let users: Vec<u32> = (1..=5)
+                        .skip(page * req.page_size)
+                        .take(req.page_size)
+                        .collect();
+                    (!users.is_empty()).then_some((users, page + 1))
+                })
+            })
+            .add_stream_handler(|x: u8| stream::iter(0..x))
+            .build();
+
+        let mut pages = mediator.handle_stream(ListUsers { page_size: 2 });
+        assert_eq!(block_on(pages.next()), Some(vec![1, 2]));
+        assert_eq!(block_on(pages.next()), Some(vec![3, 4]));
+        assert_eq!(block_on(pages.next()), Some(vec![5]));
+        assert_eq!(block_on(pages.next()), None);
+
+        let mut items = mediator.handle_stream(3u8);
+        let collected = block_on(async {
+            let mut collected = vec![];
+            while let Some(item) = items.next().await {
+                collected.push(item);
+            }
+            collected
+        });
+        assert_eq!(collected, [0, 1, 2]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_time_out() {
+        use std::time::Duration;
+        use time::{Delay, Elapsed};
+
+        let mediator = MediatorBuilder::new_shared()
+            .add_async_handler(|ms: u64| async move {
+                Delay::new(Duration::from_millis(ms)).await;
+                ms
+            })
+            .listen_for_async::<u64>()
+            .add_async_notification_receiver(|ms: u64| async move {
+                Delay::new(Duration::from_millis(ms)).await;
+            })
+            .build();
+
+        let timeout = Duration::from_millis(50);
+        assert_eq!(block_on(mediator.handle_async_timeout(1, timeout)), Ok(1));
+        assert_eq!(
+            block_on(mediator.handle_async_timeout(1000, timeout)),
+            Err(Elapsed)
+        );
+        assert_eq!(block_on(mediator.notify_async_timeout(1, timeout)), Ok(()));
+        assert_eq!(
+            block_on(mediator.notify_async_timeout(1000, timeout)),
+            Err(Elapsed)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_keep_one_waker_per_delay() {
+        use std::pin::Pin;
+        use std::time::Duration;
+        use time::Delay;
+
+        struct Noop;
+
+        impl Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let (first, second) = (Arc::new(Noop), Arc::new(Noop));
+        let (first_waker, second_waker) = (Waker::from(first.clone()), Waker::from(second.clone()));
+        let mut delay = Delay::new(Duration::from_secs(60));
+        for _ in 0..10 {
+            let _ = Pin::new(&mut delay).poll(&mut Context::from_waker(&first_waker));
+        }
+        assert_eq!(Arc::strong_count(&first), 3);
+        let _ = Pin::new(&mut delay).poll(&mut Context::from_waker(&second_waker));
+        assert_eq!(Arc::strong_count(&first), 2);
+        assert_eq!(Arc::strong_count(&second), 3);
+        drop(delay);
+        assert_eq!(Arc::strong_count(&second), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_run_dispatch_hooks() {
+        use hooks::{Dispatch, DispatchKind};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let log = Rc::new(RefCell::new(vec![]));
+
+        let (before_log, after_log) = (Rc::clone(&log), Rc::clone(&log));
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x)
+            .add_async_handler(|x: u8| async move { x })
+            .listen_for::<str>()
+            .on_before_dispatch(move |d: &Dispatch| before_log.borrow_mut().push(("before", *d)))
+            .on_after_dispatch(move |d: &Dispatch| after_log.borrow_mut().push(("after", *d)))
+            .build();
+
+        mediator.handle(1);
+        mediator.notify("hello");
+        let fut = mediator.handle_async(2u8);
+        assert_eq!(log.borrow().len(), 5);
+        block_on(fut);
+
+        let handle = Dispatch {
+            kind: DispatchKind::Handle,
+            message: "i32",
+            response: Some("i32"),
+        };
+        let notify = Dispatch {
+            kind: DispatchKind::Notify,
+            message: "str",
+            response: None,
         };
+        let handle_async = Dispatch {
+            kind: DispatchKind::HandleAsync,
+            message: "u8",
+            response: Some("u8"),
+        };
+        assert_eq!(
+            *log.borrow(),
+            [
+                ("before", handle),
+                ("after", handle),
+                ("before", notify),
+                ("after", notify),
+                ("before", handle_async),
+                ("after", handle_async),
+            ]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_run_dispatch_hooks_on_unboxed_futures() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        fn assert_send<T: Send>(t: T) -> T {
+            t
+        }
+
+        let after = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&after);
+        let mediator = MediatorBuilder::new_shared()
+            .add_async_handler(|x: u8| async move { x + 1 })
+            .on_after_dispatch(move |_: &hooks::Dispatch| {
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+            .build();
+
+        let fut = assert_send(mediator.handle_async_future(1u8));
+        assert_eq!(after.load(Ordering::SeqCst), 0);
+        assert_eq!(block_on(fut), 2);
+        assert_eq!(after.load(Ordering::SeqCst), 1);
+
+        let recording = recording::Recording::new(&mediator);
+        assert_eq!(block_on(recording.handle_async_future(2u8)), 3);
+        assert_eq!(after.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_clone_mediators() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let count = Rc::new(Cell::new(0));
+
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x + 1)
+            .add_async_handler(|x: u8| async move { x * 2 })
+            .listen_for::<str>()
+            .build_cloneable();
+        let clone = mediator.clone();
+
+        let receiver_count = Rc::clone(&count);
+        clone.subscribe(move |_: &str| receiver_count.set(receiver_count.get() + 1));
+        mediator.notify("hello");
+        assert_eq!(count.get(), 1);
+        assert_eq!(clone.handle(1), 2);
+        assert_eq!(block_on(clone.handle_async(2u8)), 4);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x * 2)
+            .build_arc();
+        let thread_shared = Arc::clone(&shared);
+        assert_eq!(
+            thread::spawn(move || thread_shared.handle(2))
+                .join()
+                .unwrap(),
+            4
+        );
+        assert_eq!(shared.handle(3), 6);
+    }
+
+    #[test]
+    fn should_mediate_through_pointers() {
+        fn double<M: Mediate>(mediator: M) -> i32
+        where
+            M::Handlers: ContainsAt<RequestResponse<i32, i32>, hlist::Z>,
+        {
+            mediator.handle(2) * 2
+        }
+
+        let mediator = MediatorBuilder::new().add_handler(|x: i32| x + 1).build();
+        assert_eq!(double(&mediator), 6);
+        let boxed = Box::new(mediator);
+        assert_eq!(double(&boxed), 6);
+        let rc = std::rc::Rc::new(*boxed);
+        assert_eq!(double(rc), 6);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x)
+            .build_arc();
+        fn shared_double<M: Mediate<flavor::Shared> + Send + 'static>(mediator: M) -> i32
+        where
+            M::Handlers: ContainsAt<RequestResponse<i32, i32, flavor::Shared>, hlist::Z>,
+        {
+            thread::spawn(move || mediator.handle(2) * 2)
+                .join()
+                .unwrap()
+        }
+        assert_eq!(shared_double(shared), 4);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_share_across_threads() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        fn assert_send<T: Send>(t: T) -> T {
+            t
+        }
+
+        let val = Arc::new(AtomicUsize::new(0));
+
+        let receiver_val = Arc::clone(&val);
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x * 2)
+            .add_async_handler(|x: u8| async move { x + 1 })
+            .listen_for_async::<bool>()
+            .add_async_notification_receiver(move |_x: bool| {
+                let receiver_val = Arc::clone(&receiver_val);
+                async move {
+                    receiver_val.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .build();
+        let mediator = Arc::new(mediator);
+
+        let thread_mediator = Arc::clone(&mediator);
+        let result = thread::spawn(move || {
+            let sync = thread_mediator.handle(21);
+            let fut = assert_send(thread_mediator.handle_async(1u8));
+            (sync, block_on(fut))
+        })
+        .join()
+        .unwrap();
+        assert_eq!(result, (42, 2));
+
+        block_on(assert_send(mediator.notify_async(true)));
+        assert_eq!(val.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_erase_mediators() {
+        use crate::erased::{DynMediate, Erased};
+        use std::any::{Any, TypeId};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(Cell::new(0));
+        let receiver_seen = Rc::clone(&seen);
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x * 2)
+            .add_handler(|s: String| s.len())
+            .add_async_handler(|x: u8| async move { x })
+            .listen_for::<u32>()
+            .add_notification_receiver(move |x: &u32| receiver_seen.set(*x))
+            .build();
+        let erased: Box<dyn DynMediate> = Box::new(Erased::new(mediator));
+
+        let resp = erased.handle_any(Box::new(21)).unwrap();
+        assert_eq!(*resp.downcast::<i32>().unwrap(), 42);
+        let resp = erased.handle_any(Box::new(String::from("noon"))).unwrap();
+        assert_eq!(*resp.downcast::<usize>().unwrap(), 4);
+
+        assert!(erased.can_handle(TypeId::of::<i32>()));
+        assert!(!erased.can_handle(TypeId::of::<u8>()));
+        let unhandled = erased.handle_any(Box::new(1u8)).unwrap_err();
+        assert_eq!(*unhandled.downcast::<u8>().unwrap(), 1);
+
+        assert!(erased.notify_any(&7u32));
+        assert!(!erased.notify_any(&7i64 as &dyn Any));
+        assert_eq!(seen.get(), 7);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x + 1)
+            .build();
+        let erased: Arc<dyn DynMediate + Send + Sync> = Arc::new(Erased::new(shared));
+        let resp = thread::spawn(move || {
+            let resp = erased.handle_any(Box::new(1)).unwrap();
+            *resp.downcast::<i32>().unwrap()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(resp, 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_register_dynamically() {
+        use crate::dynamic::DynamicMediator;
+        use crate::erased::DynMediate;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut mediator = DynamicMediator::new();
+        mediator
+            .add_handler(|x: i32| x * 2)
+            .add_handler(|x: i32| x.to_string())
+            .add_async_handler(|x: u8| async move { x + 1 });
+        assert_eq!(mediator.handle::<i32, i32>(21), Some(42));
+        assert_eq!(mediator.handle::<i32, String>(5), Some("5".to_string()));
+        assert_eq!(mediator.handle::<u8, u8>(1), None);
+        assert_eq!(block_on(mediator.handle_async::<u8, u8>(1).unwrap()), 2);
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let receiver_seen = Rc::clone(&seen);
+        let id =
+            mediator.add_notification_receiver(move |x: &u32| receiver_seen.borrow_mut().push(*x));
+        let async_seen = Rc::clone(&seen);
+        mediator.add_async_notification_receiver(move |x: u32| {
+            let async_seen = Rc::clone(&async_seen);
+            async move { async_seen.borrow_mut().push(x * 10) }
+        });
+        mediator.notify(&1u32);
+        block_on(mediator.notify_async(2u32));
+        mediator.notify(&"unheard");
+        assert!(mediator.unsubscribe::<u32>(id));
+        mediator.notify(&3u32);
+        assert_eq!(*seen.borrow(), vec![1, 20]);
+
+        let resp = mediator.handle_any(Box::new(3i32)).unwrap();
+        assert_eq!(*resp.downcast::<String>().unwrap(), "3");
+        assert!(mediator.handle_any(Box::new(3u8)).is_err());
+        assert!(mediator.notify_any(&4u32));
+
+        let mut shared = DynamicMediator::new_shared();
+        shared.add_handler(|x: i32| x + 1);
+        let shared = Arc::new(shared);
+        let thread_shared = Arc::clone(&shared);
+        let resp = thread::spawn(move || thread_shared.handle::<i32, i32>(1))
+            .join()
+            .unwrap();
+        assert_eq!(resp, Some(2));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_fall_back() {
+        use crate::dynamic::DynamicMediator;
+        use crate::erased::{DynMediate, Erased};
+        use std::any::Any;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let unknown = Rc::new(Cell::new(0));
+        let fallback_unknown = Rc::clone(&unknown);
+        let mut mediator = DynamicMediator::new();
+        mediator
+            .add_handler(|x: i32| x * 2)
+            .set_fallback(|msg: Box<dyn Any>| -> Box<dyn Any> { Box::new(msg.is::<u8>()) })
+            .set_notify_fallback(move |_msg: &dyn Any| {
+                fallback_unknown.set(fallback_unknown.get() + 1)
+            });
+        let resp = mediator.handle_any(Box::new(1u8)).unwrap();
+        assert!(*resp.downcast::<bool>().unwrap());
+        let resp = mediator.handle_any(Box::new(2)).unwrap();
+        assert_eq!(*resp.downcast::<i32>().unwrap(), 4);
+        assert!(!mediator.notify_any(&"unknown"));
+        assert_eq!(unknown.get(), 1);
+
+        let erased = Erased::new(MediatorBuilder::new().add_handler(|x: i32| x).build())
+            .with_fallback(|_msg: Box<dyn Any>| -> Box<dyn Any> { Box::new("unknown") });
+        let resp = erased.handle_any(Box::new(1u8)).unwrap();
+        assert_eq!(*resp.downcast::<&str>().unwrap(), "unknown");
+        assert!(!erased.notify_any(&1u8));
+    }
+
+    #[test]
+    fn should_delegate_to_parent() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let parent_seen = Rc::clone(&seen);
+        let parent = MediatorBuilder::new()
+            .add_handler(|x: i32| x * 2)
+            .listen_for::<u32>()
+            .add_notification_receiver(move |x: &u32| parent_seen.borrow_mut().push(*x))
+            .build();
+
+        let child_seen = Rc::clone(&seen);
+        let child = MediatorBuilder::new()
+            .add_handler(|s: &'static str| s.len())
+            .listen_for::<u8>()
+            .add_notification_receiver(move |x: &u8| child_seen.borrow_mut().push(*x as u32 + 100))
+            .with_parent(&parent)
+            .with_parent_handlers(&parent)
+            .build();
+
+        assert_eq!(child.handle("noon"), 4);
+        assert_eq!(child.handle(21), 42);
+        child.notify(&1u32);
+        child.notify(&2u8);
+        let seen_clone = Rc::clone(&seen);
+        child.subscribe(move |x: &u32| seen_clone.borrow_mut().push(*x + 10));
+        parent.notify(&3u32);
+        assert_eq!(*seen.borrow(), vec![1, 102, 3, 13]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_add_async_behaviors_to_parent_handlers() {
+        use crate::pipeline::NextAsync;
+
+        let parent = MediatorBuilder::new()
+            .add_async_handler(|x: i32| async move { x * 2 })
+            .build();
+        let child = MediatorBuilder::new()
+            .with_parent_handlers(&parent)
+            .add_async_pipeline(|x: i32, next: NextAsync<i32, i32>| async move {
+                next.call(x).await + 1
+            })
+            .build();
+
+        assert_eq!(block_on(child.handle_async(20)), 41);
+        assert_eq!(block_on(parent.handle_async(20)), 40);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_project_views() {
+        use crate::view::{Handle, HandleAsync, Notify, TryHandle, View};
+
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x * 2)
+            .add_handler(|s: &'static str| s.len())
+            .add_async_handler(|x: u8| async move { x + 1 })
+            .add_try_handler(|x: u16| if x > 0 { Ok(x) } else { Err("zero") })
+            .listen_for::<u32>()
+            .build();
+        mediator.subscribe(|x: &u32| assert_eq!(*x, 7));
+
+        fn component<M, I>(view: View<'_, M, Handle<i32, i32>>) -> i32
+        where
+            M: Mediate,
+            M::Handlers: ContainsAt<RequestResponse<i32, i32>, I>,
+        {
+            view.handle(21)
+        }
+        assert_eq!(component(mediator.project()), 42);
+
+        let view =
+            mediator.project::<(HandleAsync<u8, u8>, TryHandle<u16, u16, &str>, Notify<u32>)>();
+        assert_eq!(block_on(view.handle_async(1u8)), 2);
+        assert_eq!(view.try_handle(0u16), Err("zero"));
+        view.notify(&7u32);
+    }
+
+    #[test]
+    fn should_notify_owned() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        struct NotClone(String);
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let observer_seen = Rc::clone(&seen);
+        let consumer_seen = Rc::clone(&seen);
+        let mediator = MediatorBuilder::new()
+            .listen_for_owned::<NotClone>()
+            .add_notification_observer(move |msg: &NotClone| {
+                observer_seen.borrow_mut().push(format!("saw {}", msg.0))
+            })
+            .set_notification_consumer(move |msg: NotClone| consumer_seen.borrow_mut().push(msg.0))
+            .build();
+        mediator.notify_owned(NotClone("noon".into()));
+        assert_eq!(*seen.borrow(), vec!["saw noon", "noon"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_notify_shared() {
+        use std::sync::Mutex;
+
+        struct NotClone(i32);
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let first_seen = Arc::clone(&seen);
+        let second_seen = Arc::clone(&seen);
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for_shared::<NotClone>()
+            .add_shared_notification_receiver(move |msg: Arc<NotClone>| {
+                let first_seen = Arc::clone(&first_seen);
+                async move { first_seen.lock().unwrap().push(msg.0) }
+            })
+            .add_shared_notification_receiver(move |msg: Arc<NotClone>| {
+                let second_seen = Arc::clone(&second_seen);
+                async move { second_seen.lock().unwrap().push(msg.0 * 10) }
+            })
+            .build();
+        block_on(mediator.notify_shared(Arc::new(NotClone(4))));
+        assert_eq!(*seen.lock().unwrap(), vec![4, 40]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_notify_unsized_async() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let str_seen = Rc::clone(&seen);
+        let bytes_seen = Rc::clone(&seen);
+        let mediator = MediatorBuilder::new()
+            .listen_for_async::<Arc<str>>()
+            .add_async_notification_receiver(move |line: Arc<str>| {
+                let str_seen = Rc::clone(&str_seen);
+                async move { str_seen.borrow_mut().push(line.to_string()) }
+            })
+            .listen_for_shared::<[u8]>()
+            .add_shared_notification_receiver(move |bytes: Arc<[u8]>| {
+                let bytes_seen = Rc::clone(&bytes_seen);
+                async move {
+                    bytes_seen
+                        .borrow_mut()
+                        .push(format!("{} bytes", bytes.len()))
+                }
+            })
+            .build();
+        block_on(mediator.notify_async(Arc::from("noon")));
+        block_on(mediator.notify_shared(Arc::from(vec![1u8, 2, 3])));
+        assert_eq!(*seen.borrow(), vec!["noon", "3 bytes"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_notify_all() {
+        use crate::spec::CanNotifyAll;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        fn publish<M: CanNotifyAll<u32, I, J>, I, J>(mediator: &M) {
+            block_on(mediator.notify_all(5u32));
+        }
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let sync_seen = Rc::clone(&seen);
+        let async_seen = Rc::clone(&seen);
+        let mediator = MediatorBuilder::new()
+            .listen_for::<u32>()
+            .add_notification_receiver(move |x: &u32| sync_seen.borrow_mut().push(*x))
+            .listen_for_async::<u32>()
+            .add_async_notification_receiver(move |x: u32| {
+                let async_seen = Rc::clone(&async_seen);
+                async move { async_seen.borrow_mut().push(x * 10) }
+            })
+            .build();
+        publish(&mediator);
+        assert_eq!(*seen.borrow(), vec![5, 50]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_handle_auto() {
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x * 2)
+            .add_async_handler(|x: u8| async move { x + 1 })
+            .build();
+        let doubled: i32 = block_on(mediator.handle_auto(21));
+        assert_eq!(doubled, 42);
+        assert_eq!(block_on(mediator.handle_auto::<u8, u8, _>(1)), 2);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x.to_string())
+            .build();
+        let fut = shared.handle_auto::<i32, String, _>(5);
+        assert_eq!(thread::spawn(move || block_on(fut)).join().unwrap(), "5");
+    }
+
+    #[test]
+    #[cfg(feature = "blocking")]
+    fn should_handle_blocking() {
+        let mediator = MediatorBuilder::new()
+            .add_async_handler(|x: i32| async move {
+                time::Delay::new(std::time::Duration::from_millis(5)).await;
+                x * 2
+            })
+            .build();
+        assert_eq!(mediator.handle_blocking(21), 42);
+        assert_eq!(blocking::block_on(mediator.handle_async(1)), 2);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_async_handler(|x: i32| async move { x + 1 })
+            .build();
+        let handle = blocking::spawn(shared.handle_async(1));
+        assert_eq!(handle.join().unwrap(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_notify_detached() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let val = Arc::new(AtomicUsize::new(0));
+        let receiver_val = Arc::clone(&val);
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for_async::<usize>()
+            .add_async_notification_receiver(move |x: usize| {
+                let receiver_val = Arc::clone(&receiver_val);
+                async move {
+                    receiver_val.fetch_add(x, Ordering::SeqCst);
+                }
+            })
+            .add_async_notification_receiver(|_x: usize| async {})
+            .build();
+
+        let mut workers = vec![];
+        mediator.notify_detached(3, |fut| workers.push(thread::spawn(move || block_on(fut))));
+        assert_eq!(workers.len(), 2);
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(val.load(Ordering::SeqCst), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_offload_blocking_handlers() {
+        let mediator = MediatorBuilder::new()
+            .add_blocking_handler(|x: i32| {
+                thread::sleep(std::time::Duration::from_millis(5));
+                (x * 2, thread::current().name().map(String::from))
+            })
+            .build();
+        let (resp, name) = block_on(mediator.handle_async(21));
+        assert_eq!(resp, 42);
+        assert_eq!(name.as_deref(), Some("noon-blocking"));
+
+        let shared = MediatorBuilder::new_shared()
+            .add_blocking_handler(|x: u8| -> u8 { panic!("handler failed on {}", x) })
+            .build();
+        let fut = shared.handle_async(1u8);
+        let panicked = thread::spawn(move || block_on(fut)).join();
+        assert!(panicked.is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_isolate_receiver_panics() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let panic_seen = Rc::clone(&seen);
+        let receiver_seen = Rc::clone(&seen);
+        let mediator = MediatorBuilder::new()
+            .on_receiver_panic(move |panic: &hooks::ReceiverPanic| {
+                panic_seen
+                    .borrow_mut()
+                    .push(panic.message().unwrap_or_default().to_string())
+            })
+            .listen_for::<i32>()
+            .add_notification_receiver(|x: &i32| panic!("receiver failed on {}", x))
+            .add_notification_receiver(move |x: &i32| {
+                receiver_seen.borrow_mut().push(x.to_string())
+            })
+            .build();
+        mediator.notify(&5);
+        assert_eq!(*seen.borrow(), vec!["receiver failed on 5", "5"]);
+    }
+
+    #[test]
+    fn should_try_notify() {
+        let mediator = MediatorBuilder::new()
+            .try_listen_for::<str, String>()
+            .add_try_notification_receiver(|msg: &str| Err(format!("first: {}", msg)))
+            .add_try_notification_receiver(|_msg: &str| Ok(()))
+            .add_try_notification_receiver(|msg: &str| Err(format!("third: {}", msg)))
+            .build();
+        assert_eq!(
+            mediator.try_notify("noon"),
+            vec!["first: noon".to_string(), "third: noon".to_string()]
+        );
+        assert_eq!(
+            mediator.try_notify_each("noon"),
+            Err("first: noon".to_string())
+        );
+
+        let mediator = MediatorBuilder::new()
+            .try_listen_for::<i32, &str>()
+            .add_try_notification_receiver(
+                |x: &i32| if *x > 0 { Ok(()) } else { Err("not positive") },
+            )
+            .add_try_notification_receiver(
+                |x: &i32| if *x < 10 { Ok(()) } else { Err("too large") },
+            )
+            .build();
+        assert_eq!(mediator.try_notify_each(&5), Ok(()));
+        assert_eq!(mediator.try_notify_each(&-1), Err("not positive"));
+        assert_eq!(mediator.try_notify(&-1), vec!["not positive"]);
+    }
+
+    #[test]
+    fn should_map_responses() {
+        let mediator = MediatorBuilder::new()
+            .add_handler(|s: &'static str| s.len())
+            .add_handler(|x: i32| x * 2)
+            .add_pipeline(|x: i32, next: &dyn Fn(i32) -> i32| next(x + 1))
+            .map_response(|x: i32| format!("{} units", x))
+            .map_response::<&str, usize, bool, _, _, _>(|len| len > 3)
+            .build();
+        let resp: String = mediator.handle(1);
+        assert_eq!(resp, "4 units");
+        assert!(mediator.handle("noon"));
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: u8| x)
+            .map_response(|x: u8| u32::from(x) * 1000)
+            .build_arc();
+        let resp: u32 = thread::spawn(move || shared.handle(2u8)).join().unwrap();
+        assert_eq!(resp, 2000);
+    }
+
+    #[test]
+    fn should_map_requests() {
+        struct GetUserV1(i32);
+        struct GetUser {
+            id: i32,
+        }
+
+        let mediator = MediatorBuilder::new()
+            .add_handler(|req: GetUser| format!("user {}", req.id))
+            .add_pipeline(|req: GetUser, next: &dyn Fn(GetUser) -> String| {
+                format!("[{}]", next(req))
+            })
+            .map_request(|old: GetUserV1| GetUser { id: old.0 })
+            .build();
+        assert_eq!(mediator.handle(GetUser { id: 1 }), "[user 1]");
+        assert_eq!(mediator.handle(GetUserV1(2)), "[user 2]");
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|x: u32| x + 1)
+            .map_request(|x: u8| u32::from(x))
+            .build();
+        let resp: u32 = shared.handle(1u8);
+        assert_eq!(resp, 2);
+    }
+
+    #[test]
+    fn should_decorate_handlers() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let calls = Rc::new(Cell::new(0));
+        let counted = Rc::clone(&calls);
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x * 2)
+            .add_pipeline(|x: i32, next: &dyn Fn(i32) -> i32| next(x + 1))
+            .decorate_handler(move |inner: &dyn Fn(i32) -> i32, x| {
+                counted.set(counted.get() + 1);
+                if x < 0 {
+                    0
+                } else {
+                    inner(x)
+                }
+            })
+            .build();
+        assert_eq!(mediator.handle(1), 4);
+        assert_eq!(mediator.handle(-1), 0);
+        assert_eq!(calls.get(), 2);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_handler(|s: String| s.len())
+            .decorate_handler(|inner: &dyn Fn(String) -> usize, s| inner(s) * 10)
+            .build_arc();
+        let resp = thread::spawn(move || shared.handle("noon".to_string()))
+            .join()
+            .unwrap();
+        assert_eq!(resp, 40);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_cache_responses() {
+        use cache::Invalidate;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let mediator = MediatorBuilder::new()
+            .add_cached_handler(|x: i32| {
+                CALLS.fetch_add(1, Ordering::SeqCst);
+                x.to_string()
+            })
+            .build();
+        assert_eq!(mediator.handle(1), "1");
+        assert_eq!(mediator.handle(1), "1");
+        assert_eq!(mediator.handle(2), "2");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+
+        mediator.handle(Invalidate::Key(1));
+        assert_eq!(mediator.handle(1), "1");
+        assert_eq!(mediator.handle(2), "2");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+
+        mediator.handle(Invalidate::<i32>::All);
+        assert_eq!(mediator.handle(2), "2");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 4);
+
+        let shared = MediatorBuilder::new_shared()
+            .add_cached_handler(|x: u8| u32::from(x) * 2)
+            .build_arc();
+        let resp: u32 = thread::spawn(move || shared.handle(2u8)).join().unwrap();
+        assert_eq!(resp, 4);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_apply_cache_policies() {
+        use cache::{CacheMetrics, CachePolicy, CacheStats, Eviction};
+        use std::time::Duration;
+
+        let mediator = MediatorBuilder::new()
+            .add_cached_handler_with_policy(CachePolicy::new().with_capacity(2), |x: i32| x * 2)
+            .add_cached_handler_with_policy(
+                CachePolicy::new()
+                    .with_capacity(2)
+                    .with_eviction(Eviction::FirstIn),
+                |x: u8| x + 1,
+            )
+            .add_cached_handler_with_policy(
+                CachePolicy::new().with_ttl(Duration::from_millis(20)),
+                |s: &'static str| s.len(),
+            )
+            .build();
+
+        for x in [1, 2, 1, 3, 1, 2] {
+            let _: i32 = mediator.handle(x);
+        }
+        assert_eq!(
+            mediator.handle(CacheStats::<i32>::new()),
+            CacheMetrics {
+                hits: 2,
+                misses: 4,
+                evictions: 2,
+                len: 2,
+            }
+        );
+
+        for x in [1u8, 2, 1, 3, 1] {
+            let _: u8 = mediator.handle(x);
+        }
+        let stats = mediator.handle(CacheStats::<u8>::new());
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 4, 2));
+
+        let _: usize = mediator.handle("noon");
+        let _: usize = mediator.handle("noon");
+        thread::sleep(Duration::from_millis(30));
+        let _: usize = mediator.handle("noon");
+        let stats = mediator.handle(CacheStats::<&str>::new());
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 2, 1));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_retry_failed_handlers() {
+        use retry::{Backoff, RetryPolicy};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+        let policy = RetryPolicy::new(3)
+            .with_backoff(Backoff::Exponential {
+                initial: Duration::from_millis(1),
+                max: Duration::from_millis(5),
+            })
+            .retry_if(|err: &String| err != "fatal");
+        let mediator = MediatorBuilder::new_shared()
+            .add_async_handler_with_retry(policy, |fail_until: usize| async move {
+                let attempt = ATTEMPTS.fetch_add(1, Ordering::SeqCst) + 1;
+                match fail_until {
+                    0 => Err("fatal".to_string()),
+                    _ if attempt < fail_until => Err(format!("attempt {}", attempt)),
+                    _ => Ok(attempt),
+                }
+            })
+            .build();
+
+        assert_eq!(block_on(mediator.try_handle_async(3usize)), Ok(3));
+
+        ATTEMPTS.store(0, Ordering::SeqCst);
+        let resp: Result<usize, String> = block_on(mediator.try_handle_async(5usize));
+        assert_eq!(resp, Err("attempt 3".to_string()));
+
+        ATTEMPTS.store(0, Ordering::SeqCst);
+        let resp: Result<usize, String> = block_on(mediator.try_handle_async(0usize));
+        assert_eq!(resp, Err("fatal".to_string()));
+        assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_break_circuits() {
+        use breaker::{CircuitBreaker, CircuitOpen, CircuitState};
+        use std::time::Duration;
+
+        #[derive(Debug, PartialEq)]
+        enum Error {
+            Failed,
+            Open,
+        }
+
+        impl From<CircuitOpen> for Error {
+            fn from(_: CircuitOpen) -> Self {
+                Error::Open
+            }
+        }
+
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        let mediator = MediatorBuilder::new()
+            .add_try_handler_with_breaker(breaker.clone(), |ok: bool| {
+                if ok {
+                    Ok(())
+                } else {
+                    Err(Error::Failed)
+                }
+            })
+            .build();
+        assert_eq!(mediator.try_handle(false), Err(Error::Failed));
+        assert_eq!(mediator.try_handle(true), Ok(()));
+        assert_eq!(mediator.try_handle(false), Err(Error::Failed));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.consecutive_failures(), 1);
+
+        assert_eq!(mediator.try_handle(false), Err(Error::Failed));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(mediator.try_handle(true), Err(Error::Open));
+
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(mediator.try_handle(false), Err(Error::Failed));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(mediator.try_handle(true), Err(Error::Open));
+
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(mediator.try_handle(true), Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        let shared = MediatorBuilder::new_shared()
+            .add_async_try_handler_with_breaker(breaker.clone(), |x: u8| async move {
+                if x > 0 {
+                    Ok(x)
+                } else {
+                    Err(Error::Failed)
+                }
+            })
+            .build();
+        assert_eq!(block_on(shared.try_handle_async(0u8)), Err(Error::Failed));
+        assert_eq!(block_on(shared.try_handle_async(1u8)), Err(Error::Open));
+        breaker.reset();
+        assert_eq!(block_on(shared.try_handle_async(1u8)), Ok(1));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_limit_rates() {
+        use limit::{RateLimited, RateLimiter};
+        use std::time::{Duration, Instant};
+
+        let limiter = RateLimiter::new(50, 2);
+        let mediator = MediatorBuilder::new_shared()
+            .add_try_handler_with_rate_limit(limiter.clone(), |x: i32| Ok::<_, RateLimited>(x))
+            .add_async_try_handler_with_rate_limit(limiter.clone(), |x: u8| async move {
+                Ok::<_, RateLimited>(x)
+            })
+            .add_async_handler_with_rate_limit(limiter.clone(), |x: u16| async move { x })
+            .build();
+        assert_eq!(mediator.try_handle(1), Ok(1));
+        assert_eq!(block_on(mediator.try_handle_async(2u8)), Ok(2));
+        assert_eq!(limiter.available(), 0);
+        assert_eq!(
+            mediator.try_handle::<i32, i32, RateLimited, _>(3),
+            Err(RateLimited)
+        );
+        assert_eq!(
+            block_on(mediator.try_handle_async::<u8, u8, RateLimited, _>(4)),
+            Err(RateLimited)
+        );
+
+        let start = Instant::now();
+        assert_eq!(block_on(mediator.handle_async(5u16)), 5);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_report_metrics() {
+        use hooks::{Dispatch, DispatchKind};
+        use metrics::MetricsSink;
+        use std::sync::Mutex;
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Recorder {
+            counts: Mutex<Vec<(DispatchKind, &'static str)>>,
+            latencies: Mutex<Vec<(&'static str, Duration)>>,
+        }
+
+        impl MetricsSink for Recorder {
+            fn increment_counter(&self, dispatch: &Dispatch) {
+                self.counts
+                    .lock()
+                    .unwrap()
+                    .push((dispatch.kind, dispatch.message));
+            }
+
+            fn record_latency(&self, dispatch: &Dispatch, latency: Duration) {
+                self.latencies
+                    .lock()
+                    .unwrap()
+                    .push((dispatch.message, latency));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler(|x: i32| x)
+            .add_async_handler(|x: u8| async move {
+                time::Delay::new(Duration::from_millis(10)).await;
+                x
+            })
+            .listen_for::<str>()
+            .with_metrics(Arc::clone(&recorder))
+            .build();
+
+        mediator.handle(1);
+        mediator.notify("hello");
+        let fut = mediator.handle_async(2u8);
+        assert_eq!(recorder.latencies.lock().unwrap().len(), 2);
+        block_on(fut);
+
+        assert_eq!(
+            *recorder.counts.lock().unwrap(),
+            [
+                (DispatchKind::Handle, "i32"),
+                (DispatchKind::Notify, "str"),
+                (DispatchKind::HandleAsync, "u8"),
+            ]
+        );
+        let latencies = recorder.latencies.lock().unwrap();
+        assert_eq!(
+            latencies.iter().map(|(msg, _)| *msg).collect::<Vec<_>>(),
+            ["i32", "str", "u8"]
+        );
+        assert!(latencies[2].1 >= Duration::from_millis(10));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_propagate_envelopes() {
+        use envelope::{CorrelationId, Envelope, Metadata};
+
+        struct Checkout;
+        struct Charge;
+
+        let payments = MediatorBuilder::new_shared()
+            .add_handler(|req: Envelope<Charge>| req.metadata)
+            .listen_for::<Envelope<&'static str>>()
+            .build_arc();
+        let nested = Arc::clone(&payments);
+        let orders = MediatorBuilder::new_shared()
+            .add_handler(move |req: Envelope<Checkout>| {
+                (req.metadata, nested.handle(req.child(Charge)))
+            })
+            .build();
+
+        let (parent, child): (Metadata, Metadata) = orders.handle_enveloped(Checkout);
+        assert_eq!(parent.correlation_id, CorrelationId(parent.id.0));
+        assert_eq!(parent.causation_id, None);
+        assert_eq!(child.correlation_id, parent.correlation_id);
+        assert_eq!(child.causation_id, Some(parent.id));
+        assert_ne!(child.id, parent.id);
+
+        let incoming = Envelope::correlated(Checkout, CorrelationId(42));
+        let child: Metadata = payments.handle(incoming.child(Charge));
+        assert_eq!(child.correlation_id, CorrelationId(42));
+        payments.notify_enveloped("done");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_record_dispatches() {
+        use hooks::DispatchKind;
+        use recording::Recording;
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Deposit(u32);
+
+        let mediator = Arc::new(Recording::new(
+            MediatorBuilder::new_shared()
+                .add_handler(|req: Deposit| req.0)
+                .add_async_handler(|x: u8| async move { x })
+                .listen_for::<str>()
+                .listen_for_async::<String>()
+                .build(),
+        ));
+        let recording = Arc::clone(&mediator);
+        thread::spawn(move || recording.handle_recorded(Deposit(10)))
+            .join()
+            .unwrap();
+        mediator.notify("hello");
+        block_on(mediator.handle_async_recorded(2u8));
+        block_on(mediator.notify_async_recorded("bye".to_string()));
+        let resp: u32 = mediator.handle(Deposit(5));
+        assert_eq!(resp, 5);
+
+        assert_eq!(
+            mediator
+                .dispatches()
+                .iter()
+                .map(|d| d.kind)
+                .collect::<Vec<_>>(),
+            [
+                DispatchKind::Handle,
+                DispatchKind::Notify,
+                DispatchKind::HandleAsync,
+                DispatchKind::NotifyAsync,
+                DispatchKind::Handle,
+            ]
+        );
+        let records = mediator.take_records();
+        assert!(mediator.is_empty());
+        assert_eq!(records[0].message::<Deposit>(), Some(&Deposit(10)));
+        assert_eq!(records[0].message::<u8>(), None);
+        assert_eq!(records[0].debug(), Some("Deposit(10)"));
+        assert_eq!(records[1].debug(), None);
+        assert_eq!(records[2].message::<u8>(), Some(&2));
+        assert_eq!(records[3].debug(), Some("\"bye\""));
+        assert_eq!(records[4].message::<Deposit>(), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_describe_mediators() {
+        use describe::{EntryDescription, EntryKind};
+
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: i32| x.to_string())
+            .add_async_handler(|x: u8| async move { x })
+            .add_try_handler(|x: u16| Ok::<_, String>(x))
+            .listen_for::<str>()
+            .add_notification_receiver(|_: &str| {})
+            .add_notification_receiver(|_: &str| {})
+            .try_listen_for::<u64, String>()
+            .build_balanced();
+        let description = mediator.describe();
+        assert_eq!(
+            description.handlers,
+            [
+                EntryDescription {
+                    kind: EntryKind::Handler,
+                    message: "i32",
+                    response: Some("alloc::string::String"),
+                    receivers: None,
+                },
+                EntryDescription {
+                    kind: EntryKind::TryHandler,
+                    message: "u16",
+                    response: Some("core::result::Result<u16, alloc::string::String>"),
+                    receivers: None,
+                },
+                EntryDescription {
+                    kind: EntryKind::AsyncHandler,
+                    message: "u8",
+                    response: Some("u8"),
+                    receivers: None,
+                },
+            ]
+        );
+        mediator.subscribe(|_: &str| {});
+        assert_eq!(
+            mediator.describe().to_string(),
+            "handler i32 -> alloc::string::String\n\
+             try handler u16 -> core::result::Result<u16, alloc::string::String>\n\
+             async handler u8 -> u8\n\
+             notification str (3 receivers)\n\
+             try notification u64 -> alloc::string::String (0 receivers)\n"
+        );
+    }
+
+    #[test]
+    fn should_debug_mediators() {
+        let builder = MediatorBuilder::new()
+            .add_handler(|x: i32| x.to_string())
+            .listen_for::<str>();
+        assert_eq!(
+            format!("{:?}", builder),
+            "MediatorBuilder { handlers: [handler i32 -> alloc::string::String], \
+             receivers: [notification str (0 receivers)] }"
+        );
+        let mediator = builder
+            .add_notification_receiver(|_: &str| {})
+            .build_balanced();
+        assert_eq!(
+            format!("{:?}", mediator),
+            "Mediator { handlers: [handler i32 -> alloc::string::String], \
+             receivers: [notification str (1 receivers)] }"
+        );
+        assert_eq!(
+            format!("{:?}", MediatorBuilder::new_shared().build_arc()),
+            "Mediator { handlers: [], receivers: [] }"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_dispatch_envelopes() {
+        use codec::{EnvelopeError, TaggedMediator};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let lines = Rc::new(RefCell::new(vec![]));
+        let received = lines.clone();
+        let mediator = TaggedMediator::new(
+            MediatorBuilder::new()
+                .add_handler(|name: String| format!("Hello, {}!", name))
+                .listen_for::<Vec<u8>>()
+                .add_notification_receiver(move |bytes: &Vec<u8>| {
+                    received.borrow_mut().push(bytes.clone())
+                })
+                .build(),
+        )
+        .handle::<String, String, _>("greet")
+        .notify::<Vec<u8>, _>("log");
+
+        let mut tags: Vec<_> = mediator.tags().collect();
+        tags.sort();
+        assert_eq!(tags, ["greet", "log"]);
+        assert_eq!(
+            mediator.dispatch_envelope("greet", b"noon").unwrap(),
+            b"Hello, noon!"
+        );
+        assert_eq!(mediator.dispatch_envelope("log", b"up").unwrap(), b"");
+        assert_eq!(*lines.borrow(), [b"up".to_vec()]);
+        assert!(matches!(
+            mediator.dispatch_envelope("greet", &[0xff]),
+            Err(EnvelopeError::Decode { .. })
+        ));
+        let err = mediator.dispatch_envelope("shout", b"noon").unwrap_err();
+        assert_eq!(err.to_string(), "no message is registered with tag `shout`");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_serve_over_tcp() {
+        use codec::TaggedMediator;
+        use std::net::TcpListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use transport::{TcpInbound, TcpTransport, Transport};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let published = Arc::new(AtomicUsize::new(0));
+            let count = published.clone();
+            let mediator = TaggedMediator::new(
+                MediatorBuilder::new()
+                    .add_handler(move |_: ()| count.load(Ordering::SeqCst).to_string())
+                    .listen_for::<String>()
+                    .add_notification_receiver(move |_: &String| {
+                        published.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .build(),
+            )
+            .handle::<(), String, _>("count")
+            .notify::<String, _>("event");
+            let (stream, _) = listener.accept().unwrap();
+            mediator.serve(&mut TcpInbound::new(stream).unwrap())
+        });
+
+        let client = TcpTransport::connect(addr).unwrap();
+        assert_eq!(client.request("count", b"").unwrap(), b"0");
+        client.publish("event", b"started").unwrap();
+        client.publish("event", &[0xff]).unwrap();
+        client.publish("unknown", b"").unwrap();
+        assert_eq!(client.request("count", b"").unwrap(), b"1");
+        let err = client.request("unknown", b"").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+        assert_eq!(
+            err.to_string(),
+            "no message is registered with tag `unknown`"
+        );
+        drop(client);
+        server.join().unwrap().unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_forward_notifications() {
+        use std::sync::mpsc;
+
+        let (sender, receiver) = mpsc::channel();
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<u32>()
+            .forward_notifications_to::<u32, _, _>(sender)
+            .build_arc();
+        let notifier = Arc::clone(&mediator);
+        thread::spawn(move || (1..=3).for_each(|x| notifier.notify(&x)))
+            .join()
+            .unwrap();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+        drop(receiver);
+        mediator.notify(&4);
+    }
+
+    #[test]
+    #[cfg(feature = "ingest")]
+    fn should_ingest_streams() {
+        use std::sync::Mutex;
+
+        fn assert_send<T: Send>(value: T) -> T {
+            value
+        }
+
+        let lines = Arc::new(Mutex::new(vec![]));
+        let received = lines.clone();
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for_async::<String>()
+            .add_async_notification_receiver(move |line: String| {
+                let received = received.clone();
+                async move { received.lock().unwrap().push(line) }
+            })
+            .build();
+        let events = stream::unfold(0, |n| async move {
+            (n < 3).then(|| (format!("event {}", n), n + 1))
+        });
+        let ingested = assert_send(mediator.ingest(Box::pin(events)));
+        assert_eq!(block_on(ingested), 3);
+        assert_eq!(*lines.lock().unwrap(), ["event 0", "event 1", "event 2"]);
+        assert_eq!(
+            block_on(mediator.ingest(stream::iter(Vec::<String>::new()))),
+            0
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_subscribe_streams() {
+        use stream::StreamExt;
+
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<String>()
+            .build_arc();
+        mediator.notify(&"before".to_string());
+        let mut lines = mediator.subscribe_stream::<String, _>();
+        let notifier = Arc::clone(&mediator);
+        thread::spawn(move || {
+            notifier.notify(&"first".to_string());
+            notifier.notify(&"second".to_string());
+        });
+        assert_eq!(block_on(lines.next()).as_deref(), Some("first"));
+        assert_eq!(block_on(lines.next()).as_deref(), Some("second"));
+        assert!(mediator.unsubscribe::<String, _>(lines.id()));
+        mediator.notify(&"after".to_string());
+        assert_eq!(block_on(lines.next()), None);
+
+        let mediator = MediatorBuilder::new().listen_for::<u8>().build();
+        let mut numbers = mediator.subscribe_stream::<u8, _>();
+        mediator.notify(&1);
+        drop(mediator);
+        assert_eq!(block_on(numbers.next()), Some(1));
+        assert_eq!(block_on(numbers.next()), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_broadcast_notifications() {
+        use broadcast::{RecvError, TryRecvError};
+        use std::time::Duration;
+
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for_broadcast::<u32>(2)
+            .build_arc();
+        assert_eq!(mediator.notify_broadcast(0), 0);
+
+        let mut slow = mediator.subscribe_broadcast::<u32, _>();
+        let mut waiting = mediator.subscribe_broadcast::<u32, _>();
+        let subscriber = thread::spawn(move || {
+            let mut received = vec![];
+            while let Ok(x) = block_on(waiting.recv()) {
+                received.push(x);
+            }
+            received
+        });
+        assert_eq!(
+            mediator.describe().receivers[0].to_string(),
+            "broadcast notification u32 (2 receivers)"
+        );
+        for x in 1..=3 {
+            assert_eq!(mediator.notify_broadcast(x), 2);
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(slow.try_recv(), Err(TryRecvError::Lagged(1)));
+        assert_eq!(slow.try_recv(), Ok(2));
+        let mut copy = slow.clone();
+        assert_eq!(slow.try_recv(), Ok(3));
+        assert_eq!(slow.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(copy.try_recv(), Ok(3));
+        drop(copy);
+
+        drop(mediator);
+        assert_eq!(subscriber.join().unwrap(), [1, 2, 3]);
+        assert_eq!(block_on(slow.recv()), Err(RecvError::Closed));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_run_as_actor() {
+        use actor::ActorStopped;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let (pushed, read) = (log.clone(), log.clone());
+        let mediator = MediatorBuilder::new()
+            .add_handler(move |_: ()| read.borrow().len())
+            .add_async_handler(|x: u32| async move { x * 2 })
+            .listen_for::<String>()
+            .add_notification_receiver(move |line: &String| pushed.borrow_mut().push(line.clone()))
+            .build();
+        let (mailbox, actor) = mediator.into_actor();
+
+        let workers: Vec<_> = (0..3)
+            .map(|n| {
+                let mailbox = mailbox.clone();
+                thread::spawn(move || {
+                    block_on(mailbox.notify::<String, _>(format!("worker {}", n))).unwrap();
+                    block_on(mailbox.handle_async::<u32, u32, _>(n)).unwrap()
+                })
+            })
+            .collect();
+        let len = mailbox.handle::<(), usize, _>(());
+        let called = mailbox.call(|m| m.handle::<(), usize, _>(()) + 1);
+        drop(mailbox);
+        block_on(actor);
+
+        let mut doubled: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        doubled.sort();
+        assert_eq!(doubled, [0, 2, 4]);
+        assert_eq!(log.borrow().len(), 3);
+        assert!(block_on(len).unwrap() <= 3);
+        assert!(block_on(called).unwrap() <= 4);
+
+        let (mailbox, actor) = MediatorBuilder::new()
+            .add_handler(|x: i32| x)
+            .build()
+            .into_actor();
+        drop(actor);
+        assert_eq!(
+            block_on(mailbox.handle::<i32, i32, _>(1)),
+            Err(ActorStopped)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_send_to_addresses() {
+        use actor::{ActorStopped, Address};
+
+        fn lookup(users: &Address<u32, String>, ids: &[u32]) -> Vec<actor::Reply<String>> {
+            ids.iter().map(|&id| users.send(id)).collect()
+        }
+
+        let (mailbox, actor) = MediatorBuilder::new()
+            .add_handler(|id: u32| format!("user {}", id))
+            .add_async_handler(|id: u64| async move { id + 1 })
+            .build()
+            .into_actor();
+        let users = mailbox.address::<u32, String, _>();
+        let next = mailbox.async_address::<u64, u64, _>();
+        drop(mailbox);
+
+        let worker = {
+            let users = users.clone();
+            thread::spawn(move || {
+                let replies = lookup(&users, &[1, 2]);
+                replies
+                    .into_iter()
+                    .map(|r| block_on(r).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        };
+        let incremented = next.send(41);
+        drop((users, next));
+        block_on(actor);
+        assert_eq!(worker.join().unwrap(), ["user 1", "user 2"]);
+        assert_eq!(block_on(incremented), Ok(42));
+
+        let (mailbox, actor) = MediatorBuilder::new()
+            .add_handler(|x: i32| x)
+            .build()
+            .into_actor();
+        let address = mailbox.address::<i32, i32, _>();
+        drop(actor);
+        assert_eq!(block_on(address.send(1)), Err(ActorStopped));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_notify_periodically() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::{mpsc, Mutex};
+        use std::time::Duration;
+
+        let (sender, ticks) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<u32>()
+            .add_notification_receiver(move |n: &u32| sender.lock().unwrap().send(*n).unwrap())
+            .build_arc();
+        let count = AtomicU32::new(0);
+        let recurring = mediator.notify_every::<u32, _, _>(
+            move || count.fetch_add(1, Ordering::SeqCst),
+            Duration::from_millis(5),
+        );
+        let first = (0..3)
+            .map(|_| ticks.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(first, [0, 1, 2]);
+        drop(recurring);
+        // A tick may already have been underway.
+        while ticks.recv_timeout(Duration::from_millis(50)).is_ok() {}
+        assert!(ticks.try_recv().is_err());
+
+        let recurring = mediator.notify_every::<u32, _, _>(|| 0, Duration::from_millis(5));
+        drop(mediator);
+        assert!(ticks.recv_timeout(Duration::from_millis(50)).is_err());
+        drop(recurring);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_spawn_on_pluggable_runtimes() {
+        use flavor::{BoxFuture, Shared};
+        use runtime::{Threads, Timer, TimerThread};
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::{mpsc, Mutex};
+        use std::time::Duration;
+
+        let (sender, received) = mpsc::channel();
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for_async::<u32>()
+            .add_async_notification_receiver(move |n: u32| {
+                let sender = sender.clone();
+                async move { sender.send(n).unwrap() }
+            })
+            .build();
+        let spawned = Mutex::new(vec![]);
+        mediator.notify_spawned(1, &|fut| spawned.lock().unwrap().push(fut));
+        let futures = spawned.into_inner().unwrap();
+        assert_eq!(futures.len(), 1);
+        assert!(received.try_recv().is_err());
+        futures.into_iter().for_each(runtime::block_on);
+        assert_eq!(received.try_recv(), Ok(1));
+        mediator.notify_spawned(2, &Threads);
+        assert_eq!(received.recv_timeout(Duration::from_secs(5)), Ok(2));
+
+        struct Counted(&'static AtomicU32);
+
+        impl Timer for Counted {
+            fn sleep(&self, duration: Duration) -> BoxFuture<'static, (), Shared> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                TimerThread.sleep(duration)
+            }
+        }
+
+        static SLEEPS: AtomicU32 = AtomicU32::new(0);
+        let (sender, ticks) = mpsc::channel();
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<u32>()
+            .add_notification_receiver(move |n: &u32| sender.send(*n).unwrap())
+            .build_arc();
+        let recurring = mediator.notify_every_on::<u32, _, _>(
+            || 7,
+            Duration::from_millis(5),
+            &Threads,
+            Counted(&SLEEPS),
+        );
+        assert_eq!(ticks.recv_timeout(Duration::from_secs(5)), Ok(7));
+        assert_eq!(ticks.recv_timeout(Duration::from_secs(5)), Ok(7));
+        assert!(SLEEPS.load(Ordering::SeqCst) >= 2);
+        drop(recurring);
+        // A tick may already have been underway.
+        while ticks.recv_timeout(Duration::from_millis(50)).is_ok() {}
+        assert!(ticks.try_recv().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_release_outbox_on_success() {
+        use outbox::Outbox;
+        use std::sync::Mutex;
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let log = seen.clone();
+        let mediator = MediatorBuilder::new_shared()
+            .add_try_handler(|(x, outbox): (i32, Outbox<String>)| {
+                outbox.publish(format!("started {}", x));
+                if x < 0 {
+                    return Err("negative");
+                }
+                outbox.publish(format!("finished {}", x));
+                Ok(x * 2)
+            })
+            .listen_for::<String>()
+            .add_notification_receiver(move |event: &String| {
+                log.lock().unwrap().push(event.clone())
+            })
+            .build();
+
+        assert_eq!(mediator.try_handle_with_outbox(-1), Err("negative"));
+        assert!(seen.lock().unwrap().is_empty());
+        assert_eq!(mediator.try_handle_with_outbox(3), Ok(6));
+        assert_eq!(*seen.lock().unwrap(), ["started 3", "finished 3"]);
+
+        let outbox = Outbox::new();
+        outbox.publish("manual".to_string());
+        outbox.clone().publish("cloned".to_string());
+        assert_eq!(outbox.len(), 2);
+        assert_eq!(outbox.commit(&mediator), 2);
+        assert!(outbox.is_empty());
+        outbox.publish("dropped".to_string());
+        assert_eq!(outbox.rollback(), 1);
+        assert_eq!(outbox.commit(&mediator), 0);
+        assert_eq!(seen.lock().unwrap().len(), 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_run_within_unit_of_work() {
+        use std::sync::Mutex;
+        use unit::UnitOfWork;
+
+        #[derive(Default)]
+        struct Transactions {
+            log: Mutex<Vec<String>>,
+        }
+
+        impl UnitOfWork<i32> for Arc<Transactions> {
+            type Work = i32;
+            type Error = String;
+
+            fn begin(&self, msg: &i32) -> Result<i32, String> {
+                if *msg == i32::MIN {
+                    return Err("busy".to_string());
+                }
+                self.log.lock().unwrap().push(format!("begin {}", msg));
+                Ok(*msg)
+            }
+
+            fn commit(&self, work: i32) -> Result<(), String> {
+                if work == i32::MAX {
+                    return Err("conflict".to_string());
+                }
+                self.log.lock().unwrap().push(format!("commit {}", work));
+                Ok(())
+            }
+
+            fn rollback(&self, work: i32) {
+                self.log.lock().unwrap().push(format!("rollback {}", work));
+            }
+        }
+
+        let transactions = Arc::new(Transactions::default());
+        let mediator = MediatorBuilder::new_shared()
+            .add_try_handler_with_unit_of_work(transactions.clone(), |x: i32, work: &i32| {
+                assert_eq!(x, *work);
+                if x < 0 {
+                    return Err(format!("negative {}", x));
+                }
+                Ok(x)
+            })
+            .build();
+
+        assert_eq!(mediator.try_handle(1), Ok::<_, String>(1));
+        assert_eq!(
+            mediator.try_handle::<i32, i32, String, _>(-1),
+            Err("negative -1".to_string())
+        );
+        assert_eq!(
+            mediator.try_handle::<i32, i32, String, _>(i32::MIN),
+            Err("busy".to_string())
+        );
+        assert_eq!(
+            mediator.try_handle::<i32, i32, String, _>(i32::MAX),
+            Err("conflict".to_string())
+        );
+        assert_eq!(
+            *transactions.log.lock().unwrap(),
+            [
+                "begin 1",
+                "commit 1",
+                "begin -1",
+                "rollback -1",
+                &format!("begin {}", i32::MAX)
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_validate_before_handling() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use validate::{ValidationError, Validator};
+
+        #[derive(Debug, PartialEq)]
+        enum Error {
+            Invalid(String),
+            Failed,
+        }
+
+        impl From<ValidationError> for Error {
+            fn from(err: ValidationError) -> Self {
+                Error::Invalid(err.message().to_string())
+            }
+        }
+
+        struct Below(i32);
+
+        impl Validator<i32> for Below {
+            fn validate(&self, msg: &i32) -> Result<(), ValidationError> {
+                ValidationError::ensure(*msg < self.0, format!("must be below {}", self.0))
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let called = calls.clone();
+        let mediator = MediatorBuilder::new_shared()
+            .add_try_handler(move |x: i32| {
+                called.fetch_add(1, Ordering::SeqCst);
+                if x == 0 {
+                    return Err(Error::Failed);
+                }
+                Ok(x)
+            })
+            .add_validator(|x: &i32| ValidationError::ensure(*x >= 0, "must not be negative"))
+            .add_validator(Below(10))
+            .add_validator(Below(5))
+            .build();
+
+        assert_eq!(mediator.try_handle(3), Ok(3));
+        assert_eq!(mediator.try_handle::<i32, i32, _, _>(0), Err(Error::Failed));
+        assert_eq!(
+            mediator.try_handle::<i32, i32, _, _>(-1),
+            Err(Error::Invalid("must not be negative".to_string()))
+        );
+        assert_eq!(
+            mediator.try_handle::<i32, i32, _, _>(20),
+            Err(Error::Invalid("must be below 10".to_string()))
+        );
+        assert_eq!(
+            mediator.try_handle::<i32, i32, _, _>(7),
+            Err(Error::Invalid("must be below 5".to_string()))
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_authorize_before_handling() {
+        use auth::{Authorizer, Unauthorized};
+        use context::Context;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Roles(Vec<&'static str>);
+
+        #[derive(Clone, Copy)]
+        struct RequireRole(&'static str);
+
+        impl<TMsg> Authorizer<TMsg> for RequireRole {
+            fn authorize(&self, ctx: &Context, _: &TMsg) -> Result<(), Unauthorized> {
+                match ctx.get::<Roles>() {
+                    Some(roles) if roles.0.contains(&self.0) => Ok(()),
+                    _ => Err(Unauthorized::new(format!("requires {}", self.0))),
+                }
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (read, write) = (calls.clone(), calls.clone());
+        let mediator = MediatorBuilder::new_shared()
+            .add_try_handler(move |(id, _): (u32, Context)| {
+                read.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, Unauthorized>(format!("user {}", id))
+            })
+            .add_authorizer::<u32, _, _, _, _>(RequireRole("reader"))
+            .add_try_handler(move |(name, _): (String, Context)| {
+                write.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, Unauthorized>(name.len())
+            })
+            .add_authorizer::<String, _, _, _, _>(RequireRole("reader"))
+            .add_authorizer::<String, _, _, _, _>(|_: &Context, name: &String| {
+                if name == "root" {
+                    return Err(Unauthorized::new("reserved"));
+                }
+                Ok(())
+            })
+            .add_authorizer::<String, _, _, _, _>(RequireRole("writer"))
+            .build();
+
+        let anonymous = Context::new();
+        let reader = Context::new().with(Roles(vec!["reader"]));
+        let writer = Context::new().with(Roles(vec!["reader", "writer"]));
+        assert_eq!(
+            mediator.try_handle_with_context(1u32, &reader),
+            Ok("user 1".to_string())
+        );
+        assert_eq!(
+            mediator.try_handle_with_context::<u32, String, _, _>(1, &anonymous),
+            Err(Unauthorized::new("requires reader"))
+        );
+        assert_eq!(
+            mediator.try_handle_with_context::<String, usize, _, _>("ada".to_string(), &reader),
+            Err(Unauthorized::new("requires writer"))
+        );
+        assert_eq!(
+            mediator
+                .try_handle_with_context::<String, usize, _, _>("root".to_string(), &writer)
+                .unwrap_err()
+                .reason(),
+            "reserved"
+        );
+        assert_eq!(
+            mediator.try_handle_with_context("ada".to_string(), &writer),
+            Ok::<_, Unauthorized>(3)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn should_send_commands_and_queries() {
+        use message::{Command, Message, Query};
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        struct Increment;
+        impl Message for Increment {
+            type Response = ();
+        }
+        impl Command for Increment {}
+
+        struct Reset(u32);
+        impl Message for Reset {
+            type Response = Result<(), u32>;
+        }
+        impl Command for Reset {}
+
+        struct Current;
+        impl Message for Current {
+            type Response = u32;
+        }
+        impl Query for Current {}
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let (incremented, reset, current) = (counter.clone(), counter.clone(), counter);
+        let mediator = MediatorBuilder::new_shared()
+            .add_message_handler(move |_: Increment| {
+                incremented.fetch_add(1, Ordering::SeqCst);
+            })
+            .add_message_handler(move |Reset(expected): Reset| {
+                reset
+                    .compare_exchange(expected, 0, Ordering::SeqCst, Ordering::SeqCst)
+                    .map(drop)
+            })
+            .add_message_handler(move |_: Current| current.load(Ordering::SeqCst))
+            .build();
+
+        mediator.send_command(Increment);
+        mediator.send_command(Increment);
+        assert_eq!(mediator.send_query(Current), 2);
+        assert_eq!(mediator.send_command(Reset(1)), Err(2));
+        assert_eq!(mediator.send_command(Reset(2)), Ok(()));
+        assert_eq!(mediator.send_query(Current), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_append_events_to_store() {
+        use codec::TaggedMediator;
+        use events::{MemoryEventStore, StoredEvent};
+        use std::sync::Mutex;
+
+        let store = Arc::new(MemoryEventStore::new());
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<String>()
+            .store_events_in::<String, _, _>("renamed", store.clone())
+            .listen_for::<Vec<u8>>()
+            .store_events_in::<Vec<u8>, _, _>("uploaded", store.clone())
+            .build();
+        mediator.notify(&"ada".to_string());
+        mediator.notify(&vec![1, 2]);
+        mediator.notify(&"grace".to_string());
+        let events = store.events();
+        assert_eq!(
+            events,
+            [
+                StoredEvent::new("renamed", b"ada".to_vec()),
+                StoredEvent::new("uploaded", vec![1, 2]),
+                StoredEvent::new("renamed", b"grace".to_vec()),
+            ]
+        );
+
+        let names = Arc::new(Mutex::new(vec![]));
+        let replayed = names.clone();
+        let projection = TaggedMediator::new(
+            MediatorBuilder::new_shared()
+                .listen_for::<String>()
+                .add_notification_receiver(move |name: &String| {
+                    replayed.lock().unwrap().push(name.clone())
+                })
+                .listen_for::<Vec<u8>>()
+                .build(),
+        )
+        .notify::<String, _>("renamed")
+        .notify::<Vec<u8>, _>("uploaded");
+        for event in &events {
+            projection
+                .dispatch_envelope(event.tag, &event.payload)
+                .unwrap();
+        }
+        assert_eq!(*names.lock().unwrap(), ["ada", "grace"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_upcast_old_message_versions() {
+        use codec::{BoxError, Decode, Encode, EnvelopeError, TaggedMediator};
+
+        struct V1(String);
+        struct V2(String, u8);
+        struct V3 {
+            name: String,
+            age: u8,
+            admin: bool,
+        }
+
+        impl Decode for V1 {
+            fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+                Ok(V1(String::decode(bytes)?))
+            }
+        }
+
+        impl Decode for V2 {
+            fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+                let (age, name) = bytes.split_last().ok_or("empty")?;
+                Ok(V2(String::decode(name)?, *age))
+            }
+        }
+
+        impl Encode for V2 {
+            fn encode(&self) -> Vec<u8> {
+                let mut bytes = self.0.clone().into_bytes();
+                bytes.push(self.1);
+                bytes
+            }
+        }
+
+        impl Decode for V3 {
+            fn decode(bytes: &[u8]) -> Result<Self, BoxError> {
+                let (admin, rest) = bytes.split_last().ok_or("empty")?;
+                let V2(name, age) = V2::decode(rest)?;
+                Ok(V3 {
+                    name,
+                    age,
+                    admin: *admin == 1,
+                })
+            }
+        }
+
+        impl Encode for V3 {
+            fn encode(&self) -> Vec<u8> {
+                let mut bytes = V2(self.name.clone(), self.age).encode();
+                bytes.push(self.admin as u8);
+                bytes
+            }
+        }
+
+        impl From<V1> for V2 {
+            fn from(V1(name): V1) -> Self {
+                V2(name, 0)
+            }
+        }
+
+        impl From<V2> for V3 {
+            fn from(V2(name, age): V2) -> Self {
+                V3 {
+                    name,
+                    age,
+                    admin: false,
+                }
+            }
+        }
+
+        let build = || {
+            TaggedMediator::new(
+                MediatorBuilder::new()
+                    .add_handler(|user: V3| format!("{} {} {}", user.name, user.age, user.admin))
+                    .build(),
+            )
+        };
+        let mediator = build()
+            .upcast::<V1, V2>("user.v1", "user.v2")
+            .handle::<V3, String, _>("user.v3")
+            .upcast::<V2, V3>("user.v2", "user.v3");
+
+        let mut tags: Vec<_> = mediator.tags().collect();
+        tags.sort();
+        assert_eq!(tags, ["user.v1", "user.v2", "user.v3"]);
+        assert_eq!(
+            mediator.dispatch_envelope("user.v1", b"ada").unwrap(),
+            b"ada 0 false"
+        );
+        assert_eq!(
+            mediator.dispatch_envelope("user.v2", b"ada\x24").unwrap(),
+            b"ada 36 false"
+        );
+        assert_eq!(
+            mediator
+                .dispatch_envelope("user.v3", b"ada\x24\x01")
+                .unwrap(),
+            b"ada 36 true"
+        );
+        assert!(matches!(
+            mediator.dispatch_envelope("user.v2", b""),
+            Err(EnvelopeError::Decode { tag, .. }) if tag == "user.v2"
+        ));
+
+        let dangling = build().upcast::<V1, V2>("user.v1", "user.v2");
+        assert!(matches!(
+            dangling.dispatch_envelope("user.v1", b"ada"),
+            Err(EnvelopeError::UnknownTag(tag)) if tag == "user.v2"
+        ));
+
+        let err = std::panic::catch_unwind(|| {
+            build()
+                .upcast::<V1, V2>("user.v1", "user.v2")
+                .upcast::<V2, V2>("user.v2", "user.v1")
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().unwrap(),
+            "upcasting `user.v2` to `user.v1` would form a cycle"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_deduplicate_by_idempotency_key() {
+        use idempotency::MemoryIdempotencyStore;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Mutex;
+
+        #[derive(Clone)]
+        struct Delivery {
+            id: u64,
+            body: &'static str,
+        }
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let called = calls.clone();
+        let received = Arc::new(Mutex::new(vec![]));
+        let log = received.clone();
+        let responses = Arc::new(MemoryIdempotencyStore::new());
+        let mediator = MediatorBuilder::new_shared()
+            .add_idempotent_handler(
+                |msg: &Delivery| msg.id,
+                responses.clone(),
+                move |msg: Delivery| {
+                    called.fetch_add(1, Ordering::SeqCst);
+                    msg.body.len()
+                },
+            )
+            .listen_for::<Delivery>()
+            .add_idempotent_notification_receiver(
+                |msg: &Delivery| msg.id,
+                MemoryIdempotencyStore::new(),
+                move |msg: &Delivery| log.lock().unwrap().push(msg.body),
+            )
+            .build();
+
+        let deliveries = [
+            Delivery { id: 1, body: "one" },
+            Delivery {
+                id: 2,
+                body: "second",
+            },
+            Delivery {
+                id: 1,
+                body: "redelivered",
+            },
+        ];
+        let lengths: Vec<usize> = deliveries
+            .iter()
+            .map(|msg| mediator.handle(msg.clone()))
+            .collect();
+        assert_eq!(lengths, [3, 6, 3]);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(responses.len(), 2);
+
+        deliveries.iter().for_each(|msg| mediator.notify(msg));
+        assert_eq!(*received.lock().unwrap(), ["one", "second"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_deduplicate_concurrent_dispatches() {
+        use idempotency::MemoryIdempotencyStore;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Barrier;
+        use std::time::Duration;
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let called = calls.clone();
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .add_idempotent_handler(
+                    |id: &u64| *id,
+                    MemoryIdempotencyStore::new(),
+                    move |id: u64| {
+                        called.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(20));
+                        id * 2
+                    },
+                )
+                .build(),
+        );
+        let barrier = Arc::new(Barrier::new(4));
+        let dispatches: Vec<_> = (0..4)
+            .map(|_| {
+                let mediator = Arc::clone(&mediator);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    mediator.handle::<u64, u64, _>(21)
+                })
+            })
+            .collect();
+        for dispatch in dispatches {
+            assert_eq!(dispatch.join().unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_run_higher_priorities_first() {
+        use actor::Priority;
+        use std::sync::Mutex;
+
+        let order = Arc::new(Mutex::new(vec![]));
+        let log = order.clone();
+        let (mailbox, actor) = MediatorBuilder::new()
+            .add_handler(move |job: &'static str| log.lock().unwrap().push(job))
+            .build()
+            .into_actor();
+        let high = mailbox.with_priority(Priority::High);
+        let low = mailbox.with_priority(Priority::Low);
+        let cancel = high.address::<&'static str, (), _>();
+        assert_eq!(low.priority(), Priority::Low);
+        assert_eq!(mailbox.priority(), Priority::Normal);
+
+        let replies = [
+            low.handle::<_, (), _>("bulk 1"),
+            mailbox.handle("normal 1"),
+            low.handle("bulk 2"),
+            high.handle("health"),
+            mailbox.handle("normal 2"),
+            cancel.send("cancel"),
+        ];
+        drop((mailbox, high, low, cancel));
+        block_on(actor);
+        for reply in replies {
+            assert_eq!(block_on(reply), Ok(()));
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["health", "cancel", "normal 1", "normal 2", "bulk 1", "bulk 2"]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_deliver_batches() {
+        use batch::BatchPolicy;
+        use std::sync::Mutex;
+        use std::time::Duration;
+
+        let batches = Arc::new(Mutex::new(vec![]));
+        let delivered = batches.clone();
+        let mediator = MediatorBuilder::new_shared()
+            .add_batch_handler(
+                BatchPolicy::new(3).with_max_delay(Duration::from_millis(20)),
+                move |batch: Vec<u32>| delivered.lock().unwrap().push(batch),
+            )
+            .build();
+
+        (1..=7).for_each(|x| mediator.handle_batch(x));
+        assert_eq!(*batches.lock().unwrap(), [vec![1, 2, 3], vec![4, 5, 6]]);
+        thread::sleep(Duration::from_millis(30));
+        mediator.handle_batch(8);
+        assert_eq!(batches.lock().unwrap().last(), Some(&vec![7, 8]));
+        assert_eq!(mediator.flush_batch::<u32, _>(), 0);
+
+        mediator.handle_batch(9);
+        drop(mediator);
+        assert_eq!(batches.lock().unwrap().last(), Some(&vec![9]));
+        assert_eq!(batches.lock().unwrap().len(), 4);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_handle_streams_with_bounded_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+        use stream::StreamExt;
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let (counted, peak) = (running.clone(), most.clone());
+        let mediator = MediatorBuilder::new_shared()
+            .add_async_handler(move |ms: u64| {
+                let (running, most) = (counted.clone(), peak.clone());
+                async move {
+                    most.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    time::Delay::new(Duration::from_millis(ms * 20)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    ms
+                }
+            })
+            .build();
+
+        let mut responses =
+            mediator.handle_stream_of::<u64, u64, _, _>(stream::iter([6, 1, 1, 2]), 2);
+        let completed = block_on(async {
+            let mut completed = vec![];
+            while let Some(ms) = responses.next().await {
+                completed.push(ms);
+            }
+            completed
+        });
+        assert_eq!(completed, [1, 1, 2, 6]);
+        assert_eq!(most.load(Ordering::SeqCst), 2);
+        assert_eq!(responses.in_flight(), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_throttle_and_debounce_receivers() {
+        use std::sync::Mutex;
+        use std::time::Duration;
+
+        let throttled = Arc::new(Mutex::new(vec![]));
+        let debounced = Arc::new(Mutex::new(vec![]));
+        let (ticks, settled) = (throttled.clone(), debounced.clone());
+        let mediator = MediatorBuilder::new_shared()
+            .listen_for::<u32>()
+            .add_throttled_notification_receiver(Duration::from_millis(50), move |x: &u32| {
+                ticks.lock().unwrap().push(*x)
+            })
+            .listen_for_async::<u32>()
+            .add_debounced_notification_receiver(Duration::from_millis(30), move |x: u32| {
+                settled.lock().unwrap().push(x);
+                async {}
+            })
+            .build();
+
+        (1..=3).for_each(|x| mediator.notify(&x));
+        thread::sleep(Duration::from_millis(60));
+        mediator.notify(&4);
+        assert_eq!(*throttled.lock().unwrap(), [1, 4]);
+
+        let notify = |x| {
+            let mut workers = vec![];
+            mediator.notify_detached(x, |fut| workers.push(thread::spawn(|| block_on(fut))));
+            workers
+        };
+        let workers: Vec<_> = (1..=3).flat_map(notify).collect();
+        workers.into_iter().for_each(|w| w.join().unwrap());
+        assert_eq!(*debounced.lock().unwrap(), [3]);
+        notify(4).into_iter().for_each(|w| w.join().unwrap());
+        assert_eq!(*debounced.lock().unwrap(), [3, 4]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_replay_to_late_subscribers() {
+        use std::sync::Mutex;
+        use stream::StreamExt;
+
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .listen_for_with_replay::<u32>(3)
+                .build(),
+        );
+        (1..=5).for_each(|x| mediator.notify(&x));
+        let mut late = mediator.subscribe_stream::<u32, _>();
+        mediator.notify(&6);
+        let replayed: Vec<_> = (0..4).map(|_| block_on(late.next()).unwrap()).collect();
+        assert_eq!(replayed, [3, 4, 5, 6]);
+
+        let notifier = {
+            let mediator = mediator.clone();
+            thread::spawn(move || (7..2000).for_each(|x| mediator.notify(&x)))
+        };
+        let seen = Arc::new(Mutex::new(vec![]));
+        let log = seen.clone();
+        mediator.subscribe(move |x: &u32| log.lock().unwrap().push(*x));
+        notifier.join().unwrap();
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.last(), Some(&1999));
+        assert!(seen.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        assert!(seen.len() >= 3);
+    }
+
+    #[test]
+    fn should_handle_once() {
+        use handler::AlreadyHandled;
+
+        struct Token(String);
+
+        let token = Token("secret".to_string());
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .add_once_handler(move |_: ()| token)
+                .build(),
+        );
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let mediator = mediator.clone();
+                thread::spawn(move || mediator.try_handle::<(), Token, AlreadyHandled, _>(()))
+            })
+            .collect();
+        let results: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        let taken: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].0, "secret");
+        assert_eq!(
+            results.iter().filter(|r| r.is_err()).count(),
+            results.len() - 1
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_build_lazy_handlers_on_first_message() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let builds = Arc::new(AtomicUsize::new(0));
+        let built = builds.clone();
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .add_handler_lazy(move || {
+                    built.fetch_add(1, Ordering::SeqCst);
+                    |x: u32| x + 1
+                })
+                .build(),
+        );
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+        let workers: Vec<_> = (0..8)
+            .map(|i| {
+                let mediator = mediator.clone();
+                thread::spawn(move || mediator.handle::<u32, u32, _>(i))
+            })
+            .collect();
+        let sum: u32 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+        assert_eq!(sum, (1..=8).sum());
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_report_lazy_factories_which_panicked() {
+        let mediator = MediatorBuilder::new()
+            .add_handler_lazy(|| -> fn(u32) -> u32 { panic!("failed to build") })
+            .build();
+        let first = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mediator.handle::<u32, u32, _>(1)
+        }));
+        assert!(first.is_err());
+        let second = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mediator.handle::<u32, u32, _>(1)
+        }));
+        let message = second.unwrap_err();
+        assert_eq!(
+            message.downcast_ref::<String>().map(String::as_str),
+            Some("the lazy handler's factory panicked on an earlier dispatch")
+        );
+    }
+
+    #[test]
+    fn should_replace_handlers_keeping_behaviors() {
+        use hlist::{Cons, HList, NotContains};
+
+        fn defaults<H, N: HList, I>(
+            builder: MediatorBuilder<H, N>,
+        ) -> MediatorBuilder<Cons<RequestResponse<u32, u32>, H>, N>
+        where
+            H: HList + NotContains<RequestResponse<u32, u32>, I>,
+        {
+            builder
+                .add_handler(|x: u32| x + 1)
+                .add_pipeline(|x: u32, next: &dyn Fn(u32) -> u32| next(x) * 10)
+        }
+
+        let mediator = defaults(MediatorBuilder::new())
+            .add_handler(|_: ()| "unchanged")
+            .replace_handler(|x: u32| x + 2)
+            .build();
+        assert_eq!(mediator.handle::<u32, u32, _>(1), 30);
+        assert_eq!(mediator.handle::<(), &str, _>(()), "unchanged");
+    }
+
+    #[test]
+    fn should_remove_handlers() {
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: u32| x + 1)
+            .add_pipeline(|x: u32, next: &dyn Fn(u32) -> u32| next(x) * 10)
+            .add_handler(|x: u8| x + 1)
+            .without_handler::<u32, u32, _>()
+            .add_handler(|x: u32| x + 2)
+            .build();
+        assert_eq!(mediator.handle::<u32, u32, _>(1), 3);
+        assert_eq!(mediator.handle::<u8, u8, _>(1), 2);
+    }
+
+    #[test]
+    fn should_register_tuples_of_handlers() {
+        use handler::Handler;
+
+        struct Add(u32);
+        impl Handler<u32> for Add {
+            type Response = u32;
+            fn handle(&self, x: u32) -> u32 {
+                x + self.0
+            }
+        }
+
+        struct Len;
+        impl Handler<String> for Len {
+            type Response = usize;
+            fn handle(&self, s: String) -> usize {
+                s.len()
+            }
+        }
+
+        struct Not;
+        impl Handler<bool> for Not {
+            type Response = bool;
+            fn handle(&self, b: bool) -> bool {
+                !b
+            }
+        }
+
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .add_handlers((Add(2), Len, Not))
+                .build(),
+        );
+        let worker = {
+            let mediator = mediator.clone();
+            thread::spawn(move || mediator.handle::<u32, u32, _>(40))
+        };
+        assert_eq!(worker.join().unwrap(), 42);
+        assert_eq!(mediator.handle::<_, usize, _>("noon".to_string()), 4);
+        assert!(mediator.handle::<_, bool, _>(false));
+    }
+
+    #[cfg(feature = "registry")]
+    #[test]
+    fn should_collect_registered_handlers() {
+        use erased::Erased;
+
+        struct Ping;
+        struct Pong;
+
+        #[noon::handler]
+        fn ping(_: Ping) -> &'static str {
+            "pong"
+        }
+
+        #[noon::handler]
+        fn pong(_: Pong) -> &'static str {
+            "ping"
+        }
+
+        let names: Vec<_> = registry::registrations().iter().map(|r| r.name()).collect();
+        assert!(names.contains(&"noon::test::ping"));
+
+        let mediator = registry::collect_shared();
+        assert_eq!(mediator.handle::<Ping, &str>(Ping), Some("pong"));
+        assert_eq!(mediator.handle::<Pong, &str>(Pong), Some("ping"));
+        assert!(registry::verify(&mediator).is_ok());
+
+        let built = MediatorBuilder::new().add_handler(ping).build();
+        let missing = registry::verify(&Erased::new(built)).unwrap_err();
+        assert_eq!(missing.handlers(), ["noon::test::pong"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_install_a_global_mediator() {
+        use std::sync::Mutex;
+        use view::{Handle, HandleAsync, Notify};
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let recorded = seen.clone();
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler(|x: u32| x + 1)
+            .add_async_handler(|x: u8| async move { x * 2 })
+            .listen_for::<str>()
+            .add_notification_receiver(move |s: &str| recorded.lock().unwrap().push(s.to_owned()))
+            .build();
+        assert!(global::try_get::<(Handle<u32, u32>,)>().is_none());
+        global::install::<(Handle<u32, u32>, HandleAsync<u8, u8>, Notify<str>), _, _>(mediator)
+            .unwrap();
+        assert!(global::install::<(), _, _>(MediatorBuilder::new_shared().build()).is_err());
+
+        let worker = thread::spawn(|| global::get::<(Handle<u32, u32>,)>().handle(41));
+        assert_eq!(worker.join().unwrap(), 42);
+        let mediator = global::get::<(HandleAsync<u8, u8>, Notify<str>)>();
+        assert_eq!(block_on(mediator.handle_async(21)), 42);
+        mediator.notify("hello");
+        assert_eq!(*seen.lock().unwrap(), ["hello"]);
+        assert!(global::try_get::<(Handle<u8, u8>,)>().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_scope_mediators_to_requests() {
+        use context::{Context, Scoped};
+
+        struct RequestId(u32);
+        struct Audit;
+
+        fn audit<M: Mediate, I>(request: &Scoped<'_, M>) -> u32
+        where
+            M::Handlers: ContainsAt<RequestResponse<(Audit, Context), u32>, I>,
+        {
+            request.handle_in_scope(Audit)
+        }
+
+        let mediator = MediatorBuilder::new()
+            .add_handler(|(_, ctx): (Audit, Context)| ctx.get::<RequestId>().map_or(0, |r| r.0))
+            .add_handler(|x: u8| x * 2)
+            .build();
+        let first = mediator.scope(Context::new().with(RequestId(1)));
+        let mut second = first.clone();
+        second.context_mut().insert(RequestId(2));
+        assert_eq!(audit(&first), 1);
+        assert_eq!(audit(&second), 2);
+        assert_eq!(second.handle::<u8, u8, _>(21), 42);
+        assert_eq!(mediator.handle_with_context(Audit, &Context::new()), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_serve_async_handlers() {
+        let mediator = Arc::new(
+            MediatorBuilder::new_shared()
+                .add_async_handler(|name: String| async move { name.len() })
+                .build(),
+        );
+        let mut service = mediator.into_service::<String, usize, _>();
+        let mut cloned = service.clone();
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        assert!(service
+            .poll_ready(&mut Context::from_waker(&waker))
+            .is_ready());
+        let worker = thread::spawn(move || block_on(cloned.call("noon".to_string())));
+        assert_eq!(worker.join().unwrap(), Ok(4));
+        assert_eq!(block_on(service.call(String::new())), Ok(0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_export_graphs() {
+        let mediator = MediatorBuilder::new()
+            .add_handler(|x: u8| u16::from(x))
+            .add_handler(|x: u16| x.to_string())
+            .try_listen_for::<u16, String>()
+            .add_try_notification_receiver(|_: &u16| Ok(()))
+            .build();
+        assert_eq!(
+            mediator.to_dot(),
+            "digraph mediator {\n    \
+                 rankdir=LR;\n    \
+                 n0 [label=\"u16\", shape=box];\n    \
+                 n1 [label=\"alloc::string::String\", shape=box];\n    \
+                 n2 [label=\"u8\", shape=box];\n    \
+                 n3 [label=\"1 receivers\", shape=ellipse];\n    \
+                 n0 -> n1 [label=\"handler\"];\n    \
+                 n2 -> n0 [label=\"handler\"];\n    \
+                 n0 -> n3 [label=\"try notification (alloc::string::String)\"];\n\
+             }\n"
+        );
+        assert_eq!(
+            MediatorBuilder::new()
+                .add_handler(|x: Vec<u8>| Some(x))
+                .listen_for::<str>()
+                .build()
+                .to_mermaid(),
+            "flowchart LR\n    \
+                 n0[\"alloc::vec::Vec#lt;u8#gt;\"]\n    \
+                 n1[\"core::option::Option#lt;alloc::vec::Vec#lt;u8#gt;#gt;\"]\n    \
+                 n2[\"str\"]\n    \
+                 n3([\"0 receivers\"])\n    \
+                 n0 -->|\"handler\"| n1\n    \
+                 n2 -->|\"notification\"| n3\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fuzz")]
+    fn should_fuzz_handlers() {
+        use fuzz::Fuzzer;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let make = || {
+            let log = Rc::new(RefCell::new(vec![]));
+            let (pushes, reads) = (log.clone(), log.clone());
+            MediatorBuilder::new()
+                .add_handler(move |x: u8| pushes.borrow_mut().push(x))
+                .add_handler(move |_: ()| reads.borrow().len())
+                .listen_for::<(bool, Option<String>)>()
+                .build()
+        };
+        let fuzzer = Fuzzer::for_mediator(&make())
+            .handle::<u8, (), _>()
+            .notify::<(bool, Option<String>), _>();
+        let fuzzer = fuzzer.invariant("log is bounded", |m| m.handle::<(), usize, _>(()) <= 100);
+        assert_eq!(fuzzer.check(10, 100, make), Ok(()));
+
+        let fuzzer = fuzzer.invariant("log is short", |m| m.handle::<(), usize, _>(()) < 3);
+        let err = fuzzer.run(&make(), 7, 100).unwrap_err();
+        assert_eq!(err.invariant, "log is short");
+        assert_eq!(err.seed, 7);
+        assert_eq!(fuzzer.run(&make(), 7, 100), Err(err.clone()));
+        assert_eq!(
+            err.messages
+                .iter()
+                .filter(|m| m.parse::<u8>().is_ok())
+                .count(),
+            3
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invariant `log is short` was violated after {} message(s) with seed 7",
+                err.messages.len()
+            )
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_mock_responses() {
+        use mock::MockBuilder;
+        use spy::Spy;
+
+        struct GetBalance;
+        #[derive(Debug)]
+        struct Transfer;
+
+        let mock = MockBuilder::new_shared()
+            .add_handler::<GetBalance, u64, _>()
+            .add_async_handler::<GetBalance, String, _>()
+            .add_try_handler::<Transfer, (), String, _>()
+            .listen_for::<str>()
+            .build();
+        mock.when::<GetBalance>().return_value(10u64);
+        mock.when::<GetBalance>().return_value("ten".to_string());
+        mock.when::<Transfer>()
+            .return_value(Err::<(), _>("insufficient funds".to_string()));
+        let resp: u64 = mock.handle(GetBalance);
+        assert_eq!(resp, 10);
+        let resp: String = block_on(mock.handle_async(GetBalance));
+        assert_eq!(resp, "ten");
+        assert_eq!(
+            mock.try_handle::<_, (), String, _>(Transfer),
+            Err("insufficient funds".to_string())
+        );
+        mock.when::<GetBalance>().return_value(20u64);
+        let resp: u64 = thread::scope(|s| s.spawn(|| mock.handle(GetBalance)).join().unwrap());
+        assert_eq!(resp, 20);
+
+        let spy = Spy::new(&mock);
+        spy.notify("hello");
+        spy.expect_notified::<str>(1);
+
+        let unstubbed = MockBuilder::new().add_handler::<u8, u8, _>().build();
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unstubbed.handle(1u8)))
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().map(String::as_str),
+            Some("no `u8` response is stubbed for `u8`")
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_spy_on_dispatches() {
+        use spy::Spy;
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Withdraw(u32);
+
+        let spy = Spy::new(
+            MediatorBuilder::new()
+                .add_handler(|req: Withdraw| req.0)
+                .add_async_handler(|x: u8| async move { x })
+                .listen_for::<str>()
+                .listen_for_async::<u64>()
+                .build(),
+        );
+        spy.handle_recorded(Withdraw(1));
+        let resp: u32 = spy.handle(Withdraw(2));
+        assert_eq!(resp, 2);
+        block_on(spy.handle_async(3u8));
+        spy.notify("hello");
+        block_on(spy.notify_async_recorded(4u64));
+        block_on(spy.notify_async(5u64));
+
+        assert_eq!(spy.times_handled::<Withdraw>(), 2);
+        assert_eq!(spy.times_handled::<u8>(), 1);
+        assert_eq!(spy.times_handled::<str>(), 0);
+        assert_eq!(spy.last_message::<Withdraw>(), Some(Withdraw(1)));
+        assert_eq!(spy.last_message::<u8>(), None);
+        assert_eq!(spy.last_message::<u64>(), Some(4));
+        spy.expect_notified::<str>(1);
+        spy.expect_notified::<u64>(2);
+        spy.expect_notified::<Withdraw>(0);
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            spy.expect_notified::<str>(2)
+        }))
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().map(String::as_str),
+            Some("expected `str` to be notified 2 time(s), but it was notified 1 time(s)")
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn should_replay_recordings() {
+        use recording::Recording;
+        use replay::{Replayed, Replayer};
+        use std::sync::Mutex;
+
+        let recording = Recording::new(
+            MediatorBuilder::new_shared()
+                .add_handler(|x: u32| x)
+                .add_async_handler(|x: u8| async move { x })
+                .listen_for::<String>()
+                .listen_for_async::<bool>()
+                .build(),
+        );
+        recording.handle_recorded(1u32);
+        block_on(recording.handle_async_recorded(2u8));
+        recording.notify_recorded(&"three".to_string());
+        block_on(recording.notify_async_recorded(true));
+        recording.handle(5u32);
+        let records = recording.take_records();
+
+        let log = Arc::new(Mutex::new(vec![]));
+        let (a, b, c, d) = (log.clone(), log.clone(), log.clone(), log.clone());
+        let mediator = MediatorBuilder::new_shared()
+            .add_handler(move |x: u32| a.lock().unwrap().push(format!("handle {}", x)))
+            .add_async_handler(move |x: u8| {
+                let b = b.clone();
+                async move { b.lock().unwrap().push(format!("handle_async {}", x)) }
+            })
+            .listen_for::<String>()
+            .listen_for_async::<bool>()
+            .add_notification_receiver(move |x: &String| {
+                c.lock().unwrap().push(format!("notify {}", x))
+            })
+            .add_async_notification_receiver(move |x: bool| {
+                let d = d.clone();
+                async move { d.lock().unwrap().push(format!("notify_async {}", x)) }
+            })
+            .build();
+        let replayer = Replayer::new()
+            .handle::<u32, (), _>()
+            .handle_async::<u8, (), _>()
+            .notify::<String, _>()
+            .notify_async::<bool, _>();
+        assert_eq!(
+            block_on(replayer.replay(&mediator, &records)),
+            Replayed {
+                replayed: 4,
+                skipped: vec![4],
+            }
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "handle 1",
+                "handle_async 2",
+                "notify three",
+                "notify_async true"
+            ]
+        );
+
+        let partial = Replayer::new().notify::<String, _>();
+        assert_eq!(
+            block_on(partial.replay(&mediator, &records)).skipped,
+            [0, 1, 3, 4]
+        );
     }
 }
 
-#[cfg(doctest)]
+#[cfg(all(doctest, feature = "async"))]
 mod external_doctests {
     #[doc = include_str!("../README.md")]
     struct Readme;
@@ -0,0 +1,125 @@
+//! Rate limiting handlers.
+//!
+//! A [`RateLimiter`] is a token bucket: each call takes a token, and tokens are added back at a steady rate up to the bucket's burst size.
+//! A handler registered with a limiter either rejects calls when the bucket is empty, failing with [`RateLimited`]
+//! (see [`MediatorBuilder::add_try_handler_with_rate_limit`] and [`MediatorBuilder::add_async_try_handler_with_rate_limit`]),
+//! or, for asynchronous handlers, waits until a token is available (see [`MediatorBuilder::add_async_handler_with_rate_limit`]).
+//!
+//! Clones of a limiter share its bucket, so the same limiter can be given to several handlers to limit them together.
+//! ```rust
+//! use noon::limit::{RateLimited, RateLimiter};
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler_with_rate_limit(RateLimiter::new(10, 2), |x: i32| Ok::<_, RateLimited>(x * 2))
+//!     .build();
+//! assert_eq!(mediator.try_handle(1), Ok(2));
+//! assert_eq!(mediator.try_handle(2), Ok(4));
+//! assert_eq!(mediator.try_handle::<i32, i32, RateLimited, _>(3), Err(RateLimited));
+//! ```
+//!
+//! [`MediatorBuilder::add_try_handler_with_rate_limit`]: crate::mediator::MediatorBuilder::add_try_handler_with_rate_limit
+//! [`MediatorBuilder::add_async_try_handler_with_rate_limit`]: crate::mediator::MediatorBuilder::add_async_try_handler_with_rate_limit
+//! [`MediatorBuilder::add_async_handler_with_rate_limit`]: crate::mediator::MediatorBuilder::add_async_handler_with_rate_limit
+#[cfg(feature = "async")]
+use crate::time::Delay;
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The error returned instead of calling a rate-limited handler when its limiter has no tokens left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited;
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("rate limit exceeded")
+    }
+}
+
+impl Error for RateLimited {}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    burst: f64,
+    per_second: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.refilled_at = now;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Allows `per_second` calls each second on average, and up to `burst` calls at once. The bucket starts full.
+    ///
+    /// # Panics
+    /// If `per_second` or `burst` is zero.
+    pub fn new(per_second: u32, burst: u32) -> Self {
+        assert!(
+            per_second > 0,
+            "rate limit must allow at least one call per second"
+        );
+        assert!(
+            burst > 0,
+            "rate limit must allow bursts of at least one call"
+        );
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(burst),
+                burst: f64::from(burst),
+                per_second: f64::from(per_second),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// The number of calls which would currently be let through without waiting.
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        bucket.tokens as u32
+    }
+
+    /// Takes a token, or fails with how long to wait until one is available.
+    fn take(&self) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / bucket.per_second,
+            ))
+        }
+    }
+
+    /// Takes a token if one is available.
+    pub(crate) fn try_acquire(&self) -> Result<(), RateLimited> {
+        self.take().map_err(|_| RateLimited)
+    }
+
+    /// Waits until a token is available and takes it.
+    ///
+    /// Waiting calls aren't queued, so when several are waiting, any of them may take the next token.
+    #[cfg(feature = "async")]
+    pub(crate) async fn acquire(self) {
+        while let Err(wait) = self.take() {
+            Delay::new(wait).await;
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! The lock guarding state shared between clones of entries and handlers.
+//!
+//! With the `std` feature, this is [`std::sync::Mutex`], ignoring poisoning: the state it guards is consistent between statements,
+//! so a panic while it's locked leaves nothing to recover. Without it, this is a spin lock.
+#[cfg(not(feature = "std"))]
+use core::cell::UnsafeCell;
+#[cfg(not(feature = "std"))]
+use core::ops::{Deref, DerefMut};
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "std")]
+pub(crate) type MutexGuard<'a, T> = std::sync::MutexGuard<'a, T>;
+
+#[cfg(feature = "std")]
+pub(crate) struct Mutex<T: ?Sized>(std::sync::Mutex<T>);
+
+#[cfg(feature = "std")]
+impl<T> Mutex<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(std::sync::Mutex::new(value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Mutex<T> {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct Mutex<T: ?Sized> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the value is only reached through a `MutexGuard`, and `locked` lets a single guard exist at a time.
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> Mutex<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Mutex<T> {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        MutexGuard { mutex: self }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: this guard holds the lock.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: this guard holds the lock.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
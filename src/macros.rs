@@ -0,0 +1,169 @@
+//! Declarative registration.
+
+/// Builds a mediator from a list of registrations.
+///
+/// Each registration expands into the corresponding [`crate::mediator::MediatorBuilder`] call:
+/// - `handle TMsg -> TResp = handler;` registers a handler, see [`crate::mediator::MediatorBuilder::add_handler`].
+/// - `handle async TMsg -> TResp = handler;` registers an asynchronous handler.
+/// - `notify TMsg => [receiver, ...];` listens for `TMsg` and registers each notification receiver.
+/// - `notify async TMsg => [receiver, ...];` does the same for asynchronous notification receivers.
+///
+/// Starting the list with `shared;` builds a thread-safe mediator, see [`crate::mediator::MediatorBuilder::new_shared`].
+/// ```rust
+/// use noon::mediator::Mediate;
+///
+/// struct NewUser { id: i32 }
+/// struct AuditEvent(&'static str);
+///
+/// fn new_user(req: NewUser) -> i32 {
+///     req.id
+/// }
+///
+/// fn log_it(event: &AuditEvent) {
+///     println!("audit: {}", event.0);
+/// }
+///
+/// # #[cfg(feature = "async")] {
+/// let mediator = noon::mediator! {
+///     handle NewUser -> i32 = new_user;
+///     handle async u8 -> bool = |x: u8| async move { x > 1 };
+///     notify AuditEvent => [log_it, |_: &AuditEvent| {}];
+/// };
+/// assert_eq!(mediator.handle(NewUser { id: 5 }), 5);
+/// mediator.notify(&AuditEvent("created user 5"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mediator {
+    (shared; $($registrations:tt)*) => {
+        $crate::mediator!(@chain $crate::mediator::MediatorBuilder::new_shared(); $($registrations)*)
+    };
+    (@chain $builder:expr;) => {
+        $builder.build()
+    };
+    (@chain $builder:expr; handle async $($rest:tt)*) => {
+        $crate::mediator!(@handle $builder; add_async_handler(_, _, _); []; $($rest)*)
+    };
+    (@chain $builder:expr; handle $($rest:tt)*) => {
+        $crate::mediator!(@handle $builder; add_handler(_, _); []; $($rest)*)
+    };
+    // Message types are collected one token at a time, since a `ty` fragment can't be followed by `->`.
+    (@handle $builder:expr; $add:ident($($infer:tt)*); [$($msg:tt)*]; -> $resp:ty = $handler:expr; $($rest:tt)*) => {
+        $crate::mediator!(@chain $builder.$add::<$($msg)*, $resp, $($infer)*>($handler); $($rest)*)
+    };
+    (@handle $builder:expr; $add:ident($($infer:tt)*); [$($msg:tt)*]; $next:tt $($rest:tt)*) => {
+        $crate::mediator!(@handle $builder; $add($($infer)*); [$($msg)* $next]; $($rest)*)
+    };
+    (@chain $builder:expr; notify async $msg:ty => [$($receiver:expr),* $(,)?]; $($rest:tt)*) => {
+        $crate::mediator!(
+            @chain $builder
+                .listen_for_async::<$msg>()
+                $(.add_async_notification_receiver::<$msg, _, _, _>($receiver))*;
+            $($rest)*
+        )
+    };
+    (@chain $builder:expr; notify $msg:ty => [$($receiver:expr),* $(,)?]; $($rest:tt)*) => {
+        $crate::mediator!(
+            @chain $builder
+                .listen_for::<$msg>()
+                $(.add_notification_receiver::<$msg, _, _>($receiver))*;
+            $($rest)*
+        )
+    };
+    ($($registrations:tt)*) => {
+        $crate::mediator!(@chain $crate::mediator::MediatorBuilder::new(); $($registrations)*)
+    };
+}
+
+/// Declares a trait for the set of capabilities a mediator must have, see the [`crate::spec`] module.
+///
+/// Capabilities use the same syntax as [`mediator!`], without the receivers:
+/// - `handle TMsg -> TResp;` and `handle async TMsg -> TResp;` require a handler.
+/// - `notify TMsg;` and `notify async TMsg;` require a set of notification receivers.
+///
+/// The declared trait has one index parameter per capability, in order, followed by the flavor, which defaults to [`crate::flavor::Local`].
+/// It's implemented for every mediator with the required capabilities, and lets generic code call the corresponding [`crate::mediator::Mediate`] methods.
+/// Up to 32 capabilities can be declared in a single trait.
+/// ```rust
+/// use noon::mediator::{Mediate, MediatorBuilder};
+///
+/// struct NewUser { id: i32 }
+/// struct UserCreated(i32);
+///
+/// noon::mediator_spec! {
+///     pub trait UserMediator {
+///         handle NewUser -> i32;
+///         notify UserCreated;
+///     }
+/// }
+///
+/// fn create_user<M: UserMediator<I, J>, I, J>(mediator: &M, id: i32) {
+///     let id = mediator.handle(NewUser { id });
+///     mediator.notify(&UserCreated(id));
+/// }
+///
+/// let mediator = MediatorBuilder::new()
+///     .add_handler(|req: NewUser| req.id)
+///     .listen_for::<UserCreated>()
+///     .build();
+/// create_user(&mediator, 5);
+/// ```
+#[macro_export]
+macro_rules! mediator_spec {
+    ($(#[$attr:meta])* $vis:vis trait $name:ident { $($capabilities:tt)* }) => {
+        $crate::mediator_spec!(
+            @munch [$(#[$attr])*] [$vis] $name; []; [];
+            [I0 I1 I2 I3 I4 I5 I6 I7 I8 I9 I10 I11 I12 I13 I14 I15 I16 I17 I18 I19 I20 I21 I22 I23 I24 I25 I26 I27 I28 I29 I30 I31];
+            $($capabilities)*
+        );
+    };
+    (@munch [$($attr:tt)*] [$vis:vis] $name:ident; [$($bound:tt)*]; [$($index:ident)*]; [$($pool:ident)*];) => {
+        $($attr)*
+        $vis trait $name<$($index,)* S: $crate::flavor::Flavor = $crate::flavor::Local>:
+            $crate::mediator::Mediate<S> $($bound)*
+        {
+        }
+
+        impl<M, $($index,)* S: $crate::flavor::Flavor> $name<$($index,)* S> for M
+        where
+            M: $crate::mediator::Mediate<S> $($bound)*
+        {
+        }
+    };
+    (@munch $attr:tt $vis:tt $name:ident; $bound:tt; $index:tt; $pool:tt; handle async $($rest:tt)*) => {
+        $crate::mediator_spec!(@handle $attr $vis $name; $bound; $index; $pool; CanHandleAsync; []; $($rest)*);
+    };
+    (@munch $attr:tt $vis:tt $name:ident; $bound:tt; $index:tt; $pool:tt; handle $($rest:tt)*) => {
+        $crate::mediator_spec!(@handle $attr $vis $name; $bound; $index; $pool; CanHandle; []; $($rest)*);
+    };
+    (@handle $attr:tt $vis:tt $name:ident; [$($bound:tt)*]; [$($index:ident)*]; [$next:ident $($pool:ident)*]; $can:ident; [$($msg:tt)*]; -> $resp:ty; $($rest:tt)*) => {
+        $crate::mediator_spec!(
+            @munch $attr $vis $name;
+            [$($bound)* + $crate::spec::$can<$($msg)*, $resp, $next, S>];
+            [$($index)* $next];
+            [$($pool)*];
+            $($rest)*
+        );
+    };
+    (@handle $attr:tt $vis:tt $name:ident; $bound:tt; $index:tt; $pool:tt; $can:ident; [$($msg:tt)*]; $next:tt $($rest:tt)*) => {
+        $crate::mediator_spec!(@handle $attr $vis $name; $bound; $index; $pool; $can; [$($msg)* $next]; $($rest)*);
+    };
+    (@munch $attr:tt $vis:tt $name:ident; [$($bound:tt)*]; [$($index:ident)*]; [$next:ident $($pool:ident)*]; notify async $msg:ty; $($rest:tt)*) => {
+        $crate::mediator_spec!(
+            @munch $attr $vis $name;
+            [$($bound)* + $crate::spec::CanNotifyAsync<$msg, $next, S>];
+            [$($index)* $next];
+            [$($pool)*];
+            $($rest)*
+        );
+    };
+    (@munch $attr:tt $vis:tt $name:ident; [$($bound:tt)*]; [$($index:ident)*]; [$next:ident $($pool:ident)*]; notify $msg:ty; $($rest:tt)*) => {
+        $crate::mediator_spec!(
+            @munch $attr $vis $name;
+            [$($bound)* + $crate::spec::CanNotify<$msg, $next, S>];
+            [$($index)* $next];
+            [$($pool)*];
+            $($rest)*
+        );
+    };
+}
@@ -1,123 +1,2268 @@
+#[cfg(feature = "async")]
+use crate::actor::{Actor, Mailbox};
+#[cfg(feature = "std")]
+use crate::auth::{Authorizer, Unauthorized};
+#[cfg(feature = "std")]
+use crate::batch::{BatchPolicy, Batched, Batcher, Batching, FlushBatch};
+#[cfg(feature = "std")]
+use crate::breaker::{CircuitBreaker, CircuitOpen};
+#[cfg(feature = "std")]
+use crate::broadcast::BroadcastReceiver;
+#[cfg(feature = "async")]
+use crate::buffered::Buffered;
+#[cfg(feature = "std")]
+use crate::cache::{Cache, CacheMetrics, CachePolicy, CacheStats, Cached, Invalidate};
+#[cfg(feature = "std")]
+use crate::codec::Encode;
+use crate::concrete::Mediator;
+#[cfg(feature = "std")]
+use crate::context::{Context, Scoped};
+#[cfg(feature = "async")]
+use crate::debounce::Debounce;
+#[cfg(feature = "std")]
+use crate::debounce::Throttle;
+use crate::describe::{Describer, Description};
+#[cfg(feature = "std")]
+use crate::entry::ReceiveNotificationBroadcast;
 use crate::entry::{
-    ReceiveNotification, ReceiveNotificationAsync, RequestResponse, RequestResponseAsync,
+    ReceiveNotification, ReceiveNotificationOwned, RequestResponse, RequestResponseFn,
+    SubscriptionId, TryReceiveNotification, TryRequestResponse,
 };
-use crate::hlist::{ContainsAt, HList, HListExt, Cons, Nil};
-use crate::concrete::Mediator;
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+#[cfg(feature = "std")]
+use crate::envelope::Envelope;
+#[cfg(feature = "std")]
+use crate::events::{EventStore, StoredEvent};
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, BoxStream, IntoAsyncBehavior, IntoAsyncHandler, IntoStreamHandler};
+use crate::flavor::{
+    Flavor, IntoBehavior, IntoErased, IntoHandler, IntoReceiver, IntoTryReceiver, Local, Shared,
+};
+#[cfg(feature = "async")]
+use crate::handler::AsyncHandler;
+use crate::handler::{AlreadyHandled, Handler, Handlers};
+use crate::hlist::{
+    Balance, Concat, Cons, ContainsAt, Disjoint, HList, HListExt, HVisit, Nil, NotContains, Pluck,
+    Replace,
+};
+#[cfg(feature = "async")]
+use crate::hooks::Dispatched;
+#[cfg(feature = "std")]
+use crate::hooks::ReceiverPanic;
+use crate::hooks::{Dispatch, DispatchHooks};
+#[cfg(feature = "std")]
+use crate::idempotency::IdempotencyStore;
+#[cfg(feature = "ingest")]
+use crate::ingest::Ingest;
+#[cfg(feature = "std")]
+use crate::limit::{RateLimited, RateLimiter};
+use crate::lock::Mutex;
+use crate::message::{Command, Message, Query};
+#[cfg(feature = "std")]
+use crate::metrics::MetricsSink;
+#[cfg(feature = "async")]
+use crate::offload;
+#[cfg(feature = "std")]
+use crate::outbox::Outbox;
+#[cfg(feature = "async")]
+use crate::pipeline::NextAsync;
+#[cfg(feature = "async")]
+use crate::retry::RetryPolicy;
+#[cfg(feature = "async")]
+use crate::runtime::{Spawner, Timer};
+#[cfg(feature = "std")]
+use crate::schedule::Recurring;
+#[cfg(feature = "async")]
+use crate::service::HandlerService;
+#[cfg(feature = "std")]
+use crate::sink::NotificationSink;
+#[cfg(feature = "async")]
+use crate::stream::Stream;
+#[cfg(feature = "async")]
+use crate::subscription::{NotificationStream, StreamSender};
+#[cfg(feature = "async")]
+use crate::time::Elapsed;
+#[cfg(feature = "std")]
+use crate::unit::{self, UnitOfWork};
+#[cfg(feature = "std")]
+use crate::validate::{ValidationError, Validator};
+#[cfg(feature = "std")]
+use crate::view::{Capabilities, View};
 
-use std::future::Future;
-use std::pin::Pin;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+#[cfg(feature = "std")]
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cell::OnceCell;
+use core::cell::RefCell;
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
-pub trait Mediate {
+pub trait Mediate<S: Flavor = Local> {
     type Handlers: HList;
     type NotifyReceivers: HList;
 
     fn handle<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
     where
-        Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp>, I>;
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>;
+
+    /// Invokes a handler registered with [`MediatorBuilder::add_handler_unboxed`].
+    fn handle_unboxed<TMsg, TResp, F, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponseFn<TMsg, TResp, F>, I>,
+        F: Fn(TMsg) -> TResp;
 
+    /// Invokes the asynchronous handler for `msg`.
+    ///
+    /// The returned future is the one boxed by the handler's entry when it was called, so dispatching allocates it once.
+    /// It's only wrapped in a second allocation when after-dispatch hooks are registered, see [`crate::hooks`],
+    /// which [`Mediate::handle_async_future`] avoids.
+    #[cfg(feature = "async")]
     fn handle_async<TMsg: 'static, TResp: 'static, I>(
         &self,
         msg: TMsg,
-    ) -> Pin<Box<dyn Future<Output = TResp>>>
+    ) -> BoxFuture<'static, TResp, S>
     where
-        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp>, I>;
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>;
+
+    /// Like [`Mediate::handle_async`], but returns the future as a [`Dispatched`] rather than a [`BoxFuture`].
+    ///
+    /// The handler's future is still boxed by its entry, since the handler is stored type-erased,
+    /// but the after-dispatch hooks run without boxing it again.
+    /// ```rust
+    /// use noon::flavor::BoxFuture;
+    /// use noon::hooks::Dispatched;
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .add_async_handler(|x: i32| async move { x * 2 })
+    ///     .on_after_dispatch(|dispatch| println!("handled {}", dispatch.message))
+    ///     .build();
+    /// let fut: Dispatched<BoxFuture<'static, i32>> = mediator.handle_async_future(21);
+    /// # drop(fut);
+    /// ```
+    #[cfg(feature = "async")]
+    fn handle_async_future<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> Dispatched<BoxFuture<'static, TResp, S>, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>;
+
+    fn try_handle<TMsg, TResp, TErr, I>(&self, msg: TMsg) -> Result<TResp, TErr>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>;
+
+    #[cfg(feature = "async")]
+    fn try_handle_async<TMsg: 'static, TResp: 'static, TErr: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, Result<TResp, TErr>, S>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>;
+
+    #[cfg(feature = "async")]
+    fn handle_stream<TMsg, TItem, I>(&self, msg: TMsg) -> BoxStream<TItem, S>
+    where
+        Self::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>;
 
     fn notify<TMsg: ?Sized, I>(&self, msg: &TMsg)
     where
-        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg>, I>;
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>;
+
+    /// Notifies the fallible receivers for `TMsg` and `TErr`, registered with [`MediatorBuilder::try_listen_for`].
+    ///
+    /// Every receiver runs, even if an earlier one failed, and the errors are returned in the order the receivers were added.
+    fn try_notify<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Vec<TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>;
+
+    /// Like [`Mediate::try_notify`], but stops at the first receiver which fails and returns its error,
+    /// for receivers which form a chain of checks.
+    fn try_notify_each<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Result<(), TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>;
+
+    /// Notifies the asynchronous receivers for `TMsg` without waiting for them, by passing each receiver's future to `spawn`.
+    ///
+    /// The dispatch hooks run once every future has been spawned. A shared mediator produces `Send` futures,
+    /// which can be spawned onto a multi-threaded runtime.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// let mediator = MediatorBuilder::new_shared()
+    ///     .listen_for_async::<String>()
+    ///     .add_async_notification_receiver(|msg: String| async move { println!("{}", msg) })
+    ///     .build();
+    /// // for example, with tokio: `|fut| drop(tokio::spawn(fut))`
+    /// mediator.notify_detached("hello".to_string(), |fut| {
+    ///     std::thread::spawn(move || drop(fut));
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    fn notify_detached<TMsg: Clone + 'static, I, F>(&self, msg: TMsg, spawn: F)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        F: FnMut(BoxFuture<'static, (), S>);
+
+    /// Like [`Mediate::notify_detached`], passing each receiver's future to `spawner`, see [`crate::runtime`].
+    #[cfg(feature = "async")]
+    fn notify_spawned<TMsg: Clone + 'static, I>(&self, msg: TMsg, spawner: &impl Spawner<S>)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        self.notify_detached(msg, |fut| spawner.spawn(fut))
+    }
+
+    /// Notifies the observers registered for `TMsg` with a reference to `msg`, then moves `msg` into its consumer,
+    /// see [`MediatorBuilder::listen_for_owned`].
+    fn notify_owned<TMsg, I>(&self, msg: TMsg)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>;
+
+    #[cfg(feature = "async")]
+    fn notify_async<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>;
+
+    /// Notifies the asynchronous receivers registered with [`MediatorBuilder::listen_for_shared`], which share `msg`.
+    #[cfg(feature = "async")]
+    fn notify_shared<TMsg: ?Sized + 'static, I>(&self, msg: Arc<TMsg>) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>;
+
+    /// Sends `msg` to the receivers subscribed with [`Mediate::subscribe_broadcast`], returning how many there are, see [`crate::broadcast`].
+    #[cfg(feature = "std")]
+    fn notify_broadcast<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> usize
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>;
+
+    /// Like [`Mediate::handle`], with the response type determined by the message's [`Message`] implementation.
+    fn send<TMsg: Message, I>(&self, msg: TMsg) -> TMsg::Response
+    where
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TMsg::Response, S>, I>,
+    {
+        self.handle(msg)
+    }
+
+    /// Adds `msg` to the pending batch of the batch handler for `TMsg`, delivering the batch if it's due, see [`crate::batch`].
+    #[cfg(feature = "std")]
+    fn handle_batch<TMsg, I>(&self, msg: TMsg)
+    where
+        Self::Handlers: ContainsAt<RequestResponse<Batched<TMsg>, (), S>, I>,
+    {
+        self.handle(Batched(msg))
+    }
+
+    /// Delivers the pending batch of the batch handler for `TMsg`, returning its length.
+    #[cfg(feature = "std")]
+    fn flush_batch<TMsg, I>(&self) -> usize
+    where
+        Self::Handlers: ContainsAt<RequestResponse<FlushBatch<TMsg>, usize, S>, I>,
+    {
+        self.handle(FlushBatch::new())
+    }
+
+    /// Calls the asynchronous handler for every message of `msgs`, with at most `concurrency` calls in flight,
+    /// and returns a stream of the responses in the order the calls complete, see [`crate::buffered`].
+    ///
+    /// # Panics
+    /// If `concurrency` is zero.
+    #[cfg(feature = "async")]
+    fn handle_stream_of<TMsg: 'static, TResp: 'static, I, St>(
+        &self,
+        msgs: St,
+        concurrency: usize,
+    ) -> Buffered<'_, Self, St, TResp, I, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        St: Stream<Item = TMsg> + Unpin,
+    {
+        Buffered::new(self, msgs, concurrency)
+    }
+
+    /// Like [`Mediate::send`], for [`Command`]s.
+    fn send_command<TMsg: Command, I>(&self, msg: TMsg) -> TMsg::Response
+    where
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TMsg::Response, S>, I>,
+    {
+        self.handle(msg)
+    }
+
+    /// Like [`Mediate::send`], for [`Query`]s.
+    fn send_query<TMsg: Query, I>(&self, msg: TMsg) -> TMsg::Response
+    where
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TMsg::Response, S>, I>,
+    {
+        self.handle(msg)
+    }
 
-    fn notify_async<TMsg: Clone + 'static, I>(
+    #[cfg(feature = "async")]
+    fn send_async<TMsg: Message + 'static, I>(
         &self,
         msg: TMsg,
-    ) -> Pin<Box<dyn Future<Output = ()> + '_>>
+    ) -> BoxFuture<'static, TMsg::Response, S>
     where
-        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg>, I>;
+        TMsg::Response: 'static,
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TMsg::Response, S>, I>,
+    {
+        self.handle_async(msg)
+    }
+
+    /// Like [`Mediate::handle_async`], but blocks the current thread until the handler completes, see [`crate::blocking`].
+    #[cfg(feature = "blocking")]
+    fn handle_blocking<TMsg: 'static, TResp: 'static, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+    {
+        crate::blocking::block_on(self.handle_async(msg))
+    }
+
+    /// Invokes the handler for `TMsg` and `TResp`, whether it was registered as synchronous or asynchronous.
+    ///
+    /// A synchronous handler is called immediately, and the returned future is already complete.
+    /// `K` is the index of the handler wrapped in [`SyncAt`] or [`AsyncAt`], and should be left to the compiler to infer.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    /// # fn block_on<F: std::future::Future>(_: F) {}
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .add_handler(|x: i32| x * 2)
+    ///     .add_async_handler(|x: u8| async move { x + 1 })
+    ///     .build();
+    /// block_on(async {
+    ///     let doubled: i32 = mediator.handle_auto(21).await;
+    ///     let incremented: u8 = mediator.handle_auto(1u8).await;
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    fn handle_auto<TMsg: 'static, TResp: 'static, K>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        Self::Handlers: HandleAuto<TMsg, TResp, K, S>,
+    {
+        Self::Handlers::handle_auto(self, msg)
+    }
+
+    /// Notifies both the receivers registered with [`MediatorBuilder::listen_for`] and with [`MediatorBuilder::listen_for_async`] for `TMsg`.
+    ///
+    /// The synchronous receivers are called immediately, and the asynchronous ones when the returned future is awaited.
+    /// `I` and `J` are the indices of the two sets of receivers.
+    #[cfg(feature = "async")]
+    fn notify_all<TMsg: Clone + 'static, I, J>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>
+            + ContainsAt<ReceiveNotificationAsync<TMsg, S>, J>,
+    {
+        self.notify::<TMsg, I>(&msg);
+        self.notify_async::<TMsg, J>(msg)
+    }
+
+    /// Invokes a handler registered for `(TMsg, Context)` with `msg` and a copy of `ctx`, see the [`crate::context`] module.
+    #[cfg(feature = "std")]
+    fn handle_with_context<TMsg, TResp, I>(&self, msg: TMsg, ctx: &Context) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponse<(TMsg, Context), TResp, S>, I>,
+    {
+        self.handle((msg, ctx.clone()))
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_async_with_context<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+        ctx: &Context,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<(TMsg, Context), TResp, S>, I>,
+    {
+        self.handle_async((msg, ctx.clone()))
+    }
+
+    /// Like [`Mediate::handle_with_context`], for fallible handlers, such as those guarded by an authorizer, see [`crate::auth`].
+    #[cfg(feature = "std")]
+    fn try_handle_with_context<TMsg, TResp, TErr, I>(
+        &self,
+        msg: TMsg,
+        ctx: &Context,
+    ) -> Result<TResp, TErr>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponse<(TMsg, Context), TResp, TErr, S>, I>,
+    {
+        self.try_handle((msg, ctx.clone()))
+    }
+
+    /// A short-lived mediator carrying `ctx`, which dispatches everything to this one, for per-request state, see [`crate::context`].
+    #[cfg(feature = "std")]
+    fn scope(&self, ctx: Context) -> Scoped<'_, Self> {
+        Scoped::new(self, ctx)
+    }
+
+    /// Invokes a fallible handler registered for `(TMsg, Outbox<TEvent>)` with a new outbox,
+    /// then notifies the receivers for `TEvent` with the events it published if it succeeded, or discards them if it failed, see [`crate::outbox`].
+    #[cfg(feature = "std")]
+    fn try_handle_with_outbox<TMsg, TResp, TErr, TEvent, I, J>(
+        &self,
+        msg: TMsg,
+    ) -> Result<TResp, TErr>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponse<(TMsg, Outbox<TEvent>), TResp, TErr, S>, I>,
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TEvent, S>, J>,
+    {
+        let outbox = Outbox::new();
+        let result = self.try_handle((msg, outbox.clone()));
+        match result {
+            Ok(_) => outbox.commit(self),
+            Err(_) => outbox.rollback(),
+        };
+        result
+    }
+
+    /// Invokes a handler registered for `Envelope<TMsg>` with `msg` in a new envelope, starting a new correlation, see [`crate::envelope`].
+    #[cfg(feature = "std")]
+    fn handle_enveloped<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponse<Envelope<TMsg>, TResp, S>, I>,
+    {
+        self.handle(Envelope::new(msg))
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_async_enveloped<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<Envelope<TMsg>, TResp, S>, I>,
+    {
+        self.handle_async(Envelope::new(msg))
+    }
+
+    /// Notifies the receivers registered for `Envelope<TMsg>` with `msg` in a new envelope, starting a new correlation.
+    #[cfg(feature = "std")]
+    fn notify_enveloped<TMsg, I>(&self, msg: TMsg)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<Envelope<TMsg>, S>, I>,
+    {
+        self.notify(&Envelope::new(msg))
+    }
+
+    /// Registers a notification receiver on the built mediator, returning an id to pass to [`Mediate::unsubscribe`].
+    fn subscribe<TMsg: ?Sized, I, F>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: Fn(&TMsg) + IntoReceiver<S, TMsg>;
+
+    #[cfg(feature = "async")]
+    fn subscribe_async<TMsg: Clone, I, F, Fut>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        Fut: Future<Output = ()> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, ()>;
+
+    /// Registers a notification receiver which queues every subsequent `TMsg` for the returned stream, see [`crate::subscription`].
+    #[cfg(feature = "async")]
+    fn subscribe_stream<TMsg: Clone, I>(&self) -> NotificationStream<TMsg>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        StreamSender<TMsg>: IntoReceiver<S, TMsg>,
+    {
+        NotificationStream::new(|sender| self.notify_receivers().take().add(sender))
+    }
+
+    /// Subscribes to the messages sent with [`Mediate::notify_broadcast`] from now on, see [`crate::broadcast`].
+    #[cfg(feature = "std")]
+    fn subscribe_broadcast<TMsg: Clone, I>(&self) -> BroadcastReceiver<TMsg>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>,
+    {
+        self.notify_receivers().take().subscribe()
+    }
+
+    /// Removes a notification receiver, returning whether it was registered.
+    ///
+    /// Notifications already in flight still reach the receiver.
+    fn unsubscribe<TMsg: ?Sized, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>;
+
+    #[cfg(feature = "async")]
+    fn unsubscribe_async<TMsg: Clone, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>;
+
+    /// The handlers of this mediator, for composing mediators, see [`MediatorBuilder::with_parent_handlers`].
+    fn handlers(&self) -> &Self::Handlers;
+
+    /// The notification receivers of this mediator, for composing mediators, see [`MediatorBuilder::with_parent`].
+    fn notify_receivers(&self) -> &Self::NotifyReceivers;
+
+    /// Like [`Mediate::handle_async`], but fails with [`Elapsed`] if the handler doesn't complete within `timeout`.
+    #[cfg(feature = "async")]
+    fn handle_async_timeout<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+        timeout: Duration,
+    ) -> BoxFuture<'static, Result<TResp, Elapsed>, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+    {
+        S::timeout(self.handle_async(msg), timeout)
+    }
+
+    /// Like [`Mediate::notify_async`], but fails with [`Elapsed`] if the receivers don't all complete within `timeout`.
+    #[cfg(feature = "async")]
+    fn notify_async_timeout<TMsg: Clone + 'static, I>(
+        &self,
+        msg: TMsg,
+        timeout: Duration,
+    ) -> BoxFuture<'_, Result<(), Elapsed>, S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        S::timeout(self.notify_async(msg), timeout)
+    }
+
+    /// Notifies the asynchronous receivers for every item of `stream`, one at a time, see [`crate::ingest`].
+    #[cfg(feature = "ingest")]
+    fn ingest<St, I>(&self, stream: St) -> Ingest<'_, Self, St, I, S>
+    where
+        St: Stream + Unpin,
+        St::Item: Clone + 'static,
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<St::Item, S>, I>,
+    {
+        Ingest::new(self, stream)
+    }
+
+    /// Notifies the receivers for `TMsg` with a message from `factory` every `interval`, until the returned guard is dropped, see [`crate::schedule`].
+    ///
+    /// # Panics
+    /// If `interval` is zero.
+    #[cfg(feature = "std")]
+    fn notify_every<TMsg, I, F>(self: &Arc<Self>, factory: F, interval: Duration) -> Recurring
+    where
+        Self: Send + Sync + 'static,
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: FnMut() -> TMsg + Send + 'static,
+    {
+        let mediator = Arc::downgrade(self);
+        let mut factory = factory;
+        Recurring::spawn(interval, move || match mediator.upgrade() {
+            Some(mediator) => {
+                mediator.notify(&factory());
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Like [`Mediate::notify_every`], but notifies from a task of `spawner` which waits on `timer`, see [`crate::runtime`].
+    ///
+    /// # Panics
+    /// If `interval` is zero.
+    #[cfg(feature = "async")]
+    fn notify_every_on<TMsg, I, F>(
+        self: &Arc<Self>,
+        factory: F,
+        interval: Duration,
+        spawner: &impl Spawner,
+        timer: impl Timer + Send + 'static,
+    ) -> Recurring
+    where
+        Self: Send + Sync + 'static,
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: FnMut() -> TMsg + Send + 'static,
+    {
+        let mediator = Arc::downgrade(self);
+        let mut factory = factory;
+        Recurring::spawn_on(spawner, timer, interval, move || match mediator.upgrade() {
+            Some(mediator) => {
+                mediator.notify(&factory());
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Wraps this mediator into a service dispatching `TMsg` to its asynchronous handler, see [`crate::service`].
+    #[cfg(feature = "async")]
+    fn into_service<TMsg, TResp, I>(self) -> HandlerService<Self, TMsg, TResp, I, S>
+    where
+        Self: Sized,
+    {
+        HandlerService::new(self)
+    }
+
+    /// Moves this mediator into an actor, which runs the commands sent through the returned mailbox, see [`crate::actor`].
+    #[cfg(feature = "async")]
+    fn into_actor(self) -> (Mailbox<Self, S>, Actor)
+    where
+        Self: Sized + 'static,
+    {
+        Actor::new(self)
+    }
+
+    /// A view of this mediator which can only dispatch the messages listed in `P`, see [`crate::view`].
+    #[cfg(feature = "std")]
+    fn project<P: Capabilities>(&self) -> View<'_, Self, P, S> {
+        View::new(self)
+    }
+
+    /// The types of messages this mediator has handlers for and listens for, see [`crate::describe`].
+    fn describe(&self) -> Description
+    where
+        Self::Handlers: HVisit<Describer>,
+        Self::NotifyReceivers: HVisit<Describer>,
+    {
+        Description::new(self.handlers(), self.notify_receivers())
+    }
+
+    /// The entries of this mediator as a Graphviz graph, see [`crate::graph`].
+    #[cfg(feature = "std")]
+    fn to_dot(&self) -> String
+    where
+        Self::Handlers: HVisit<Describer>,
+        Self::NotifyReceivers: HVisit<Describer>,
+    {
+        self.describe().to_dot()
+    }
+
+    /// The entries of this mediator as a Mermaid flowchart, see [`crate::graph`].
+    #[cfg(feature = "std")]
+    fn to_mermaid(&self) -> String
+    where
+        Self::Handlers: HVisit<Describer>,
+        Self::NotifyReceivers: HVisit<Describer>,
+    {
+        self.describe().to_mermaid()
+    }
+}
+
+/// The index of a synchronous handler, for [`Mediate::handle_auto`].
+#[cfg(feature = "async")]
+pub struct SyncAt<I>(I);
+
+/// The index of an asynchronous handler, for [`Mediate::handle_auto`].
+#[cfg(feature = "async")]
+pub struct AsyncAt<I>(I);
+
+/// Handlers which can be invoked by [`Mediate::handle_auto`], at the index `K`.
+#[cfg(feature = "async")]
+pub trait HandleAuto<TMsg, TResp: 'static, K, S: Flavor> {
+    fn handle_auto<M: Mediate<S, Handlers = Self> + ?Sized>(
+        mediator: &M,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>;
+}
+
+#[cfg(feature = "async")]
+impl<H, TMsg, TResp: 'static, I> HandleAuto<TMsg, TResp, SyncAt<I>, Local> for H
+where
+    H: ContainsAt<RequestResponse<TMsg, TResp, Local>, I>,
+{
+    fn handle_auto<M: Mediate<Local, Handlers = Self> + ?Sized>(
+        mediator: &M,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp> {
+        Box::pin(core::future::ready(mediator.handle(msg)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<H, TMsg, TResp: Send + 'static, I> HandleAuto<TMsg, TResp, SyncAt<I>, Shared> for H
+where
+    H: ContainsAt<RequestResponse<TMsg, TResp, Shared>, I>,
+{
+    fn handle_auto<M: Mediate<Shared, Handlers = Self> + ?Sized>(
+        mediator: &M,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, Shared> {
+        Box::pin(core::future::ready(mediator.handle(msg)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<H, TMsg: 'static, TResp: 'static, I, S: Flavor> HandleAuto<TMsg, TResp, AsyncAt<I>, S> for H
+where
+    H: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+{
+    fn handle_auto<M: Mediate<S, Handlers = Self> + ?Sized>(
+        mediator: &M,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S> {
+        mediator.handle_async(msg)
+    }
+}
+
+/// Implements [`Mediate`] for a pointer to a mediator, delegating to the mediator.
+macro_rules! delegate_mediate {
+    ($($ptr:ty),*) => {$(
+        impl<M: Mediate<S> + ?Sized, S: Flavor> Mediate<S> for $ptr {
+            type Handlers = M::Handlers;
+            type NotifyReceivers = M::NotifyReceivers;
+
+            fn handle<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
+            where
+                Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+            {
+                (**self).handle(msg)
+            }
+
+            fn handle_unboxed<TMsg, TResp, F, I>(&self, msg: TMsg) -> TResp
+            where
+                Self::Handlers: ContainsAt<RequestResponseFn<TMsg, TResp, F>, I>,
+                F: Fn(TMsg) -> TResp,
+            {
+                (**self).handle_unboxed(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn handle_async<TMsg: 'static, TResp: 'static, I>(
+                &self,
+                msg: TMsg,
+            ) -> BoxFuture<'static, TResp, S>
+            where
+                Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+            {
+                (**self).handle_async(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn handle_async_future<TMsg: 'static, TResp: 'static, I>(
+                &self,
+                msg: TMsg,
+            ) -> Dispatched<BoxFuture<'static, TResp, S>, S>
+            where
+                Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+            {
+                (**self).handle_async_future(msg)
+            }
+
+            fn try_handle<TMsg, TResp, TErr, I>(&self, msg: TMsg) -> Result<TResp, TErr>
+            where
+                Self::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+            {
+                (**self).try_handle(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn try_handle_async<TMsg: 'static, TResp: 'static, TErr: 'static, I>(
+                &self,
+                msg: TMsg,
+            ) -> BoxFuture<'static, Result<TResp, TErr>, S>
+            where
+                Self::Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>,
+            {
+                (**self).try_handle_async(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn handle_stream<TMsg, TItem, I>(&self, msg: TMsg) -> BoxStream<TItem, S>
+            where
+                Self::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>,
+            {
+                (**self).handle_stream(msg)
+            }
+
+            fn notify<TMsg: ?Sized, I>(&self, msg: &TMsg)
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+            {
+                (**self).notify(msg)
+            }
+
+            fn try_notify<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Vec<TErr>
+            where
+                Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+            {
+                (**self).try_notify(msg)
+            }
+
+            fn try_notify_each<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Result<(), TErr>
+            where
+                Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+            {
+                (**self).try_notify_each(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn notify_detached<TMsg: Clone + 'static, I, F>(&self, msg: TMsg, spawn: F)
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+                F: FnMut(BoxFuture<'static, (), S>),
+            {
+                (**self).notify_detached(msg, spawn)
+            }
+
+            fn notify_owned<TMsg, I>(&self, msg: TMsg)
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
+            {
+                (**self).notify_owned(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn notify_async<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+            {
+                (**self).notify_async(msg)
+            }
+
+            #[cfg(feature = "async")]
+            fn notify_shared<TMsg: ?Sized + 'static, I>(
+                &self,
+                msg: Arc<TMsg>,
+            ) -> BoxFuture<'_, (), S>
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>,
+            {
+                (**self).notify_shared(msg)
+            }
+
+            #[cfg(feature = "std")]
+            fn notify_broadcast<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> usize
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>,
+            {
+                (**self).notify_broadcast(msg)
+            }
+
+            fn subscribe<TMsg: ?Sized, I, F>(&self, receiver: F) -> SubscriptionId
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+                F: Fn(&TMsg) + IntoReceiver<S, TMsg>,
+            {
+                (**self).subscribe(receiver)
+            }
+
+            #[cfg(feature = "async")]
+            fn subscribe_async<TMsg: Clone, I, F, Fut>(&self, receiver: F) -> SubscriptionId
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+                Fut: Future<Output = ()> + 'static,
+                F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, ()>,
+            {
+                (**self).subscribe_async(receiver)
+            }
+
+            fn unsubscribe<TMsg: ?Sized, I>(&self, id: SubscriptionId) -> bool
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+            {
+                (**self).unsubscribe::<TMsg, I>(id)
+            }
+
+            #[cfg(feature = "async")]
+            fn unsubscribe_async<TMsg: Clone, I>(&self, id: SubscriptionId) -> bool
+            where
+                Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+            {
+                (**self).unsubscribe_async::<TMsg, I>(id)
+            }
+
+            fn handlers(&self) -> &Self::Handlers {
+                (**self).handlers()
+            }
+
+            fn notify_receivers(&self) -> &Self::NotifyReceivers {
+                (**self).notify_receivers()
+            }
+        }
+    )*};
 }
 
-pub struct MediatorBuilder<H, N> {
+delegate_mediate!(&M, &mut M, Box<M>, Rc<M>, Arc<M>);
+#[cfg(feature = "std")]
+delegate_mediate!(crate::mock::MockMediator<M, S>, Scoped<'_, M>);
+
+pub struct MediatorBuilder<H, N, S: Flavor = Local> {
     contents: H,
-    receivers: N
+    receivers: N,
+    hooks: DispatchHooks<S>,
 }
 
 impl MediatorBuilder<Nil, Nil> {
     pub fn new() -> Self {
-	Self {
-	    contents: Nil,
-	    receivers: Nil
-	}
+        Self {
+            contents: Nil,
+            receivers: Nil,
+            hooks: DispatchHooks::new(),
+        }
+    }
+}
+
+impl Default for MediatorBuilder<Nil, Nil> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<H: HList, N: HList> MediatorBuilder<H, N> {
-    pub fn add_handler<TMsg, TResp>(
+/// Lists the entries registered so far, see [`crate::describe`].
+impl<H: HVisit<Describer>, N: HVisit<Describer>, S: Flavor> fmt::Debug
+    for MediatorBuilder<H, N, S>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Description::new(&self.contents, &self.receivers).debug_struct(f, "MediatorBuilder")
+    }
+}
+
+impl MediatorBuilder<Nil, Nil, Shared> {
+    /// Creates a builder for a thread-safe mediator.
+    ///
+    /// Every receiver registered with this builder must be `Send + Sync`, and asynchronous receivers must produce `Send` futures.
+    /// In return, the built mediator is `Send + Sync` and the futures it produces are `Send`.
+    pub fn new_shared() -> Self {
+        Self {
+            contents: Nil,
+            receivers: Nil,
+            hooks: DispatchHooks::new(),
+        }
+    }
+}
+
+impl<H: HList, N: HList, S: Flavor> MediatorBuilder<H, N, S> {
+    pub fn add_handler<TMsg, TResp, I, F>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp, S>, H>, N, S>
+    where
+        H: NotContains<RequestResponse<TMsg, TResp, S>, I>,
+        F: Fn(TMsg) -> TResp + IntoHandler<S, TMsg, TResp>,
+    {
+        let rr = RequestResponse::new(handler);
+        MediatorBuilder {
+            contents: self.contents.push(rr),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    /// Removes the handler registered for `TMsg` and `TResp`, along with its pipeline behaviors,
+    /// so a shared setup can be reused without one of its handlers, for example to register a different one in its place.
+    ///
+    /// Dispatching `TMsg` to the built mediator then fails to compile, as if the handler had never been registered.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .add_handler(|x: u32| x + 1)
+    ///     .add_handler(|x: u8| x + 1)
+    ///     .without_handler::<u32, u32, _>()
+    ///     .add_handler(|x: u32| x * 2)
+    ///     .build();
+    /// assert_eq!(mediator.handle::<u32, u32, _>(21), 42);
+    /// ```
+    pub fn without_handler<TMsg, TResp, I>(self) -> MediatorBuilder<H::Remainder, N, S>
+    where
+        H: Pluck<RequestResponse<TMsg, TResp, S>, I>,
+    {
+        MediatorBuilder {
+            contents: self.contents.pluck().1,
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    /// Registers every [`Handler`] of a tuple, as if by calling [`MediatorBuilder::add_handler_struct`] for each of them in order.
+    /// ```rust
+    /// use noon::handler::Handler;
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// struct Greeter;
+    /// impl Handler<String> for Greeter {
+    ///     type Response = String;
+    ///     fn handle(&self, name: String) -> String {
+    ///         format!("hello, {name}")
+    ///     }
+    /// }
+    ///
+    /// struct Doubler;
+    /// impl Handler<u32> for Doubler {
+    ///     type Response = u32;
+    ///     fn handle(&self, x: u32) -> u32 {
+    ///         x * 2
+    ///     }
+    /// }
+    ///
+    /// let mediator = MediatorBuilder::new().add_handlers((Greeter, Doubler)).build();
+    /// assert_eq!(mediator.handle::<_, String, _>("ada".to_string()), "hello, ada");
+    /// assert_eq!(mediator.handle::<u32, u32, _>(21), 42);
+    /// ```
+    pub fn add_handlers<M, T>(self, handlers: T) -> MediatorBuilder<T::Output, N, S>
+    where
+        T: Handlers<H, N, S, M>,
+    {
+        handlers.register(self)
+    }
+
+    /// Replaces the handler already registered for `TMsg` and `TResp`, keeping the builder's type,
+    /// so a test or an environment can override a registration made by shared setup code.
+    ///
+    /// Pipeline behaviors already added to the handler keep running around the new one.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .add_handler(|user: u32| format!("user {user} from the database"))
+    ///     .replace_handler(|user: u32| format!("user {user} from a fixture"))
+    ///     .build();
+    /// assert_eq!(mediator.handle::<_, String, _>(7), "user 7 from a fixture");
+    /// ```
+    pub fn replace_handler<TMsg, TResp, I, F>(self, handler: F) -> MediatorBuilder<H::Output, N, S>
+    where
+        H: Replace<RequestResponse<TMsg, TResp, S>, RequestResponse<TMsg, TResp, S>, I>,
+        F: Fn(TMsg) -> TResp + IntoHandler<S, TMsg, TResp>,
+    {
+        MediatorBuilder {
+            contents: self
+                .contents
+                .replace(|registered| registered.with_handler(handler)),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    /// Registers a handler without boxing it, for handlers on hot paths.
+    ///
+    /// The handler's type becomes part of the mediator's type, so calls to it through [`Mediate::handle_unboxed`] are statically dispatched and can be inlined.
+    /// Pipeline behaviors can't be added to unboxed handlers.
+    pub fn add_handler_unboxed<TMsg, TResp, F>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestResponseFn<TMsg, TResp, F>, H>, N, S>
+    where
+        F: Fn(TMsg) -> TResp + IntoHandler<S, TMsg, TResp>,
+    {
+        let rr = RequestResponseFn::new(handler);
+        MediatorBuilder {
+            contents: self.contents.push(rr),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn add_async_handler<TMsg, TResp: 'static, I, F, Fut>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp, S>, H>, N, S>
+    where
+        H: NotContains<RequestResponseAsync<TMsg, TResp, S>, I>,
+        Fut: Future<Output = TResp> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, TResp>,
+    {
+        let rr = RequestResponseAsync::new(handler);
+        MediatorBuilder {
+            contents: self.contents.push(rr),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    /// Registers a handler for a [`Message`], which must produce the message's response type.
+    #[allow(clippy::type_complexity)]
+    pub fn add_message_handler<TMsg: Message, I, F>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TMsg::Response, S>, H>, N, S>
+    where
+        H: NotContains<RequestResponse<TMsg, TMsg::Response, S>, I>,
+        F: Fn(TMsg) -> TMsg::Response + IntoHandler<S, TMsg, TMsg::Response>,
+    {
+        self.add_handler(handler)
+    }
+
+    #[cfg(feature = "async")]
+    #[allow(clippy::type_complexity)]
+    pub fn add_async_message_handler<TMsg: Message, I, F, Fut>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TMsg::Response, S>, H>, N, S>
+    where
+        H: NotContains<RequestResponseAsync<TMsg, TMsg::Response, S>, I>,
+        TMsg::Response: 'static,
+        Fut: Future<Output = TMsg::Response> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, TMsg::Response>,
+    {
+        self.add_async_handler(handler)
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn add_try_handler<TMsg, TResp, TErr, I, F>(
         self,
-        handler: impl Fn(TMsg) -> TResp + 'static,
-    ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp>, H>, N> {
-        let rr = RequestResponse::from(handler);
+        handler: F,
+    ) -> MediatorBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr, S>, H>, N, S>
+    where
+        H: NotContains<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+        F: Fn(TMsg) -> Result<TResp, TErr> + IntoHandler<S, TMsg, Result<TResp, TErr>>,
+    {
+        let rr = TryRequestResponse::new(handler);
         MediatorBuilder {
             contents: self.contents.push(rr),
-	    receivers: self.receivers
+            receivers: self.receivers,
+            hooks: self.hooks,
         }
     }
 
-    pub fn add_async_handler<TMsg, TResp, F, Fut>(
-	self,
-	handler: F
-    ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp>, H>, N>
+    #[cfg(feature = "async")]
+    #[allow(clippy::type_complexity)]
+    pub fn add_async_try_handler<TMsg, TResp: 'static, TErr: 'static, I, F, Fut>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<TryRequestResponseAsync<TMsg, TResp, TErr, S>, H>, N, S>
     where
-	Fut: Future<Output = TResp> + 'static,
-	F: Fn(TMsg) -> Fut + 'static
+        H: NotContains<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>,
+        Fut: Future<Output = Result<TResp, TErr>> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, Result<TResp, TErr>>,
     {
-	let rr = RequestResponseAsync::from(handler);
-	MediatorBuilder {
-	    contents: self.contents.push(rr),
-	    receivers: self.receivers
-	}
+        let rr = TryRequestResponseAsync::new(handler);
+        MediatorBuilder {
+            contents: self.contents.push(rr),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
     }
 
-    pub fn listen_for<TMsg: ?Sized>(self) -> MediatorBuilder<H, Cons<ReceiveNotification<TMsg>, N>> {
+    #[cfg(feature = "async")]
+    pub fn add_stream_handler<TMsg, TItem, F, St>(
+        self,
+        handler: F,
+    ) -> MediatorBuilder<Cons<RequestStream<TMsg, TItem, S>, H>, N, S>
+    where
+        St: Stream<Item = TItem> + 'static,
+        F: Fn(TMsg) -> St + IntoStreamHandler<S, TMsg, TItem>,
+    {
+        let rs = RequestStream::new(handler);
+        MediatorBuilder {
+            contents: self.contents.push(rs),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
+    }
+
+    pub fn listen_for<TMsg: ?Sized>(
+        self,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotification<TMsg, S>, N>, S> {
         let rn = ReceiveNotification::new();
         MediatorBuilder {
-	    contents: self.contents,
+            contents: self.contents,
             receivers: self.receivers.push(rn),
+            hooks: self.hooks,
         }
     }
 
-    pub fn listen_for_async<TMsg: Clone>(self) -> MediatorBuilder<H, Cons<ReceiveNotificationAsync<TMsg>, N>> {
+    /// Like [`MediatorBuilder::listen_for`], but keeps copies of the last `capacity` notifications,
+    /// which are sent to each receiver as it's added, such as those added with [`Mediate::subscribe`] or [`Mediate::subscribe_stream`].
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let mediator = MediatorBuilder::new().listen_for_with_replay::<u32>(2).build();
+    /// (1..=3).for_each(|x| mediator.notify(&x));
+    ///
+    /// let seen = Rc::new(RefCell::new(vec![]));
+    /// let log = seen.clone();
+    /// mediator.subscribe(move |x: &u32| log.borrow_mut().push(*x));
+    /// mediator.notify(&4);
+    /// assert_eq!(*seen.borrow(), [2, 3, 4]);
+    /// ```
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn listen_for_with_replay<TMsg: Clone + IntoErased<S> + 'static>(
+        self,
+        capacity: usize,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotification<TMsg, S>, N>, S> {
+        let rn = ReceiveNotification::with_replay(capacity);
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.push(rn),
+            hooks: self.hooks,
+        }
+    }
+
+    /// Listens for asynchronous notifications, which are cloned for each receiver.
+    ///
+    /// Unsized messages such as `str` or `[u8]` can be sent as `Arc<str>` or `Arc<[u8]>`, which are cheap to clone.
+    /// Alternatively, [`MediatorBuilder::listen_for_shared`] accepts the unsized type itself.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    /// use std::sync::Arc;
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .listen_for_async::<Arc<str>>()
+    ///     .add_async_notification_receiver(|line: Arc<str>| async move { println!("{}", line) })
+    ///     .listen_for_shared::<[u8]>()
+    ///     .add_shared_notification_receiver(|bytes: Arc<[u8]>| async move { println!("{:?}", bytes) })
+    ///     .build();
+    /// let _ = mediator.notify_async(Arc::from("hello"));
+    /// let _ = mediator.notify_shared(Arc::from(&b"hello"[..]));
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn listen_for_async<TMsg: Clone>(
+        self,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotificationAsync<TMsg, S>, N>, S> {
         let rn = ReceiveNotificationAsync::new();
         MediatorBuilder {
-	    contents: self.contents,
+            contents: self.contents,
             receivers: self.receivers.push(rn),
+            hooks: self.hooks,
         }
     }
 
-    pub fn add_notification_receiver<TMsg: ?Sized, I>(
-        mut self,
-        receiver: impl Fn(&TMsg) + 'static,
-    ) -> Self
+    /// Listens for notifications whose receivers can fail with `TErr`, see [`Mediate::try_notify`].
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// struct Order { quantity: u32 }
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .try_listen_for::<Order, String>()
+    ///     .add_try_notification_receiver(|order: &Order| {
+    ///         if order.quantity > 0 { Ok(()) } else { Err("empty order".to_string()) }
+    ///     })
+    ///     .build();
+    /// assert_eq!(mediator.try_notify(&Order { quantity: 0 }), vec!["empty order"]);
+    /// ```
+    pub fn try_listen_for<TMsg: ?Sized, TErr>(
+        self,
+    ) -> MediatorBuilder<H, Cons<TryReceiveNotification<TMsg, TErr, S>, N>, S> {
+        let rn = TryReceiveNotification::new();
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.push(rn),
+            hooks: self.hooks,
+        }
+    }
+
+    pub fn add_try_notification_receiver<TMsg: ?Sized, TErr, I, F>(self, receiver: F) -> Self
     where
-        N: ContainsAt<ReceiveNotification<TMsg>, I>,
+        N: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+        F: Fn(&TMsg) -> Result<(), TErr> + IntoTryReceiver<S, TMsg, TErr>,
+    {
+        self.receivers.take().add(receiver);
+        self
+    }
+
+    /// Listens for notifications which are moved into a single consumer, see [`Mediate::notify_owned`].
+    ///
+    /// The consumer is set with [`MediatorBuilder::set_notification_consumer`], and other receivers are added with
+    /// [`MediatorBuilder::add_notification_observer`]. Unlike [`MediatorBuilder::listen_for_async`], `TMsg` needn't be `Clone`.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// struct Upload { bytes: Vec<u8> }
+    ///
+    /// let mediator = MediatorBuilder::new()
+    ///     .listen_for_owned::<Upload>()
+    ///     .add_notification_observer(|upload: &Upload| println!("{} bytes", upload.bytes.len()))
+    ///     .set_notification_consumer(|upload: Upload| drop(upload.bytes))
+    ///     .build();
+    /// mediator.notify_owned(Upload { bytes: vec![0; 16] });
+    /// ```
+    pub fn listen_for_owned<TMsg>(
+        self,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotificationOwned<TMsg, S>, N>, S> {
+        let rn = ReceiveNotificationOwned::new();
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.push(rn),
+            hooks: self.hooks,
+        }
+    }
+
+    pub fn add_notification_observer<TMsg, I, F>(self, observer: F) -> Self
+    where
+        N: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
+        F: Fn(&TMsg) + IntoReceiver<S, TMsg>,
+    {
+        self.receivers.take().add_observer(observer);
+        self
+    }
+
+    /// Sets the receiver which takes ownership of notifications, replacing any previous one.
+    pub fn set_notification_consumer<TMsg, I, F>(mut self, consumer: F) -> Self
+    where
+        N: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
+        F: Fn(TMsg) + IntoHandler<S, TMsg, ()>,
+    {
+        self.receivers.take_mut().set_consumer(consumer);
+        self
+    }
+
+    /// Listens for asynchronous notifications which are shared between receivers through an [`Arc`], see [`Mediate::notify_shared`].
+    ///
+    /// Unlike [`MediatorBuilder::listen_for_async`], the message isn't cloned for each receiver, so `TMsg` needn't be `Clone`.
+    #[cfg(feature = "async")]
+    pub fn listen_for_shared<TMsg: ?Sized>(
+        self,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotificationShared<TMsg, S>, N>, S> {
+        let rn = ReceiveNotificationShared::new();
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.push(rn),
+            hooks: self.hooks,
+        }
+    }
+
+    /// Listens for notifications sent to a channel holding the last `capacity` messages, see [`crate::broadcast`].
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    #[cfg(feature = "std")]
+    pub fn listen_for_broadcast<TMsg: Clone>(
+        self,
+        capacity: usize,
+    ) -> MediatorBuilder<H, Cons<ReceiveNotificationBroadcast<TMsg, S>, N>, S> {
+        let rn = ReceiveNotificationBroadcast::new(capacity);
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.push(rn),
+            hooks: self.hooks,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn add_shared_notification_receiver<TMsg: ?Sized, I, F, Fut>(self, receiver: F) -> Self
+    where
+        N: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>,
+        Fut: Future<Output = ()> + 'static,
+        F: Fn(Arc<TMsg>) -> Fut + IntoAsyncHandler<S, Arc<TMsg>, ()>,
+    {
+        self.receivers.take().add(receiver);
+        self
+    }
+
+    pub fn add_notification_receiver<TMsg: ?Sized, I, F>(mut self, receiver: F) -> Self
+    where
+        N: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: Fn(&TMsg) + IntoReceiver<S, TMsg>,
     {
         let receiver_set = self.receivers.take_mut();
         receiver_set.add(receiver);
         self
     }
 
-    pub fn add_async_notification_receiver<TMsg: Clone, I, F, Fut>(
-	mut self,
-	receiver: F
-    ) -> Self
+    #[cfg(feature = "async")]
+    pub fn add_async_notification_receiver<TMsg: Clone, I, F, Fut>(mut self, receiver: F) -> Self
     where
-	N: ContainsAt<ReceiveNotificationAsync<TMsg>, I>,
-	Fut: Future<Output = ()> + 'static,
-	F: Fn(TMsg) -> Fut + 'static,
+        N: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        Fut: Future<Output = ()> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, ()>,
     {
-	let receiver_set = self.receivers.take_mut();
+        let receiver_set = self.receivers.take_mut();
         receiver_set.add(receiver);
         self
     }
 
-    pub fn build(self) -> impl Mediate<Handlers = H, NotifyReceivers = N> {
-        Mediator::new(self.contents, self.receivers)
+    pub fn add_pipeline<TMsg, TResp, I, F>(mut self, behavior: F) -> Self
+    where
+        H: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        F: Fn(TMsg, &dyn Fn(TMsg) -> TResp) -> TResp + IntoBehavior<S, TMsg, TResp>,
+    {
+        let handler = self.contents.take_mut();
+        handler.add_behavior(behavior);
+        self
+    }
+
+    #[cfg(feature = "async")]
+    pub fn add_async_pipeline<TMsg, TResp: 'static, I, F, Fut>(mut self, behavior: F) -> Self
+    where
+        H: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        Fut: Future<Output = TResp> + 'static,
+        F: Fn(TMsg, NextAsync<TMsg, TResp, S>) -> Fut + IntoAsyncBehavior<S, TMsg, TResp>,
+    {
+        let handler = self.contents.take_mut();
+        handler.add_behavior(behavior);
+        self
+    }
+
+    /// Isolates notification receivers from each other's panics, passing each caught panic to `hook`.
+    ///
+    /// Without this hook, a panicking receiver unwinds through [`Mediate::notify`] and the remaining receivers are skipped.
+    /// With it, every receiver runs under [`std::panic::catch_unwind`], and `notify` returns normally once all of them have run.
+    /// The process's panic hook still runs for each panic, which prints its message by default.
+    #[cfg(feature = "std")]
+    pub fn on_receiver_panic<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ReceiverPanic) + IntoReceiver<S, ReceiverPanic>,
+    {
+        self.hooks.set_on_panic(hook);
+        self
+    }
+
+    pub fn on_before_dispatch<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Dispatch) + IntoReceiver<S, Dispatch>,
+    {
+        self.hooks.add_before(hook);
+        self
+    }
+
+    pub fn on_after_dispatch<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Dispatch) + IntoReceiver<S, Dispatch>,
+    {
+        self.hooks.add_after(hook);
+        self
+    }
+
+    /// Combines the registrations from `other` with this builder's, for assembling a mediator from independently built parts.
+    ///
+    /// If both builders listen for the same type of message, the merged mediator has two sets of receivers for it,
+    /// so the index of the set must be given explicitly when notifying.
+    pub fn merge<H2, N2>(
+        mut self,
+        other: MediatorBuilder<H2, N2, S>,
+    ) -> MediatorBuilder<H::Output, N::Output, S>
+    where
+        H: Concat<H2>,
+        N: Concat<N2>,
+        H2: HList,
+        N2: HList,
+    {
+        self.hooks.extend(other.hooks);
+        MediatorBuilder {
+            contents: self.contents.concat(other.contents),
+            receivers: self.receivers.concat(other.receivers),
+            hooks: self.hooks,
+        }
+    }
+
+    pub fn build(self) -> impl Mediate<S, Handlers = H, NotifyReceivers = N> + fmt::Debug
+    where
+        H: HVisit<Describer>,
+        N: HVisit<Describer>,
+    {
+        Mediator::new(self.contents, self.receivers, self.hooks)
+    }
+
+    /// Forwards notifications to the receivers of `parent`, for types of messages this builder doesn't listen for.
+    ///
+    /// The built mediator shares receivers with `parent`, so receivers subscribed through either are notified through both.
+    /// Listening for a type of message which `parent` also listens for makes notifying it ambiguous, as with [`MediatorBuilder::merge`].
+    /// Only the dispatch hooks of the built mediator run for forwarded notifications.
+    /// ```rust
+    /// use noon::mediator::{Mediate, MediatorBuilder};
+    ///
+    /// struct Shutdown;
+    ///
+    /// let parent = MediatorBuilder::new()
+    ///     .listen_for::<Shutdown>()
+    ///     .add_notification_receiver(|_: &Shutdown| println!("shutting down"))
+    ///     .build();
+    /// let child = MediatorBuilder::new()
+    ///     .add_handler(|x: i32| x * 2)
+    ///     .with_parent(&parent)
+    ///     .build();
+    /// child.notify(&Shutdown);
+    /// ```
+    pub fn with_parent<P: Mediate<S>>(self, parent: &P) -> MediatorBuilder<H, N::Output, S>
+    where
+        N: Concat<P::NotifyReceivers>,
+        P::NotifyReceivers: Clone,
+    {
+        MediatorBuilder {
+            contents: self.contents,
+            receivers: self.receivers.concat(parent.notify_receivers().clone()),
+            hooks: self.hooks,
+        }
+    }
+
+    /// Like [`MediatorBuilder::with_parent`], but forwards requests to the handlers of `parent`.
+    ///
+    /// Registering a handler for the same types of message and response as one of `parent`'s is a compile-time error,
+    /// whether it's registered before or after calling this, see [`NotContains`] and [`Disjoint`].
+    /// ```rust,compile_fail
+    /// use noon::mediator::MediatorBuilder;
+    ///
+    /// let parent = MediatorBuilder::new().add_handler(|x: i32| x).build();
+    /// // Compile-time error, `parent` already has a handler from `i32` to `i32`.
+    /// let child = MediatorBuilder::new()
+    ///     .add_handler(|x: i32| x + 1)
+    ///     .with_parent_handlers(&parent)
+    ///     .build();
+    /// ```
+    pub fn with_parent_handlers<P: Mediate<S>, I>(
+        self,
+        parent: &P,
+    ) -> MediatorBuilder<H::Output, N, S>
+    where
+        H: Concat<P::Handlers> + Disjoint<P::Handlers, I>,
+        P::Handlers: Clone,
+    {
+        MediatorBuilder {
+            contents: self.contents.concat(parent.handlers().clone()),
+            receivers: self.receivers,
+            hooks: self.hooks,
+        }
     }
+
+    /// Like [`MediatorBuilder::build`], for handing the same mediator to many components.
+    pub fn build_arc(self) -> Arc<impl Mediate<S, Handlers = H, NotifyReceivers = N> + fmt::Debug>
+    where
+        H: HVisit<Describer>,
+        N: HVisit<Describer>,
+    {
+        Arc::new(self.build())
+    }
+
+    /// Like [`MediatorBuilder::build`], for mediators whose entries can all be cloned.
+    ///
+    /// Cloning the mediator is cheap, and clones share their receivers and hooks,
+    /// so a receiver subscribed through one clone is notified through all of them.
+    /// Every kind of entry can be cloned, except for handlers registered with [`MediatorBuilder::add_handler_unboxed`] whose closures aren't `Clone`.
+    pub fn build_cloneable(
+        self,
+    ) -> impl Mediate<S, Handlers = H, NotifyReceivers = N> + Clone + fmt::Debug
+    where
+        H: Clone + HVisit<Describer>,
+        N: Clone + HVisit<Describer>,
+    {
+        Mediator::new(self.contents, self.receivers, self.hooks)
+    }
+
+    /// Like [`MediatorBuilder::build`], but stores the receivers in balanced trees rather than lists, see [`Balance`].
+    ///
+    /// This keeps the compile-time cost of looking up a receiver low for mediators with many registrations,
+    /// which might otherwise exceed the compiler's `recursion_limit`.
+    pub fn build_balanced(
+        self,
+    ) -> impl Mediate<S, Handlers = H::Output, NotifyReceivers = N::Output> + fmt::Debug
+    where
+        H: Balance,
+        N: Balance,
+        H::Output: HVisit<Describer>,
+        N::Output: HVisit<Describer>,
+    {
+        Mediator::new(
+            self.contents.balance(),
+            self.receivers.balance(),
+            self.hooks,
+        )
+    }
+}
+
+/// Exclusive access to a value through a shared reference, a [`RefCell`] for [`Local`] builders and a [`Mutex`](std::sync::Mutex) for [`Shared`] ones.
+trait Exclusive<T> {
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R;
+}
+
+impl<T> Exclusive<T> for RefCell<T> {
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+impl<T> Exclusive<T> for Mutex<T> {
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock())
+    }
+}
+
+/// Implements the builder methods whose bounds depend on the flavor, given the bounds [`Shared`] adds to callbacks
+/// and their futures, the pointer to share callbacks with, and the cells to mutate and lazily initialize them in.
+macro_rules! flavor_builder {
+    ($flavor:ident, ($($sync:tt)*), ($($send:tt)*), $rc:ident, $cell:ident, $once:ident) => {
+        impl<H: HList, N: HList> MediatorBuilder<H, N, $flavor> {
+            pub fn add_handler_struct<TMsg, I, T>(
+                self,
+                handler: T,
+            ) -> MediatorBuilder<Cons<RequestResponse<TMsg, T::Response, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, T::Response, $flavor>, I>,
+                T: Handler<TMsg> $($sync)* + 'static,
+            {
+                self.add_handler(move |msg| handler.handle(msg))
+            }
+
+            #[cfg(feature = "async")]
+            pub fn add_async_handler_struct<TMsg, I, T>(
+                self,
+                handler: T,
+            ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, T::Response, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponseAsync<TMsg, T::Response, $flavor>, I>,
+                T: AsyncHandler<TMsg> $($sync)* + 'static,
+                T::Response: 'static,
+                T::Future: 'static $($send)*,
+            {
+                self.add_async_handler(move |msg| handler.handle(msg))
+            }
+            /// Registers a handler receiving a reference to `state` along with each message.
+            ///
+            /// The same `state` can be passed to several handlers to share it between them.
+            pub fn add_handler_with_state<TMsg, TResp, I, St, F>(
+                self,
+                state: Arc<St>,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>,
+                St: ?Sized $($sync)* + 'static,
+                F: Fn(&St, TMsg) -> TResp $($sync)* + 'static,
+            {
+                self.add_handler(move |msg| handler(&state, msg))
+            }
+
+            /// Registers an asynchronous handler receiving `state` along with each message.
+            ///
+            /// Since its futures can't borrow from the mediator, the handler receives its own handle to `state`.
+            #[cfg(feature = "async")]
+            pub fn add_async_handler_with_state<TMsg, TResp: 'static, I, St, F, Fut>(
+                self,
+                state: Arc<St>,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponseAsync<TMsg, TResp, $flavor>, I>,
+                St: ?Sized $($sync)* + 'static,
+                Fut: Future<Output = TResp> $($send)* + 'static,
+                F: Fn(Arc<St>, TMsg) -> Fut $($sync)* + 'static,
+            {
+                self.add_async_handler(move |msg| handler(Arc::clone(&state), msg))
+            }
+
+            /// Registers a synchronous handler which is run on its own thread when invoked, see [`crate::offload`].
+            ///
+            /// The handler is registered as an asynchronous handler, and invoked with [`Mediate::handle_async`],
+            /// so a slow handler doesn't block the executor polling the returned future.
+            #[cfg(feature = "async")]
+            pub fn add_blocking_handler<TMsg, TResp, I, F>(
+                self,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponseAsync<TMsg, TResp, $flavor>, I>,
+                TMsg: Send + 'static,
+                TResp: Send + 'static,
+                F: Fn(TMsg) -> TResp + Send + Sync + 'static,
+            {
+                let handler = Arc::new(handler);
+                self.add_async_handler(move |msg| {
+                    let handler = Arc::clone(&handler);
+                    offload::spawn_blocking(move || handler(msg))
+                })
+            }
+
+            /// Registers a fallible asynchronous handler which is called again when it fails with an error matching `policy`,
+            /// see [`crate::retry`]. Each attempt receives a clone of the message.
+            #[cfg(feature = "async")]
+            pub fn add_async_handler_with_retry<TMsg, TResp: 'static, TErr: 'static, I, F, Fut>(
+                self,
+                policy: RetryPolicy<TErr>,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, I>,
+                TMsg: Clone $($send)* + 'static,
+                Fut: Future<Output = Result<TResp, TErr>> $($send)* + 'static,
+                F: Fn(TMsg) -> Fut $($sync)* + 'static,
+            {
+                let handler = $rc::new(handler);
+                self.add_async_try_handler(move |msg: TMsg| {
+                    let handler = $rc::clone(&handler);
+                    policy.run(move || handler(msg.clone()))
+                })
+            }
+
+            /// Registers a fallible handler guarded by `breaker`, which fails fast with [`CircuitOpen`] while the circuit is open,
+            /// see [`crate::breaker`].
+            #[cfg(feature = "std")]
+            pub fn add_try_handler_with_breaker<TMsg, TResp, TErr, I, F>(
+                self,
+                breaker: CircuitBreaker,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponse<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<CircuitOpen>,
+                F: Fn(TMsg) -> Result<TResp, TErr> $($sync)* + 'static,
+            {
+                self.add_try_handler(move |msg| breaker.call(|| handler(msg)))
+            }
+
+            /// Like [`MediatorBuilder::add_try_handler_with_breaker`], for fallible asynchronous handlers.
+            ///
+            /// A call is recorded as a success or failure once its future completes.
+            #[cfg(feature = "async")]
+            pub fn add_async_try_handler_with_breaker<TMsg, TResp: 'static, TErr, I, F, Fut>(
+                self,
+                breaker: CircuitBreaker,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<CircuitOpen> + 'static,
+                Fut: Future<Output = Result<TResp, TErr>> $($send)* + 'static,
+                F: Fn(TMsg) -> Fut $($sync)* + 'static,
+            {
+                self.add_async_try_handler(move |msg| breaker.call_async(|| handler(msg)).run())
+            }
+
+            /// Registers a fallible handler which fails with [`RateLimited`] instead of being called once `limiter` runs out of tokens,
+            /// see [`crate::limit`].
+            #[cfg(feature = "std")]
+            pub fn add_try_handler_with_rate_limit<TMsg, TResp, TErr, I, F>(
+                self,
+                limiter: RateLimiter,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponse<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<RateLimited>,
+                F: Fn(TMsg) -> Result<TResp, TErr> $($sync)* + 'static,
+            {
+                self.add_try_handler(move |msg| {
+                    limiter.try_acquire()?;
+                    handler(msg)
+                })
+            }
+
+            /// Like [`MediatorBuilder::add_try_handler_with_rate_limit`], for fallible asynchronous handlers.
+            #[cfg(feature = "async")]
+            pub fn add_async_try_handler_with_rate_limit<TMsg, TResp: 'static, TErr, I, F, Fut>(
+                self,
+                limiter: RateLimiter,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponseAsync<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<RateLimited> + 'static,
+                Fut: Future<Output = Result<TResp, TErr>> $($send)* + 'static,
+                F: Fn(TMsg) -> Fut $($sync)* + 'static,
+            {
+                self.add_async_try_handler(move |msg| {
+                    let fut = limiter.try_acquire().map(|()| handler(msg));
+                    async move { fut?.await }
+                })
+            }
+
+            /// Checks every dispatch to the fallible handler for `(TMsg, Context)` with `authorizer` before calling the handler,
+            /// which is skipped if the dispatch is refused, see [`crate::auth`].
+            #[cfg(feature = "std")]
+            pub fn add_authorizer<TMsg, TResp, TErr, I, A>(mut self, authorizer: A) -> Self
+            where
+                H: ContainsAt<TryRequestResponse<(TMsg, Context), TResp, TErr, $flavor>, I>,
+                TErr: From<Unauthorized>,
+                A: Authorizer<TMsg> $($sync)* + 'static,
+            {
+                let handler = self.contents.take_mut();
+                handler.add_behavior(
+                    move |(msg, ctx): (TMsg, Context),
+                          next: &dyn Fn((TMsg, Context)) -> Result<TResp, TErr>| {
+                        authorizer.authorize(&ctx, &msg)?;
+                        next((msg, ctx))
+                    },
+                );
+                self
+            }
+
+            /// Checks every message sent to the fallible handler for `TMsg` with `validator` before calling the handler,
+            /// which is skipped if the message is rejected, see [`crate::validate`].
+            #[cfg(feature = "std")]
+            pub fn add_validator<TMsg, TResp, TErr, I, V>(mut self, validator: V) -> Self
+            where
+                H: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<ValidationError>,
+                V: Validator<TMsg> $($sync)* + 'static,
+            {
+                let handler = self.contents.take_mut();
+                handler.add_behavior(move |msg, next: &dyn Fn(TMsg) -> Result<TResp, TErr>| {
+                    validator.validate(&msg)?;
+                    next(msg)
+                });
+                self
+            }
+
+            /// Registers a fallible handler which is called within a unit of work begun by `unit`,
+            /// and which commits it on success or rolls it back on failure, see [`crate::unit`].
+            #[cfg(feature = "std")]
+            pub fn add_try_handler_with_unit_of_work<TMsg, TResp, TErr, I, U, F>(
+                self,
+                unit: U,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponse<TMsg, TResp, TErr, $flavor>, I>,
+                TErr: From<U::Error>,
+                U: UnitOfWork<TMsg> $($sync)* + 'static,
+                F: Fn(TMsg, &U::Work) -> Result<TResp, TErr> $($sync)* + 'static,
+            {
+                self.add_try_handler(move |msg| unit::run(&unit, msg, &handler))
+            }
+
+            /// Registers an asynchronous handler which waits until `limiter` has a token before being called, see [`crate::limit`].
+            #[cfg(feature = "async")]
+            pub fn add_async_handler_with_rate_limit<TMsg, TResp: 'static, I, F, Fut>(
+                self,
+                limiter: RateLimiter,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponseAsync<TMsg, TResp, $flavor>, I>,
+                TMsg: 'static $($send)*,
+                Fut: Future<Output = TResp> $($send)* + 'static,
+                F: Fn(TMsg) -> Fut $($sync)* + 'static,
+            {
+                let handler = $rc::new(handler);
+                self.add_async_handler(move |msg| {
+                    let (limiter, handler) = (limiter.clone(), $rc::clone(&handler));
+                    async move {
+                        limiter.acquire().await;
+                        handler(msg).await
+                    }
+                })
+            }
+
+            /// Transforms the responses of the handler for `TMsg` and `TResp`, changing its response type to `TNew`.
+            ///
+            /// The handler's pipeline behaviors still see the original response.
+            /// As with [`MediatorBuilder::add_handler`], the other handlers can't already include one for `TMsg` and `TNew`.
+            /// ```rust,compile_fail
+            /// use noon::mediator::MediatorBuilder;
+            ///
+            /// // Compile-time error, there's already a handler from `i32` to `String`.
+            /// let mediator = MediatorBuilder::new()
+            ///     .add_handler(|x: i32| x.to_string())
+            ///     .add_handler(|x: i32| x * 2)
+            ///     .map_response::<i32, i32, String, _, _, _>(|x| x.to_string())
+            ///     .build();
+            /// ```
+            pub fn map_response<TMsg, TResp, TNew, I, J, F>(
+                self,
+                f: F,
+            ) -> MediatorBuilder<H::Output, N, $flavor>
+            where
+                H: Replace<RequestResponse<TMsg, TResp, $flavor>, RequestResponse<TMsg, TNew, $flavor>, I>
+                    + Pluck<RequestResponse<TMsg, TResp, $flavor>, I>,
+                <H as Pluck<RequestResponse<TMsg, TResp, $flavor>, I>>::Remainder:
+                    NotContains<RequestResponse<TMsg, TNew, $flavor>, J>,
+                TMsg: 'static,
+                TResp: 'static,
+                F: Fn(TResp) -> TNew $($sync)* + 'static,
+            {
+                MediatorBuilder {
+                    contents: self
+                        .contents
+                        .replace(|handler| RequestResponse::new(move |msg| f(handler.call(msg)))),
+                    receivers: self.receivers,
+                    hooks: self.hooks,
+                }
+            }
+
+            /// Wraps the handler for `TMsg` and `TResp` with `f`, which receives the original handler along with each message.
+            ///
+            /// Unlike a pipeline behavior, the decorator only applies to this handler, and runs outside of its pipeline behaviors.
+            /// ```rust
+            /// use noon::mediator::{Mediate, MediatorBuilder};
+            ///
+            /// let mediator = MediatorBuilder::new()
+            ///     .add_handler(|x: i32| x * 2)
+            ///     .decorate_handler(|inner: &dyn Fn(i32) -> i32, x| if x < 0 { 0 } else { inner(x) })
+            ///     .build();
+            /// assert_eq!(mediator.handle(21), 42);
+            /// assert_eq!(mediator.handle(-1), 0);
+            /// ```
+            pub fn decorate_handler<TMsg, TResp, I, F>(self, f: F) -> MediatorBuilder<H::Output, N, $flavor>
+            where
+                H: Replace<RequestResponse<TMsg, TResp, $flavor>, RequestResponse<TMsg, TResp, $flavor>, I>,
+                TMsg: 'static,
+                TResp: 'static,
+                F: Fn(&dyn Fn(TMsg) -> TResp, TMsg) -> TResp $($sync)* + 'static,
+            {
+                MediatorBuilder {
+                    contents: self.contents.replace(|handler| {
+                        RequestResponse::new(move |msg| f(&|msg| handler.call(msg), msg))
+                    }),
+                    receivers: self.receivers,
+                    hooks: self.hooks,
+                }
+            }
+
+            /// Registers a handler for `TOld` which converts it with `f` and dispatches it to the handler for `TNew`,
+            /// for keeping old messages working while moving their callers over.
+            ///
+            /// The handler for `TNew` must already be registered. Its pipeline behaviors registered before the adapter run for adapted messages too.
+            pub fn map_request<TOld, TNew, TResp, I, J, F>(
+                self,
+                f: F,
+            ) -> MediatorBuilder<Cons<RequestResponse<TOld, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: ContainsAt<RequestResponse<TNew, TResp, $flavor>, I>
+                    + NotContains<RequestResponse<TOld, TResp, $flavor>, J>,
+                TNew: 'static,
+                TResp: 'static,
+                F: Fn(TOld) -> TNew $($sync)* + 'static,
+            {
+                let handler = self.contents.take().clone();
+                self.add_handler(move |msg| handler.call(f(msg)))
+            }
+
+            /// Registers a handler which can mutate its captured state.
+            ///
+            /// The handler is stored in a [`RefCell`] for [`Local`] builders, and in a [`Mutex`](std::sync::Mutex) for [`Shared`] ones,
+            /// where concurrent dispatches to it are serialized, and it deadlocks if it dispatches a message to itself.
+            /// A panic in the handler doesn't poison it, later dispatches still call it with whatever state the panic left behind.
+            ///
+            /// # Panics
+            /// For [`Local`] builders, dispatches panic with a [`BorrowMutError`](std::cell::BorrowMutError)
+            /// if the handler dispatches a message to itself.
+            pub fn add_handler_mut<TMsg, TResp, I, F>(
+                self,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>,
+                F: FnMut(TMsg) -> TResp $($send)* + 'static,
+            {
+                let handler = $cell::new(handler);
+                self.add_handler(move |msg| handler.with(|handler| handler(msg)))
+            }
+
+            /// Registers a handler built by `factory` when the first `TMsg` is handled, which suits handlers that are expensive to construct,
+            /// such as ones loading a model or opening a connection pool.
+            ///
+            /// The handler is stored in a [`OnceCell`] for [`Local`] builders, so it panics if `factory` dispatches a message to it,
+            /// and in a [`OnceLock`] for [`Shared`] ones, so concurrent first dispatches wait for a single call to `factory`,
+            /// and it deadlocks if `factory` dispatches a message to it.
+            ///
+            /// # Panics
+            /// Dispatches panic if `factory` panicked while building the handler for an earlier dispatch.
+            #[cfg(feature = "std")]
+            pub fn add_handler_lazy<TMsg, TResp, I, Fac, F>(
+                self,
+                factory: Fac,
+            ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>,
+                Fac: FnOnce() -> F $($send)* + 'static,
+                F: Fn(TMsg) -> TResp $($sync)* + 'static,
+            {
+                let factory = $cell::new(Some(factory));
+                let handler = $once::new();
+                self.add_handler(move |msg| {
+                    let handler = handler.get_or_init(|| {
+                        let factory = factory
+                            .with(Option::take)
+                            .expect("the lazy handler's factory panicked on an earlier dispatch");
+                        factory()
+                    });
+                    handler(msg)
+                })
+            }
+
+            /// Registers a handler whose responses are stored by message, along with handlers for [`Invalidate`] and [`CacheStats`],
+            /// see [`crate::cache`]. Responses are kept until they are invalidated.
+            ///
+            /// Pipeline behaviors added afterwards run for every dispatch, including ones answered from the cache.
+            /// For [`Shared`] builders, concurrent dispatches of an equal message which isn't stored yet may each call the handler.
+            #[cfg(feature = "std")]
+            pub fn add_cached_handler<TMsg, TResp, I, J, K, F>(
+                self,
+                handler: F,
+            ) -> MediatorBuilder<Cached<TMsg, TResp, H, $flavor>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>
+                    + NotContains<RequestResponse<Invalidate<TMsg>, (), $flavor>, J>
+                    + NotContains<RequestResponse<CacheStats<TMsg>, CacheMetrics, $flavor>, K>,
+                TMsg: Hash + Eq + Clone $($send)* + 'static,
+                TResp: Clone $($send)* + 'static,
+                F: Fn(TMsg) -> TResp $($sync)* + 'static,
+            {
+                self.add_cached_handler_with_policy(CachePolicy::new(), handler)
+            }
+
+            /// Like [`MediatorBuilder::add_cached_handler`], keeping responses according to `policy`.
+            #[cfg(feature = "std")]
+            pub fn add_cached_handler_with_policy<TMsg, TResp, I, J, K, F>(
+                self,
+                policy: CachePolicy,
+                handler: F,
+            ) -> MediatorBuilder<Cached<TMsg, TResp, H, $flavor>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>
+                    + NotContains<RequestResponse<Invalidate<TMsg>, (), $flavor>, J>
+                    + NotContains<RequestResponse<CacheStats<TMsg>, CacheMetrics, $flavor>, K>,
+                TMsg: Hash + Eq + Clone $($send)* + 'static,
+                TResp: Clone $($send)* + 'static,
+                F: Fn(TMsg) -> TResp $($sync)* + 'static,
+            {
+                let cache = $rc::new(Cache::new(policy));
+                let (invalidated, measured) = ($rc::clone(&cache), $rc::clone(&cache));
+                MediatorBuilder {
+                    contents: self
+                        .contents
+                        .push(RequestResponse::new(move |msg| {
+                            cache.get_or_insert_with(msg, &handler)
+                        }))
+                        .push(RequestResponse::new(move |which| {
+                            invalidated.invalidate(which)
+                        }))
+                        .push(RequestResponse::new(move |_: CacheStats<TMsg>| {
+                            measured.metrics()
+                        })),
+                    receivers: self.receivers,
+                    hooks: self.hooks,
+                }
+            }
+
+            /// Registers a handler which receives the messages dispatched with [`Mediate::handle_batch`] in batches, according to `policy`,
+            /// see [`crate::batch`].
+            #[cfg(feature = "std")]
+            pub fn add_batch_handler<TMsg, I, J, F>(
+                self,
+                policy: BatchPolicy,
+                handler: F,
+            ) -> MediatorBuilder<Batching<TMsg, H, $flavor>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<Batched<TMsg>, (), $flavor>, I>
+                    + NotContains<RequestResponse<FlushBatch<TMsg>, usize, $flavor>, J>,
+                TMsg: 'static $($send)*,
+                F: Fn(Vec<TMsg>) $($sync)* + 'static,
+            {
+                let batcher = $rc::new(Batcher::new(policy, handler));
+                let flushed = $rc::clone(&batcher);
+                MediatorBuilder {
+                    contents: self
+                        .contents
+                        .push(RequestResponse::new(move |Batched(msg)| batcher.push(msg)))
+                        .push(RequestResponse::new(move |_: FlushBatch<TMsg>| {
+                            flushed.flush()
+                        })),
+                    receivers: self.receivers,
+                    hooks: self.hooks,
+                }
+            }
+
+            /// Registers a handler which can only be called once, such as one handing out a resource it owns.
+            ///
+            /// It's registered as a fallible handler, so the first [`Mediate::try_handle`] returns its response,
+            /// and every later one returns [`AlreadyHandled`] without calling it.
+            /// Since mediators are dispatched to through shared references, a second call can't be ruled out at compile time.
+            /// ```rust
+            /// use noon::handler::AlreadyHandled;
+            /// use noon::mediator::{Mediate, MediatorBuilder};
+            ///
+            /// struct TakeConfig;
+            ///
+            /// let config = vec!["port=80".to_string()];
+            /// let mediator = MediatorBuilder::new()
+            ///     .add_once_handler(move |_: TakeConfig| config)
+            ///     .build();
+            /// assert_eq!(mediator.try_handle(TakeConfig), Ok(vec!["port=80".to_string()]));
+            /// assert_eq!(mediator.try_handle::<_, Vec<String>, _, _>(TakeConfig), Err(AlreadyHandled));
+            /// ```
+            pub fn add_once_handler<TMsg, TResp, I, F>(
+                self,
+                handler: F,
+            ) -> MediatorBuilder<Cons<TryRequestResponse<TMsg, TResp, AlreadyHandled, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<TryRequestResponse<TMsg, TResp, AlreadyHandled, $flavor>, I>,
+                F: FnOnce(TMsg) -> TResp $($send)* + 'static,
+            {
+                let handler = $cell::new(Some(handler));
+                self.add_try_handler(move |msg| {
+                    let handler = handler.with(Option::take).ok_or(AlreadyHandled)?;
+                    Ok(handler(msg))
+                })
+            }
+
+            /// Registers an asynchronous handler which can mutate its captured state when creating its futures.
+            ///
+            /// The handler is stored in a [`RefCell`] for [`Local`] builders, and in a [`Mutex`](std::sync::Mutex) for [`Shared`] ones,
+            /// which is only held while creating each future.
+            /// A panic in the handler doesn't poison it, later dispatches still call it with whatever state the panic left behind.
+            ///
+            /// # Panics
+            /// For [`Local`] builders, dispatches panic with a [`BorrowMutError`](std::cell::BorrowMutError)
+            /// if the handler dispatches a message to itself before returning its future.
+            #[cfg(feature = "async")]
+            pub fn add_async_handler_mut<TMsg, TResp: 'static, I, F, Fut>(
+                self,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponseAsync<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponseAsync<TMsg, TResp, $flavor>, I>,
+                Fut: Future<Output = TResp> $($send)* + 'static,
+                F: FnMut(TMsg) -> Fut $($send)* + 'static,
+            {
+                let handler = $cell::new(handler);
+                self.add_async_handler(move |msg| handler.with(|handler| handler(msg)))
+            }
+
+            /// Registers a notification receiver which is only called for messages matching `predicate`.
+            pub fn add_filtered_notification_receiver<TMsg: ?Sized, I, P, F>(
+                self,
+                predicate: P,
+                receiver: F,
+            ) -> Self
+            where
+                N: ContainsAt<ReceiveNotification<TMsg, $flavor>, I>,
+                P: Fn(&TMsg) -> bool $($sync)* + 'static,
+                F: Fn(&TMsg) $($sync)* + 'static,
+            {
+                self.add_notification_receiver(move |msg: &TMsg| {
+                    if predicate(msg) {
+                        receiver(msg)
+                    }
+                })
+            }
+
+            /// Registers a handler which remembers its response for the key `key` extracts from each message in `store`,
+            /// and returns the remembered response instead of calling `handler` for a message with the same key, see [`crate::idempotency`].
+            ///
+            /// For [`Shared`] builders, concurrent dispatches with the same key only call `handler` once
+            /// if `store` overrides [`IdempotencyStore::get_or_insert_with`], as [`MemoryIdempotencyStore`](crate::idempotency::MemoryIdempotencyStore) does.
+            #[cfg(feature = "std")]
+            pub fn add_idempotent_handler<TMsg, TResp, I, K, X, St, F>(
+                self,
+                key: X,
+                store: St,
+                handler: F,
+            ) -> MediatorBuilder<Cons<RequestResponse<TMsg, TResp, $flavor>, H>, N, $flavor>
+            where
+                H: NotContains<RequestResponse<TMsg, TResp, $flavor>, I>,
+                TResp: Clone,
+                X: Fn(&TMsg) -> K $($sync)* + 'static,
+                St: IdempotencyStore<K, TResp> $($sync)* + 'static,
+                F: Fn(TMsg) -> TResp $($sync)* + 'static,
+            {
+                self.add_handler(move |msg: TMsg| {
+                    let key = key(&msg);
+                    store.get_or_insert_with(key, || handler(msg))
+                })
+            }
+
+            /// Registers a notification receiver which skips the notifications whose key, extracted by `key`, is already in `store`,
+            /// see [`crate::idempotency`].
+            ///
+            /// As with [`MediatorBuilder::add_idempotent_handler`], concurrent notifications with the same key only call `receiver` once
+            /// if `store` overrides [`IdempotencyStore::get_or_insert_with`].
+            #[cfg(feature = "std")]
+            pub fn add_idempotent_notification_receiver<TMsg: ?Sized, I, K, X, St, F>(
+                self,
+                key: X,
+                store: St,
+                receiver: F,
+            ) -> Self
+            where
+                N: ContainsAt<ReceiveNotification<TMsg, $flavor>, I>,
+                X: Fn(&TMsg) -> K $($sync)* + 'static,
+                St: IdempotencyStore<K, ()> $($sync)* + 'static,
+                F: Fn(&TMsg) $($sync)* + 'static,
+            {
+                self.add_notification_receiver(move |msg: &TMsg| {
+                    store.get_or_insert_with(key(msg), || receiver(msg))
+                })
+            }
+
+            /// Registers a notification receiver which runs at most once per `interval`, skipping the notifications in between,
+            /// see [`crate::debounce`].
+            #[cfg(feature = "std")]
+            pub fn add_throttled_notification_receiver<TMsg: ?Sized, I, F>(
+                self,
+                interval: Duration,
+                receiver: F,
+            ) -> Self
+            where
+                N: ContainsAt<ReceiveNotification<TMsg, $flavor>, I>,
+                F: Fn(&TMsg) $($sync)* + 'static,
+            {
+                let throttle = Throttle::new(interval);
+                self.add_notification_receiver(move |msg: &TMsg| {
+                    if throttle.try_pass() {
+                        receiver(msg)
+                    }
+                })
+            }
+
+            /// Registers an asynchronous notification receiver which runs once no other notification was sent for `quiet`,
+            /// with the last notification, see [`crate::debounce`].
+            #[cfg(feature = "async")]
+            pub fn add_debounced_notification_receiver<TMsg, I, F, Fut>(
+                self,
+                quiet: Duration,
+                receiver: F,
+            ) -> Self
+            where
+                N: ContainsAt<ReceiveNotificationAsync<TMsg, $flavor>, I>,
+                TMsg: Clone $($send)* + 'static,
+                Fut: Future<Output = ()> $($send)* + 'static,
+                F: Fn(TMsg) -> Fut $($sync)* + 'static,
+            {
+                let debounce = $rc::new(Debounce::new(quiet));
+                let receiver = $rc::new(receiver);
+                self.add_async_notification_receiver(move |msg: TMsg| {
+                    let (debounce, receiver) = ($rc::clone(&debounce), $rc::clone(&receiver));
+                    let (quiet, call) = debounce.start();
+                    async move {
+                        quiet.await;
+                        if debounce.is_latest(call) {
+                            receiver(msg).await
+                        }
+                    }
+                })
+            }
+
+            /// Registers a notification receiver which sends a copy of every `TMsg` to `sink`, see [`crate::sink`].
+            #[cfg(feature = "std")]
+            pub fn forward_notifications_to<TMsg, I, K>(self, sink: K) -> Self
+            where
+                N: ContainsAt<ReceiveNotification<TMsg, $flavor>, I>,
+                TMsg: Clone + 'static,
+                K: NotificationSink<TMsg> $($sync)* + 'static,
+            {
+                self.add_notification_receiver(move |msg: &TMsg| sink.send(msg.clone()))
+            }
+
+            /// Registers a notification receiver which appends every `TMsg`, encoded and tagged with `tag`, to `store`, see [`crate::events`].
+            #[cfg(feature = "std")]
+            pub fn store_events_in<TMsg, I, St>(self, tag: &'static str, store: St) -> Self
+            where
+                N: ContainsAt<ReceiveNotification<TMsg, $flavor>, I>,
+                TMsg: Encode + 'static,
+                St: EventStore $($sync)* + 'static,
+            {
+                self.add_notification_receiver(move |msg: &TMsg| {
+                    store.append(StoredEvent::new(tag, msg.encode()))
+                })
+            }
+
+            /// Reports every dispatch through the built mediator to `sink`, see [`crate::metrics`].
+            #[cfg(feature = "std")]
+            pub fn with_metrics<M: MetricsSink $($sync)* + 'static>(mut self, sink: M) -> Self {
+                let sink = $rc::new(sink);
+                let counted = $rc::clone(&sink);
+                self.hooks
+                    .add_before(move |dispatch: &Dispatch| counted.increment_counter(dispatch));
+                self.hooks
+                    .add_timed(move |(dispatch, latency): &(Dispatch, Duration)| {
+                        sink.record_latency(dispatch, *latency)
+                    });
+                self
+            }
+        }
+    };
 }
+
+flavor_builder!(Local, (), (), Rc, RefCell, OnceCell);
+flavor_builder!(Shared, (+ Send + Sync), (+ Send), Arc, Mutex, OnceLock);
@@ -0,0 +1,87 @@
+//! Messages with a known response type.
+//!
+//! Implementing [`Message`] for a type of message ties it to the type of response its handler produces.
+//! Handlers registered with [`crate::mediator::MediatorBuilder::add_message_handler`] must produce that response,
+//! and [`crate::mediator::Mediate::send`] infers it, so call sites don't need type annotations.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::message::Message;
+//!
+//! struct CountUsers;
+//!
+//! impl Message for CountUsers {
+//!     type Response = u32;
+//! }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_message_handler(|_: CountUsers| 42)
+//!     .build();
+//! let total = mediator.send(CountUsers);
+//! assert_eq!(total, 42);
+//! ```
+//! With the `derive` feature enabled, `Message` can be derived instead, with the response type given in an attribute.
+//! ```rust,ignore
+//! use noon::message::Message;
+//!
+//! #[derive(Message)]
+//! #[message(response = u32)]
+//! struct CountUsers;
+//! ```
+pub trait Message {
+    type Response;
+}
+
+/// A message which changes state, dispatched with [`crate::mediator::Mediate::send_command`].
+///
+/// A command can only respond with `()` or `Result<(), E>`, so its caller learns whether it succeeded and nothing more.
+/// Together with [`Query`], this lets a codebase keep reads and writes apart through the type system.
+/// ```rust
+/// use noon::mediator::{Mediate, MediatorBuilder};
+/// use noon::message::{Command, Message, Query};
+///
+/// struct Rename(String);
+/// impl Message for Rename {
+///     type Response = Result<(), String>;
+/// }
+/// impl Command for Rename {}
+///
+/// struct GetName;
+/// impl Message for GetName {
+///     type Response = String;
+/// }
+/// impl Query for GetName {}
+///
+/// let mediator = MediatorBuilder::new()
+///     .add_message_handler(|Rename(name): Rename| if name.is_empty() { Err(name) } else { Ok(()) })
+///     .add_message_handler(|_: GetName| "ada".to_string())
+///     .build();
+/// assert_eq!(mediator.send_command(Rename("grace".to_string())), Ok(()));
+/// assert_eq!(mediator.send_query(GetName), "ada");
+/// ```
+/// A command with any other response doesn't compile.
+/// ```rust,compile_fail
+/// use noon::message::{Command, Message};
+///
+/// struct Rename(String);
+/// impl Message for Rename {
+///     type Response = String;
+/// }
+/// impl Command for Rename {}
+/// ```
+pub trait Command: Message<Response: CommandResponse> {}
+
+/// The responses a [`Command`] can have.
+pub trait CommandResponse {}
+
+impl CommandResponse for () {}
+
+impl<E> CommandResponse for Result<(), E> {}
+
+/// A message which reads state without changing it, dispatched with [`crate::mediator::Mediate::send_query`].
+pub trait Query: Message {}
+
+/// Derives [`Message`], with the response type given by a `#[message(response = ...)]` attribute.
+///
+/// Requires the `derive` feature.
+#[cfg(feature = "derive")]
+pub use noon_derive::Message;
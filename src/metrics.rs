@@ -0,0 +1,54 @@
+//! Reporting dispatch metrics.
+//!
+//! A [`MetricsSink`] installed with [`MediatorBuilder::with_metrics`] is told about every dispatch through the mediator,
+//! so it can forward counts and latencies to a metrics library, labelled with the [`Dispatch`]'s kind and type names.
+//! ```rust
+//! use noon::hooks::Dispatch;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::metrics::MetricsSink;
+//! use std::time::Duration;
+//!
+//! struct Log;
+//!
+//! impl MetricsSink for Log {
+//!     fn increment_counter(&self, dispatch: &Dispatch) {
+//!         println!("dispatching {}", dispatch.message);
+//!     }
+//!
+//!     fn record_latency(&self, dispatch: &Dispatch, latency: Duration) {
+//!         println!("{} took {:?}", dispatch.message, latency);
+//!     }
+//! }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|x: i32| x * 2)
+//!     .with_metrics(Log)
+//!     .build();
+//! mediator.handle(21);
+//! ```
+//!
+//! [`MediatorBuilder::with_metrics`]: crate::mediator::MediatorBuilder::with_metrics
+use crate::hooks::Dispatch;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+pub trait MetricsSink {
+    /// Counts a dispatch, when it starts.
+    fn increment_counter(&self, dispatch: &Dispatch);
+
+    /// Records how long a dispatch took, when it completes.
+    ///
+    /// Asynchronous dispatches complete when their future does, and streaming dispatches once the stream has been created.
+    fn record_latency(&self, dispatch: &Dispatch, latency: Duration);
+}
+
+impl<M: MetricsSink + ?Sized> MetricsSink for Arc<M> {
+    fn increment_counter(&self, dispatch: &Dispatch) {
+        (**self).increment_counter(dispatch)
+    }
+
+    fn record_latency(&self, dispatch: &Dispatch, latency: Duration) {
+        (**self).record_latency(dispatch, latency)
+    }
+}
@@ -0,0 +1,315 @@
+//! Mediators with canned responses, for testing the code which uses them.
+//!
+//! A [`MockMediator`] has handlers for the types of messages registered with its [`MockBuilder`], like a real mediator,
+//! but responds to each with the value stubbed by [`MockMediator::when`] rather than running any logic.
+//! Since it implements [`Mediate`], it can be passed to generic code bounded on a set of capabilities, see [`crate::mediator_spec`].
+//! ```rust
+//! use noon::mediator::Mediate;
+//! use noon::mock::MockBuilder;
+//!
+//! struct GetUser(u32);
+//! #[derive(Debug, Clone, PartialEq)]
+//! struct User { name: String }
+//! struct UserViewed(u32);
+//!
+//! noon::mediator_spec! {
+//!     trait Users {
+//!         handle GetUser -> User;
+//!         notify UserViewed;
+//!     }
+//! }
+//!
+//! fn greet<M: Users<I, J>, I, J>(mediator: &M, id: u32) -> String {
+//!     let user = mediator.handle(GetUser(id));
+//!     mediator.notify(&UserViewed(id));
+//!     format!("Hello, {}!", user.name)
+//! }
+//!
+//! let mock = MockBuilder::new()
+//!     .add_handler::<GetUser, User, _>()
+//!     .listen_for::<UserViewed>()
+//!     .build();
+//! mock.when::<GetUser>().return_value(User { name: "noon".to_string() });
+//! assert_eq!(greet(&mock, 1), "Hello, noon!");
+//! ```
+//!
+//! Notifications are accepted and dropped. To check which were sent, wrap the mock in a [`crate::spy::Spy`].
+use crate::describe::Describer;
+use crate::entry::{ReceiveNotification, RequestResponse, TryRequestResponse};
+#[cfg(feature = "async")]
+use crate::entry::{ReceiveNotificationAsync, RequestResponseAsync};
+use crate::flavor::{Flavor, IntoErased, Local, Shared};
+use crate::hlist::{Cons, HList, HVisit, Nil, NotContains};
+use crate::mediator::{Mediate, MediatorBuilder};
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+/// The responses stubbed for a mock, by the types of message and response.
+struct Stubs<S: Flavor> {
+    responses: Mutex<HashMap<Signature, Box<S::Erased>>>,
+}
+
+/// The types of a message and its response.
+type Signature = (TypeId, TypeId);
+
+impl<S: Flavor> Stubs<S> {
+    fn new() -> Self {
+        Self {
+            responses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn insert<TMsg: 'static, TResp: IntoErased<S> + 'static>(&self, resp: TResp) {
+        let key = (TypeId::of::<TMsg>(), TypeId::of::<TResp>());
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(key, resp.into_erased());
+    }
+
+    /// A copy of the response stubbed for a `TMsg`.
+    ///
+    /// # Panics
+    /// If no `TResp` is stubbed for a `TMsg`.
+    fn respond<TMsg: 'static, TResp: Clone + 'static>(&self) -> TResp {
+        let key = (TypeId::of::<TMsg>(), TypeId::of::<TResp>());
+        let responses = self.responses.lock().unwrap();
+        match responses
+            .get(&key)
+            .and_then(|resp| S::downcast_ref::<TResp>(resp))
+        {
+            Some(resp) => resp.clone(),
+            None => panic!(
+                "no `{}` response is stubbed for `{}`",
+                std::any::type_name::<TResp>(),
+                std::any::type_name::<TMsg>()
+            ),
+        }
+    }
+}
+
+/// Stubs the response to a `TMsg`, see [`MockMediator::when`].
+pub struct When<'a, TMsg, S: Flavor = Local> {
+    stubs: &'a Stubs<S>,
+    _message: PhantomData<fn(TMsg)>,
+}
+
+impl<TMsg: 'static, S: Flavor> When<'_, TMsg, S> {
+    /// Responds to every `TMsg` with a copy of `resp`, replacing any response stubbed before.
+    ///
+    /// Fallible handlers respond with a `Result`, so their stubs are `Ok` or `Err` values.
+    pub fn return_value<TResp: Clone + IntoErased<S> + 'static>(self, resp: TResp) {
+        self.stubs.insert::<TMsg, TResp>(resp)
+    }
+}
+
+/// A mediator which responds with stubbed values, see [`crate::mock`].
+pub struct MockMediator<M: ?Sized, S: Flavor = Local> {
+    stubs: Arc<Stubs<S>>,
+    mediator: M,
+}
+
+impl<M: Mediate<S>, S: Flavor> MockMediator<M, S> {
+    /// Stubs the response to a `TMsg`.
+    ///
+    /// Handling a message with no stubbed response panics.
+    pub fn when<TMsg: 'static>(&self) -> When<'_, TMsg, S> {
+        When {
+            stubs: &self.stubs,
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<M: ?Sized, S: Flavor> Deref for MockMediator<M, S> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.mediator
+    }
+}
+
+impl<M: ?Sized, S: Flavor> fmt::Debug for MockMediator<M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stubs = self.stubs.responses.lock().unwrap().len();
+        f.debug_struct("MockMediator")
+            .field("stubs", &stubs)
+            .finish_non_exhaustive()
+    }
+}
+
+pub struct MockBuilder<H, N, S: Flavor = Local> {
+    builder: MediatorBuilder<H, N, S>,
+    stubs: Arc<Stubs<S>>,
+}
+
+impl<S: Flavor> MockBuilder<Nil, Nil, S> {
+    fn with_builder(builder: MediatorBuilder<Nil, Nil, S>) -> Self {
+        Self {
+            builder,
+            stubs: Arc::new(Stubs::new()),
+        }
+    }
+}
+
+impl MockBuilder<Nil, Nil> {
+    pub fn new() -> Self {
+        Self::with_builder(MediatorBuilder::new())
+    }
+}
+
+impl Default for MockBuilder<Nil, Nil> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBuilder<Nil, Nil, Shared> {
+    /// Creates a builder for a thread-safe mock, see [`MediatorBuilder::new_shared`].
+    pub fn new_shared() -> Self {
+        Self::with_builder(MediatorBuilder::new_shared())
+    }
+}
+
+impl<H: HList, N: HList, S: Flavor> MockBuilder<H, N, S> {
+    pub fn listen_for<TMsg: ?Sized>(
+        self,
+    ) -> MockBuilder<H, Cons<ReceiveNotification<TMsg, S>, N>, S> {
+        MockBuilder {
+            builder: self.builder.listen_for(),
+            stubs: self.stubs,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn listen_for_async<TMsg: Clone>(
+        self,
+    ) -> MockBuilder<H, Cons<ReceiveNotificationAsync<TMsg, S>, N>, S> {
+        MockBuilder {
+            builder: self.builder.listen_for_async(),
+            stubs: self.stubs,
+        }
+    }
+
+    pub fn build(self) -> MockMediator<impl Mediate<S, Handlers = H, NotifyReceivers = N>, S>
+    where
+        H: HVisit<Describer>,
+        N: HVisit<Describer>,
+    {
+        MockMediator {
+            stubs: self.stubs,
+            mediator: self.builder.build(),
+        }
+    }
+}
+
+impl<H: HList, N: HList> MockBuilder<H, N, Local> {
+    /// Registers a handler for `TMsg`, which responds with the `TResp` stubbed for it.
+    pub fn add_handler<TMsg: 'static, TResp: Clone + 'static, I>(
+        self,
+    ) -> MockBuilder<Cons<RequestResponse<TMsg, TResp>, H>, N>
+    where
+        H: NotContains<RequestResponse<TMsg, TResp>, I>,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self
+                .builder
+                .add_handler(move |_: TMsg| stubs.respond::<TMsg, TResp>()),
+            stubs: self.stubs,
+        }
+    }
+
+    /// Registers an asynchronous handler for `TMsg`, which responds with the `TResp` stubbed for it.
+    #[cfg(feature = "async")]
+    pub fn add_async_handler<TMsg: 'static, TResp: Clone + 'static, I>(
+        self,
+    ) -> MockBuilder<Cons<RequestResponseAsync<TMsg, TResp>, H>, N>
+    where
+        H: NotContains<RequestResponseAsync<TMsg, TResp>, I>,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self.builder.add_async_handler(move |_: TMsg| {
+                std::future::ready(stubs.respond::<TMsg, TResp>())
+            }),
+            stubs: self.stubs,
+        }
+    }
+
+    /// Registers a fallible handler for `TMsg`, which responds with the `Result<TResp, TErr>` stubbed for it.
+    pub fn add_try_handler<TMsg: 'static, TResp: Clone + 'static, TErr: Clone + 'static, I>(
+        self,
+    ) -> MockBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr>, H>, N>
+    where
+        H: NotContains<TryRequestResponse<TMsg, TResp, TErr>, I>,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self
+                .builder
+                .add_try_handler(move |_: TMsg| stubs.respond::<TMsg, Result<TResp, TErr>>()),
+            stubs: self.stubs,
+        }
+    }
+}
+
+impl<H: HList, N: HList> MockBuilder<H, N, Shared> {
+    /// Registers a handler for `TMsg`, which responds with the `TResp` stubbed for it.
+    pub fn add_handler<TMsg: 'static, TResp: Clone + Send + Sync + 'static, I>(
+        self,
+    ) -> MockBuilder<Cons<RequestResponse<TMsg, TResp, Shared>, H>, N, Shared>
+    where
+        H: NotContains<RequestResponse<TMsg, TResp, Shared>, I>,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self
+                .builder
+                .add_handler(move |_: TMsg| stubs.respond::<TMsg, TResp>()),
+            stubs: self.stubs,
+        }
+    }
+
+    /// Registers an asynchronous handler for `TMsg`, which responds with the `TResp` stubbed for it.
+    #[cfg(feature = "async")]
+    pub fn add_async_handler<TMsg: 'static, TResp: Clone + Send + Sync + 'static, I>(
+        self,
+    ) -> MockBuilder<Cons<RequestResponseAsync<TMsg, TResp, Shared>, H>, N, Shared>
+    where
+        H: NotContains<RequestResponseAsync<TMsg, TResp, Shared>, I>,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self.builder.add_async_handler(move |_: TMsg| {
+                std::future::ready(stubs.respond::<TMsg, TResp>())
+            }),
+            stubs: self.stubs,
+        }
+    }
+
+    /// Registers a fallible handler for `TMsg`, which responds with the `Result<TResp, TErr>` stubbed for it.
+    #[allow(clippy::type_complexity)]
+    pub fn add_try_handler<TMsg, TResp, TErr, I>(
+        self,
+    ) -> MockBuilder<Cons<TryRequestResponse<TMsg, TResp, TErr, Shared>, H>, N, Shared>
+    where
+        H: NotContains<TryRequestResponse<TMsg, TResp, TErr, Shared>, I>,
+        TMsg: 'static,
+        TResp: Clone + Send + Sync + 'static,
+        TErr: Clone + Send + Sync + 'static,
+    {
+        let stubs = Arc::clone(&self.stubs);
+        MockBuilder {
+            builder: self
+                .builder
+                .add_try_handler(move |_: TMsg| stubs.respond::<TMsg, Result<TResp, TErr>>()),
+            stubs: self.stubs,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Running blocking work off the async executor.
+//!
+//! [`spawn_blocking`] runs a closure on its own thread and returns a future resolving to its result,
+//! so a slow synchronous handler doesn't stall the executor polling the future.
+//! Handlers are offloaded this way by registering them with [`crate::mediator::MediatorBuilder::add_blocking_handler`].
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+struct State<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// The future returned by [`spawn_blocking`].
+///
+/// If the closure panics, the panic is resumed when the future is polled.
+pub struct Blocking<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+/// Runs `f` on a new thread.
+pub fn spawn_blocking<T, F>(f: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(State {
+        result: None,
+        waker: None,
+    }));
+    let thread_state = Arc::clone(&state);
+    thread::Builder::new()
+        .name("noon-blocking".into())
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            let mut state = thread_state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        })
+        .expect("failed to spawn blocking thread");
+    Blocking { state }
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Holding back the notifications published by a handler until it succeeds.
+//!
+//! A handler registered for a `(TMsg, Outbox<TEvent>)` tuple publishes its events to the [`Outbox`] instead of notifying them directly.
+//! [`Mediate::try_handle_with_outbox`] then notifies the receivers for `TEvent` with the buffered events only if the handler returns `Ok`,
+//! and discards them otherwise, so observers never see events for work which was rolled back.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::outbox::Outbox;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! struct Withdraw(u32);
+//! struct Withdrawn(u32);
+//!
+//! let seen = Rc::new(RefCell::new(vec![]));
+//! let log = seen.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler(|(Withdraw(amount), outbox): (Withdraw, Outbox<Withdrawn>)| {
+//!         outbox.publish(Withdrawn(amount));
+//!         if amount > 100 {
+//!             return Err("insufficient funds");
+//!         }
+//!         Ok(())
+//!     })
+//!     .listen_for::<Withdrawn>()
+//!     .add_notification_receiver(move |Withdrawn(amount): &Withdrawn| log.borrow_mut().push(*amount))
+//!     .build();
+//!
+//! assert_eq!(mediator.try_handle_with_outbox(Withdraw(50)), Ok(()));
+//! assert_eq!(mediator.try_handle_with_outbox(Withdraw(500)), Err("insufficient funds"));
+//! assert_eq!(*seen.borrow(), [50]);
+//! ```
+//!
+//! An outbox can also be created and resolved by hand with [`Outbox::commit`] and [`Outbox::rollback`].
+//! Dropping an outbox without committing it discards its events.
+//!
+//! [`Mediate::try_handle_with_outbox`]: crate::mediator::Mediate::try_handle_with_outbox
+use crate::entry::ReceiveNotification;
+use crate::flavor::Flavor;
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A buffer of events to notify once the work which published them has succeeded, see [`crate::outbox`].
+///
+/// Clones share the same buffer.
+pub struct Outbox<TEvent> {
+    events: Arc<Mutex<Vec<TEvent>>>,
+}
+
+impl<TEvent> Outbox<TEvent> {
+    pub fn new() -> Self {
+        Self {
+            events: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn publish(&self, event: TEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    /// The number of events published and not yet committed or rolled back.
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Notifies the receivers for `TEvent` with every buffered event, in the order they were published,
+    /// and returns how many there were.
+    pub fn commit<M, I, S>(&self, mediator: &M) -> usize
+    where
+        M: Mediate<S> + ?Sized,
+        S: Flavor,
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TEvent, S>, I>,
+    {
+        // Taken before notifying, so a receiver publishing to the same outbox doesn't deadlock.
+        let events = mem::take(&mut *self.events.lock().unwrap());
+        events.iter().for_each(|event| mediator.notify(event));
+        events.len()
+    }
+
+    /// Discards every buffered event, returning how many there were.
+    pub fn rollback(&self) -> usize {
+        mem::take(&mut *self.events.lock().unwrap()).len()
+    }
+}
+
+impl<TEvent> Clone for Outbox<TEvent> {
+    fn clone(&self) -> Self {
+        Self {
+            events: Arc::clone(&self.events),
+        }
+    }
+}
+
+impl<TEvent> Default for Outbox<TEvent> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<TEvent> fmt::Debug for Outbox<TEvent> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Outbox")
+            .field("pending", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,47 @@
+//! Pipeline behaviors.
+//!
+//! A behavior wraps the handler registered for a message type, and can run code before and after it, or skip it entirely.
+//! Behaviors are registered with [`crate::mediator::MediatorBuilder::add_pipeline`] and [`crate::mediator::MediatorBuilder::add_async_pipeline`],
+//! and run in registration order, so the first behavior registered for a message type is the outermost.
+use crate::flavor::{AsyncBehavior, AsyncCallback, BoxFuture, Flavor, Local};
+
+use std::sync::Arc;
+
+pub(crate) struct AsyncPipeline<TMsg, TResp: 'static, S: Flavor> {
+    pub(crate) handler: Arc<AsyncCallback<TMsg, TResp, S>>,
+    pub(crate) behaviors: Vec<Arc<AsyncBehavior<TMsg, TResp, S>>>,
+}
+
+impl<TMsg, TResp: 'static, S: Flavor> Clone for AsyncPipeline<TMsg, TResp, S> {
+    fn clone(&self) -> Self {
+        Self {
+            handler: Arc::clone(&self.handler),
+            behaviors: self.behaviors.clone(),
+        }
+    }
+}
+
+/// The remainder of an asynchronous pipeline, ending with the handler itself.
+pub struct NextAsync<TMsg, TResp: 'static, S: Flavor = Local> {
+    pipeline: Arc<AsyncPipeline<TMsg, TResp, S>>,
+    index: usize,
+}
+
+impl<TMsg, TResp: 'static, S: Flavor> NextAsync<TMsg, TResp, S> {
+    pub(crate) fn new(pipeline: Arc<AsyncPipeline<TMsg, TResp, S>>) -> Self {
+        Self { pipeline, index: 0 }
+    }
+
+    pub fn call(self, msg: TMsg) -> BoxFuture<'static, TResp, S> {
+        match self.pipeline.behaviors.get(self.index) {
+            Some(behavior) => {
+                let next = NextAsync {
+                    pipeline: Arc::clone(&self.pipeline),
+                    index: self.index + 1,
+                };
+                behavior((msg, next))
+            }
+            None => (self.pipeline.handler)(msg),
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! Recording the messages dispatched through a mediator.
+//!
+//! A [`Recording`] wraps a mediator and logs a [`Record`] for each dispatch through it, in the order the dispatches started,
+//! for debugging and audit trails in development builds.
+//!
+//! Dispatches through [`Mediate`] only record the [`Dispatch`] describing them, since the types of messages aren't required to be `Debug` or `Clone`.
+//! Dispatching with [`Recording::handle_recorded`] and its siblings also records the message itself, which can be read back with [`Record::message`].
+//! ```rust
+//! use noon::hooks::DispatchKind;
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::recording::Recording;
+//!
+//! let mediator = Recording::new(
+//!     MediatorBuilder::new()
+//!         .add_handler(|x: i32| x * 2)
+//!         .listen_for::<str>()
+//!         .build(),
+//! );
+//! mediator.handle_recorded(21);
+//! mediator.notify("hello");
+//!
+//! let records = mediator.take_records();
+//! assert_eq!(records[0].message::<i32>(), Some(&21));
+//! assert_eq!(records[0].debug(), Some("21"));
+//! assert_eq!(records[1].dispatch.kind, DispatchKind::Notify);
+//! assert_eq!(records[1].debug(), None);
+//! ```
+//!
+//! The records can be fed back into a mediator with a [`crate::replay::Replayer`].
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationBroadcast, ReceiveNotificationOwned, RequestResponse,
+    RequestResponseFn, SubscriptionId, TryReceiveNotification, TryRequestResponse,
+};
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, BoxStream, IntoAsyncHandler};
+use crate::flavor::{Flavor, IntoErased, IntoReceiver, Local};
+use crate::hlist::ContainsAt;
+#[cfg(feature = "async")]
+use crate::hooks::Dispatched;
+use crate::hooks::{Dispatch, DispatchKind};
+use crate::mediator::Mediate;
+
+use std::any::TypeId;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// A dispatch logged by a [`Recording`].
+pub struct Record<S: Flavor = Local> {
+    pub dispatch: Dispatch,
+    /// When the dispatch started.
+    pub timestamp: SystemTime,
+    message: Option<(TypeId, Box<S::Erased>)>,
+    debug: Option<String>,
+}
+
+impl<S: Flavor> Record<S> {
+    fn new(dispatch: Dispatch) -> Self {
+        Self {
+            dispatch,
+            timestamp: SystemTime::now(),
+            message: None,
+            debug: None,
+        }
+    }
+
+    fn with_message<TMsg: fmt::Debug + IntoErased<S> + 'static>(
+        dispatch: Dispatch,
+        msg: TMsg,
+    ) -> Self {
+        Self {
+            debug: Some(format!("{:?}", msg)),
+            message: Some((TypeId::of::<TMsg>(), msg.into_erased())),
+            ..Self::new(dispatch)
+        }
+    }
+
+    /// A copy of the dispatched message, if it was recorded and is a `TMsg`.
+    pub fn message<TMsg: 'static>(&self) -> Option<&TMsg> {
+        self.message
+            .as_ref()
+            .and_then(|(_, msg)| S::downcast_ref(msg))
+    }
+
+    /// The type of the recorded message and the message itself, for replaying it.
+    #[cfg(feature = "async")]
+    pub(crate) fn erased(&self) -> Option<(TypeId, &S::Erased)> {
+        self.message.as_ref().map(|(id, msg)| (*id, &**msg))
+    }
+
+    /// The [`fmt::Debug`] representation of the dispatched message, if it was recorded.
+    pub fn debug(&self) -> Option<&str> {
+        self.debug.as_deref()
+    }
+}
+
+impl<S: Flavor> fmt::Debug for Record<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Record")
+            .field("dispatch", &self.dispatch)
+            .field("timestamp", &self.timestamp)
+            .field("message", &self.debug)
+            .finish()
+    }
+}
+
+/// A mediator which logs each dispatch through it, see [`crate::recording`].
+pub struct Recording<M, S: Flavor = Local> {
+    mediator: M,
+    records: Mutex<Vec<Record<S>>>,
+}
+
+impl<M: Mediate<S>, S: Flavor> Recording<M, S> {
+    pub fn new(mediator: M) -> Self {
+        Self {
+            mediator,
+            records: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.mediator
+    }
+
+    pub fn into_inner(self) -> M {
+        self.mediator
+    }
+
+    /// Removes and returns the records logged so far.
+    pub fn take_records(&self) -> Vec<Record<S>> {
+        std::mem::take(&mut *self.records.lock().unwrap())
+    }
+
+    /// The dispatches logged so far, without removing them.
+    pub fn dispatches(&self) -> Vec<Dispatch> {
+        let records = self.records.lock().unwrap();
+        records.iter().map(|record| record.dispatch).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Calls `f` with the records logged so far, without removing them.
+    pub(crate) fn inspect<R>(&self, f: impl FnOnce(&[Record<S>]) -> R) -> R {
+        f(&self.records.lock().unwrap())
+    }
+
+    fn push(&self, record: Record<S>) {
+        self.records.lock().unwrap().push(record);
+    }
+
+    /// Like [`Mediate::handle`], recording a copy of `msg`.
+    pub fn handle_recorded<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Clone + fmt::Debug + IntoErased<S> + 'static,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::Handle);
+        self.push(Record::with_message(dispatch, msg.clone()));
+        self.mediator.handle(msg)
+    }
+
+    /// Like [`Mediate::handle_async`], recording a copy of `msg`.
+    #[cfg(feature = "async")]
+    pub fn handle_async_recorded<TMsg, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        TMsg: Clone + fmt::Debug + IntoErased<S> + 'static,
+    {
+        let dispatch = Dispatch::handle::<TMsg, TResp>(DispatchKind::HandleAsync);
+        self.push(Record::with_message(dispatch, msg.clone()));
+        self.mediator.handle_async(msg)
+    }
+
+    /// Like [`Mediate::notify`], recording a copy of `msg`.
+    pub fn notify_recorded<TMsg, I>(&self, msg: &TMsg)
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        TMsg: Clone + fmt::Debug + IntoErased<S> + 'static,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::Notify);
+        self.push(Record::with_message(dispatch, msg.clone()));
+        self.mediator.notify(msg)
+    }
+
+    /// Like [`Mediate::notify_async`], recording a copy of `msg`.
+    #[cfg(feature = "async")]
+    pub fn notify_async_recorded<TMsg, I>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        TMsg: Clone + fmt::Debug + IntoErased<S> + 'static,
+    {
+        let dispatch = Dispatch::notify::<TMsg>(DispatchKind::NotifyAsync);
+        self.push(Record::with_message(dispatch, msg.clone()));
+        self.mediator.notify_async(msg)
+    }
+
+    fn record(&self, dispatch: Dispatch) {
+        self.push(Record::new(dispatch));
+    }
+}
+
+impl<M: Mediate<S>, S: Flavor> Mediate<S> for Recording<M, S> {
+    type Handlers = M::Handlers;
+    type NotifyReceivers = M::NotifyReceivers;
+
+    fn handle<TMsg, TResp, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(DispatchKind::Handle));
+        self.mediator.handle(msg)
+    }
+
+    fn handle_unboxed<TMsg, TResp, F, I>(&self, msg: TMsg) -> TResp
+    where
+        Self::Handlers: ContainsAt<RequestResponseFn<TMsg, TResp, F>, I>,
+        F: Fn(TMsg) -> TResp,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(DispatchKind::Handle));
+        self.mediator.handle_unboxed(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_async<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(DispatchKind::HandleAsync));
+        self.mediator.handle_async(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_async_future<TMsg: 'static, TResp: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> Dispatched<BoxFuture<'static, TResp, S>, S>
+    where
+        Self::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(DispatchKind::HandleAsync));
+        self.mediator.handle_async_future(msg)
+    }
+
+    fn try_handle<TMsg, TResp, TErr, I>(&self, msg: TMsg) -> Result<TResp, TErr>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(DispatchKind::TryHandle));
+        self.mediator.try_handle(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn try_handle_async<TMsg: 'static, TResp: 'static, TErr: 'static, I>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, Result<TResp, TErr>, S>
+    where
+        Self::Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TResp>(
+            DispatchKind::TryHandleAsync,
+        ));
+        self.mediator.try_handle_async(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn handle_stream<TMsg, TItem, I>(&self, msg: TMsg) -> BoxStream<TItem, S>
+    where
+        Self::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TItem>(DispatchKind::HandleStream));
+        self.mediator.handle_stream(msg)
+    }
+
+    fn notify<TMsg: ?Sized, I>(&self, msg: &TMsg)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::Notify));
+        self.mediator.notify(msg)
+    }
+
+    fn try_notify<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Vec<TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TErr>(DispatchKind::TryNotify));
+        self.mediator.try_notify(msg)
+    }
+
+    fn try_notify_each<TMsg: ?Sized, TErr, I>(&self, msg: &TMsg) -> Result<(), TErr>
+    where
+        Self::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+    {
+        self.record(Dispatch::handle::<TMsg, TErr>(DispatchKind::TryNotifyEach));
+        self.mediator.try_notify_each(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_detached<TMsg: Clone + 'static, I, F>(&self, msg: TMsg, spawn: F)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        F: FnMut(BoxFuture<'static, (), S>),
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::NotifyDetached));
+        self.mediator.notify_detached(msg, spawn)
+    }
+
+    fn notify_owned<TMsg, I>(&self, msg: TMsg)
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::NotifyOwned));
+        self.mediator.notify_owned(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_async<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::NotifyAsync));
+        self.mediator.notify_async(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn notify_shared<TMsg: ?Sized + 'static, I>(&self, msg: Arc<TMsg>) -> BoxFuture<'_, (), S>
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>,
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::NotifyShared));
+        self.mediator.notify_shared(msg)
+    }
+
+    fn notify_broadcast<TMsg: Clone + 'static, I>(&self, msg: TMsg) -> usize
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>,
+    {
+        self.record(Dispatch::notify::<TMsg>(DispatchKind::NotifyBroadcast));
+        self.mediator.notify_broadcast(msg)
+    }
+
+    fn subscribe<TMsg: ?Sized, I, F>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        F: Fn(&TMsg) + IntoReceiver<S, TMsg>,
+    {
+        self.mediator.subscribe(receiver)
+    }
+
+    #[cfg(feature = "async")]
+    fn subscribe_async<TMsg: Clone, I, F, Fut>(&self, receiver: F) -> SubscriptionId
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        Fut: Future<Output = ()> + 'static,
+        F: Fn(TMsg) -> Fut + IntoAsyncHandler<S, TMsg, ()>,
+    {
+        self.mediator.subscribe_async(receiver)
+    }
+
+    fn unsubscribe<TMsg: ?Sized, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+    {
+        self.mediator.unsubscribe::<TMsg, I>(id)
+    }
+
+    #[cfg(feature = "async")]
+    fn unsubscribe_async<TMsg: Clone, I>(&self, id: SubscriptionId) -> bool
+    where
+        Self::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+    {
+        self.mediator.unsubscribe_async::<TMsg, I>(id)
+    }
+
+    fn handlers(&self) -> &Self::Handlers {
+        self.mediator.handlers()
+    }
+
+    fn notify_receivers(&self) -> &Self::NotifyReceivers {
+        self.mediator.notify_receivers()
+    }
+}
@@ -0,0 +1,182 @@
+//! Collecting handlers at link time, behind the `registry` feature.
+//!
+//! Free functions annotated with [`#[noon::handler]`](macro@crate::handler) are collected into a list when the program is linked,
+//! wherever they are defined, so large applications don't have to maintain one function registering every handler.
+//! [`collect`] registers them into a [`DynamicMediator`], and [`verify`] checks that a mediator built with
+//! [`crate::mediator::MediatorBuilder`] has a handler for each of them.
+//! ```rust
+//! use noon::erased::Erased;
+//! use noon::mediator::MediatorBuilder;
+//! use noon::registry;
+//!
+//! #[noon::handler]
+//! fn double(x: i32) -> i32 {
+//!     x * 2
+//! }
+//!
+//! let mediator = registry::collect();
+//! assert_eq!(mediator.handle::<i32, i32>(21), Some(42));
+//!
+//! let built = MediatorBuilder::new().add_handler(|x: u8| x).build();
+//! let missing = registry::verify(&Erased::new(built)).unwrap_err();
+//! assert_eq!(missing.handlers(), ["rust_out::double"]);
+//! ```
+//!
+//! Registrations are stored in a linker section, so this only works on targets whose linker supports `__start_` and `__stop_` symbols,
+//! such as Linux and the BSDs. A crate is only linked if it's used, so a crate holding nothing but handlers needs a `use some_crate as _;`.
+//! Registrations are listed in an unspecified order.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly",
+    target_os = "illumos",
+    target_os = "fuchsia",
+)))]
+compile_error!("the `registry` feature is only supported on targets using ELF linker sections");
+
+use crate::dynamic::DynamicMediator;
+use crate::erased::DynMediate;
+use crate::flavor::Shared;
+
+use std::any::TypeId;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::slice;
+
+/// A handler collected by [`#[handler]`](macro@crate::handler), see [`crate::registry`].
+pub struct Registration {
+    name: &'static str,
+    message: fn() -> TypeId,
+    install: fn(&mut DynamicMediator),
+    install_shared: fn(&mut DynamicMediator<Shared>),
+}
+
+impl Registration {
+    #[doc(hidden)]
+    pub const fn new(
+        name: &'static str,
+        message: fn() -> TypeId,
+        install: fn(&mut DynamicMediator),
+        install_shared: fn(&mut DynamicMediator<Shared>),
+    ) -> Self {
+        Self {
+            name,
+            message,
+            install,
+            install_shared,
+        }
+    }
+
+    /// The path of the handler function.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The type of messages the handler receives.
+    pub fn message(&self) -> TypeId {
+        (self.message)()
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[doc(hidden)]
+pub fn message_type<TMsg: 'static, TResp, F: Fn(TMsg) -> TResp>(_: &F) -> TypeId {
+    TypeId::of::<TMsg>()
+}
+
+extern "Rust" {
+    #[link_name = "__start_noon_handlers"]
+    static START: Registration;
+    #[link_name = "__stop_noon_handlers"]
+    static STOP: Registration;
+}
+
+// Makes sure the section exists, so its bounds are defined even when nothing is registered.
+#[used]
+#[link_section = "noon_handlers"]
+static EMPTY: [Registration; 0] = [];
+
+/// Every handler registered with [`#[handler]`](macro@crate::handler) in the program.
+pub fn registrations() -> &'static [Registration] {
+    // SAFETY: the linker places every static in the `noon_handlers` section between these two symbols,
+    // and only `Registration`s are placed there.
+    unsafe {
+        let start = ptr::addr_of!(START);
+        let stop = ptr::addr_of!(STOP);
+        let len = (stop as usize - start as usize) / mem::size_of::<Registration>();
+        slice::from_raw_parts(start, len)
+    }
+}
+
+/// Creates a dynamic mediator holding every registered handler.
+///
+/// If several handlers are registered for the same message and response types, which one is kept is unspecified.
+pub fn collect() -> DynamicMediator {
+    let mut mediator = DynamicMediator::new();
+    for registration in registrations() {
+        (registration.install)(&mut mediator);
+    }
+    mediator
+}
+
+/// Creates a dynamic mediator holding every registered handler which can be shared between threads.
+pub fn collect_shared() -> DynamicMediator<Shared> {
+    let mut mediator = DynamicMediator::new_shared();
+    for registration in registrations() {
+        (registration.install_shared)(&mut mediator);
+    }
+    mediator
+}
+
+/// Checks that `mediator` has a handler for the messages of every registered handler.
+///
+/// Handlers are matched by message type only, since [`DynMediate`] doesn't expose response types.
+pub fn verify(mediator: &(impl DynMediate + ?Sized)) -> Result<(), Unregistered> {
+    let mut handlers: Vec<_> = registrations()
+        .iter()
+        .filter(|registration| !mediator.can_handle(registration.message()))
+        .map(Registration::name)
+        .collect();
+    if handlers.is_empty() {
+        return Ok(());
+    }
+    handlers.sort_unstable();
+    Err(Unregistered { handlers })
+}
+
+/// The registered handlers missing from a mediator, see [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unregistered {
+    handlers: Vec<&'static str>,
+}
+
+impl Unregistered {
+    /// The names of the missing handlers, sorted.
+    pub fn handlers(&self) -> &[&'static str] {
+        &self.handlers
+    }
+}
+
+impl fmt::Display for Unregistered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "handlers missing from the mediator: {}",
+            self.handlers.join(", ")
+        )
+    }
+}
+
+impl Error for Unregistered {}
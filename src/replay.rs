@@ -0,0 +1,202 @@
+//! Replaying recorded dispatches.
+//!
+//! A [`Replayer`] feeds the [`Record`]s captured by a [`crate::recording::Recording`] back into a mediator,
+//! which needn't be the one they were recorded from, for reproducing bugs and for tests.
+//! Records are replayed in order, and asynchronous dispatches are awaited before the next record is replayed.
+//!
+//! Since records only hold type-erased messages, each type of message to replay is registered with the replayer,
+//! along with how it was dispatched. Records which didn't capture their message, or whose type of message and dispatch
+//! wasn't registered, are skipped.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::recording::Recording;
+//! use noon::replay::Replayer;
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! # fn block_on<T>(fut: impl std::future::Future<Output = T>) -> T {
+//! #     let mut fut = std::pin::pin!(fut);
+//! #     let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+//! #     loop {
+//! #         if let std::task::Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+//! #             return out;
+//! #         }
+//! #     }
+//! # }
+//! let recording = Recording::new(MediatorBuilder::new().add_handler(|x: i32| x * 2).build());
+//! recording.handle_recorded(1);
+//! recording.handle_recorded(2);
+//!
+//! let total = Rc::new(Cell::new(0));
+//! let sum = Rc::clone(&total);
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(move |x: i32| sum.set(sum.get() + x))
+//!     .build();
+//! let replayed = block_on(
+//!     Replayer::new()
+//!         .handle::<i32, (), _>()
+//!         .replay(&mediator, &recording.take_records()),
+//! );
+//! assert_eq!(replayed.replayed, 2);
+//! assert_eq!(total.get(), 3);
+//! ```
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationAsync, RequestResponse, RequestResponseAsync,
+};
+use crate::flavor::{BoxFuture, Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::hooks::DispatchKind;
+use crate::mediator::Mediate;
+use crate::recording::Record;
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The outcome of [`Replayer::replay`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replayed {
+    /// The number of records replayed.
+    pub replayed: usize,
+    /// The indices of the records which were skipped.
+    pub skipped: Vec<usize>,
+}
+
+/// A replayed dispatch, which is either done or must be awaited before the next one.
+enum Step<'a, S: Flavor> {
+    Done,
+    Pending(BoxFuture<'a, (), S>),
+}
+
+type ReplayFn<M, S> = for<'a> fn(&'a M, &<S as Flavor>::Erased) -> Option<Step<'a, S>>;
+
+pub struct Replayer<M, S: Flavor = Local> {
+    replays: HashMap<(TypeId, DispatchKind), ReplayFn<M, S>>,
+}
+
+impl<M: Mediate<S>, S: Flavor> Replayer<M, S> {
+    pub fn new() -> Self {
+        Self {
+            replays: HashMap::new(),
+        }
+    }
+
+    /// Replays records of [`Mediate::handle`] with a `TMsg`, discarding the responses.
+    pub fn handle<TMsg, TResp, I>(self) -> Self
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        TMsg: Clone + 'static,
+    {
+        fn replay<'a, M, S, TMsg, TResp, I>(mediator: &'a M, msg: &S::Erased) -> Option<Step<'a, S>>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+            TMsg: Clone + 'static,
+        {
+            let msg = S::downcast_ref::<TMsg>(msg)?.clone();
+            mediator.handle(msg);
+            Some(Step::Done)
+        }
+        self.register::<TMsg>(DispatchKind::Handle, replay::<M, S, TMsg, TResp, I>)
+    }
+
+    /// Replays records of [`Mediate::handle_async`] with a `TMsg`, discarding the responses.
+    pub fn handle_async<TMsg, TResp, I>(self) -> Self
+    where
+        M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        TMsg: Clone + 'static,
+        TResp: 'static,
+    {
+        fn replay<'a, M, S, TMsg, TResp, I>(mediator: &'a M, msg: &S::Erased) -> Option<Step<'a, S>>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+            TMsg: Clone + 'static,
+            TResp: 'static,
+        {
+            let msg = S::downcast_ref::<TMsg>(msg)?.clone();
+            Some(Step::Pending(S::discard(mediator.handle_async(msg))))
+        }
+        self.register::<TMsg>(DispatchKind::HandleAsync, replay::<M, S, TMsg, TResp, I>)
+    }
+
+    /// Replays records of [`Mediate::notify`] with a `TMsg`.
+    pub fn notify<TMsg, I>(self) -> Self
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        TMsg: 'static,
+    {
+        fn replay<'a, M, S, TMsg, I>(mediator: &'a M, msg: &S::Erased) -> Option<Step<'a, S>>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+            TMsg: 'static,
+        {
+            mediator.notify(S::downcast_ref::<TMsg>(msg)?);
+            Some(Step::Done)
+        }
+        self.register::<TMsg>(DispatchKind::Notify, replay::<M, S, TMsg, I>)
+    }
+
+    /// Replays records of [`Mediate::notify_async`] with a `TMsg`.
+    pub fn notify_async<TMsg, I>(self) -> Self
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        TMsg: Clone + 'static,
+    {
+        fn replay<'a, M, S, TMsg, I>(mediator: &'a M, msg: &S::Erased) -> Option<Step<'a, S>>
+        where
+            M: Mediate<S>,
+            S: Flavor,
+            M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+            TMsg: Clone + 'static,
+        {
+            let msg = S::downcast_ref::<TMsg>(msg)?.clone();
+            Some(Step::Pending(mediator.notify_async(msg)))
+        }
+        self.register::<TMsg>(DispatchKind::NotifyAsync, replay::<M, S, TMsg, I>)
+    }
+
+    fn register<TMsg: 'static>(mut self, kind: DispatchKind, replay: ReplayFn<M, S>) -> Self {
+        self.replays.insert((TypeId::of::<TMsg>(), kind), replay);
+        self
+    }
+
+    /// Dispatches each of `records` through `mediator` in order, awaiting each asynchronous dispatch before the next.
+    pub async fn replay(&self, mediator: &M, records: &[Record<S>]) -> Replayed {
+        let mut replayed = Replayed::default();
+        for (index, record) in records.iter().enumerate() {
+            let step = record.erased().and_then(|(type_id, msg)| {
+                let replay = self.replays.get(&(type_id, record.dispatch.kind))?;
+                replay(mediator, msg)
+            });
+            match step {
+                Some(Step::Done) => {}
+                Some(Step::Pending(fut)) => fut.await,
+                None => {
+                    replayed.skipped.push(index);
+                    continue;
+                }
+            }
+            replayed.replayed += 1;
+        }
+        replayed
+    }
+}
+
+impl<M: Mediate<S>, S: Flavor> Default for Replayer<M, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M, S: Flavor> fmt::Debug for Replayer<M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replayer")
+            .field("replays", &self.replays.keys())
+            .finish()
+    }
+}
@@ -0,0 +1,109 @@
+//! Retrying fallible asynchronous handlers.
+//!
+//! A handler registered with [`MediatorBuilder::add_async_handler_with_retry`] is called again when it fails with an error matching
+//! its [`RetryPolicy`], waiting between attempts as configured by the policy's [`Backoff`]. Once the attempts run out,
+//! or the handler fails with an error which isn't retried, the error is returned to the caller of [`Mediate::try_handle_async`].
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::retry::{Backoff, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! #[derive(Clone)]
+//! struct FetchQuote(String);
+//!
+//! enum FetchError { Unavailable, NotFound }
+//!
+//! let policy = RetryPolicy::new(3)
+//!     .with_backoff(Backoff::Exponential { initial: Duration::from_millis(10), max: Duration::from_secs(1) })
+//!     .retry_if(|err: &FetchError| matches!(err, FetchError::Unavailable));
+//! let mediator = MediatorBuilder::new()
+//!     .add_async_handler_with_retry(policy, |req: FetchQuote| async move {
+//!         if req.0.is_empty() { Err(FetchError::NotFound) } else { Ok(42) }
+//!     })
+//!     .build();
+//! let _ = mediator.try_handle_async::<_, i32, FetchError, _>(FetchQuote("NOON".to_string()));
+//! ```
+//!
+//! [`MediatorBuilder::add_async_handler_with_retry`]: crate::mediator::MediatorBuilder::add_async_handler_with_retry
+//! [`Mediate::try_handle_async`]: crate::mediator::Mediate::try_handle_async
+use crate::time::Delay;
+
+use std::future::Future;
+use std::time::Duration;
+
+/// How long to wait before each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Waits the same duration before every retry.
+    Fixed(Duration),
+    /// Waits `initial` before the first retry, doubling the duration before each later retry up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+/// When and how often a failed handler is retried.
+pub struct RetryPolicy<TErr> {
+    max_attempts: u32,
+    backoff: Backoff,
+    retry_if: fn(&TErr) -> bool,
+}
+
+impl<TErr> Clone for RetryPolicy<TErr> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<TErr> Copy for RetryPolicy<TErr> {}
+
+impl<TErr> RetryPolicy<TErr> {
+    /// Calls the handler at most `max_attempts` times, retrying every error immediately.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::Fixed(Duration::ZERO),
+            retry_if: |_| true,
+        }
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only retries errors matching `predicate`, returning other errors immediately.
+    pub fn retry_if(mut self, predicate: fn(&TErr) -> bool) -> Self {
+        self.retry_if = predicate;
+        self
+    }
+
+    /// The delay before the retry following the `attempt`th failed attempt, counting from 1.
+    fn delay(&self, attempt: u32) -> Duration {
+        match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => initial
+                .checked_mul(2u32.saturating_pow(attempt - 1))
+                .map_or(max, |delay| delay.min(max)),
+        }
+    }
+
+    /// Calls `attempt` until it succeeds or fails with an error which shouldn't be retried.
+    pub(crate) async fn run<TResp, Fut>(
+        self,
+        mut attempt: impl FnMut() -> Fut,
+    ) -> Result<TResp, TErr>
+    where
+        Fut: Future<Output = Result<TResp, TErr>>,
+    {
+        let mut attempts = 1;
+        loop {
+            let delay = match attempt().await {
+                Err(err) if attempts < self.max_attempts && (self.retry_if)(&err) => {
+                    self.delay(attempts)
+                }
+                result => return result,
+            };
+            Delay::new(delay).await;
+            attempts += 1;
+        }
+    }
+}
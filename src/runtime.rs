@@ -0,0 +1,119 @@
+//! Plugging in an executor.
+//!
+//! A [`Spawner`] runs futures in the background, and a [`Timer`] produces futures completing after a duration.
+//! [`Mediate::notify_spawned`] hands the futures of asynchronous receivers to a spawner, and [`Mediate::notify_every_on`]
+//! schedules recurring notifications as a task of a spawner, waiting on a timer between them, instead of on a thread of its own.
+//! noon comes with [`Threads`], which drives each future on a new thread, and [`TimerThread`], which waits on noon's timer thread,
+//! so neither requires a runtime.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::runtime::{Threads, TimerThread};
+//! use std::sync::mpsc;
+//! use std::time::Duration;
+//!
+//! struct Tick;
+//!
+//! let (tx, rx) = mpsc::channel();
+//! let mediator = MediatorBuilder::new_shared()
+//!     .listen_for::<Tick>()
+//!     .add_notification_receiver(move |_: &Tick| tx.send(()).unwrap())
+//!     .build_arc();
+//! let recurring =
+//!     mediator.notify_every_on::<Tick, _, _>(|| Tick, Duration::from_millis(5), &Threads, TimerThread);
+//! rx.recv().unwrap();
+//! drop(recurring);
+//! ```
+//!
+//! Any other executor can be plugged in by implementing the traits, here for `tokio`:
+//! ```rust,ignore
+//! use noon::flavor::{BoxFuture, Shared};
+//! use noon::runtime::{Spawner, Timer};
+//! use std::time::Duration;
+//!
+//! struct Tokio;
+//!
+//! impl Spawner for Tokio {
+//!     fn spawn(&self, fut: BoxFuture<'static, (), Shared>) {
+//!         drop(tokio::spawn(fut));
+//!     }
+//! }
+//!
+//! impl Timer for Tokio {
+//!     fn sleep(&self, duration: Duration) -> BoxFuture<'static, (), Shared> {
+//!         Box::pin(tokio::time::sleep(duration))
+//!     }
+//! }
+//! ```
+//! Closures taking a future implement [`Spawner`] as well, such as `|fut| drop(async_std::task::spawn(fut))`.
+//!
+//! [`Mediate::notify_spawned`]: crate::mediator::Mediate::notify_spawned
+//! [`Mediate::notify_every_on`]: crate::mediator::Mediate::notify_every_on
+use crate::flavor::{BoxFuture, Flavor, Shared};
+use crate::time::Delay;
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// Runs futures of the flavor `S` in the background.
+pub trait Spawner<S: Flavor = Shared> {
+    fn spawn(&self, fut: BoxFuture<'static, (), S>);
+}
+
+impl<F: Fn(BoxFuture<'static, (), S>), S: Flavor> Spawner<S> for F {
+    fn spawn(&self, fut: BoxFuture<'static, (), S>) {
+        self(fut)
+    }
+}
+
+/// Produces futures completing after a duration.
+pub trait Timer {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, (), Shared>;
+}
+
+/// Runs each future to completion on a new thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Threads;
+
+impl Spawner for Threads {
+    fn spawn(&self, fut: BoxFuture<'static, (), Shared>) {
+        thread::Builder::new()
+            .name("noon-spawned".into())
+            .spawn(move || block_on(fut))
+            .expect("failed to spawn the future's thread");
+    }
+}
+
+/// Waits on noon's timer thread, see [`crate::time::Delay`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimerThread;
+
+impl Timer for TimerThread {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, (), Shared> {
+        Box::pin(Delay::new(duration))
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `fut` to completion on the current thread, parking it while `fut` is pending.
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Sending notifications periodically.
+//!
+//! [`Mediate::notify_every`] notifies the receivers for a type of message at a fixed interval from a background thread,
+//! with a message produced by a factory each time, until the returned [`Recurring`] guard or the mediator is dropped.
+//! This suits heartbeats and ticks which would otherwise each need their own background task.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! struct Tick;
+//!
+//! let ticks = Arc::new(AtomicU32::new(0));
+//! let counted = ticks.clone();
+//! let mediator = MediatorBuilder::new_shared()
+//!     .listen_for::<Tick>()
+//!     .add_notification_receiver(move |_: &Tick| {
+//!         counted.fetch_add(1, Ordering::SeqCst);
+//!     })
+//!     .build_arc();
+//! let recurring = mediator.notify_every::<Tick, _, _>(|| Tick, Duration::from_millis(10));
+//! std::thread::sleep(Duration::from_millis(55));
+//! drop(recurring);
+//! assert!(ticks.load(Ordering::SeqCst) >= 1);
+//! ```
+//!
+//! The mediator is only held weakly, and has to be thread-safe since it's notified from another thread, see [`crate::flavor::Shared`].
+//! The first notification is sent one interval after the call. If notifying takes longer than the interval, the missed ticks are skipped.
+//!
+//! [`Mediate::notify_every_on`] schedules the notifications as a task of an executor instead, see [`crate::runtime`].
+//!
+//! [`Mediate::notify_every`]: crate::mediator::Mediate::notify_every
+//! [`Mediate::notify_every_on`]: crate::mediator::Mediate::notify_every_on
+#[cfg(feature = "async")]
+use crate::runtime::{Spawner, Timer};
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Stops a recurring notification when dropped, see [`crate::schedule`].
+#[must_use = "the notifications stop when the guard is dropped"]
+#[derive(Debug)]
+pub struct Recurring {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Recurring {
+    /// Calls `tick` every `interval` on a new thread, until the guard is dropped or `tick` returns `false`.
+    ///
+    /// # Panics
+    /// If `interval` is zero.
+    pub(crate) fn spawn(
+        interval: Duration,
+        mut tick: impl FnMut() -> bool + Send + 'static,
+    ) -> Self {
+        assert!(!interval.is_zero(), "interval must be positive");
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let stopped = Arc::clone(&stop);
+        thread::Builder::new()
+            .name("noon-recurring".to_string())
+            .spawn(move || {
+                let (lock, changed) = &*stopped;
+                let mut next = Instant::now() + interval;
+                loop {
+                    let mut stop = lock.lock().unwrap();
+                    while !*stop && Instant::now() < next {
+                        let wait = next.saturating_duration_since(Instant::now());
+                        stop = changed.wait_timeout(stop, wait).unwrap().0;
+                    }
+                    if *stop {
+                        return;
+                    }
+                    drop(stop);
+                    if !tick() {
+                        return;
+                    }
+                    let now = Instant::now();
+                    while next <= now {
+                        next += interval;
+                    }
+                }
+            })
+            .expect("failed to spawn the recurring notification thread");
+        Self { stop }
+    }
+
+    /// Like [`Recurring::spawn`], but calls `tick` from a task of `spawner`, waiting on `timer` between calls.
+    ///
+    /// The task ends at the first tick after the guard is dropped.
+    #[cfg(feature = "async")]
+    pub(crate) fn spawn_on(
+        spawner: &impl Spawner,
+        timer: impl Timer + Send + 'static,
+        interval: Duration,
+        mut tick: impl FnMut() -> bool + Send + 'static,
+    ) -> Self {
+        assert!(!interval.is_zero(), "interval must be positive");
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let stopped = Arc::clone(&stop);
+        spawner.spawn(Box::pin(async move {
+            let mut next = Instant::now() + interval;
+            loop {
+                timer
+                    .sleep(next.saturating_duration_since(Instant::now()))
+                    .await;
+                if *stopped.0.lock().unwrap() || !tick() {
+                    return;
+                }
+                let now = Instant::now();
+                while next <= now {
+                    next += interval;
+                }
+            }
+        }));
+        Self { stop }
+    }
+}
+
+impl Drop for Recurring {
+    fn drop(&mut self) {
+        let (lock, changed) = &*self.stop;
+        *lock.lock().unwrap() = true;
+        changed.notify_one();
+    }
+}
@@ -0,0 +1,110 @@
+//! Asynchronous handlers as services.
+//!
+//! [`Mediate::into_service`] turns a mediator into a [`HandlerService`] for one type of message, whose `poll_ready` and `call` methods
+//! have the signatures of `tower::Service<TMsg>`, with `Response = TResp` and `Error = Infallible`, so it can be wrapped into a
+//! `tower::Service` implementation with a line per method, and slot into middleware such as timeouts and load shedding.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::sync::Arc;
+//! # use std::task::{Context, Poll, Wake, Waker};
+//! # struct Noop;
+//! # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+//! # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+//! #     let waker = Waker::from(Arc::new(Noop));
+//! #     let mut fut = std::pin::pin!(fut);
+//! #     loop {
+//! #         if let Poll::Ready(out) = fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+//! #             return out;
+//! #         }
+//! #     }
+//! # }
+//!
+//! let mediator = Arc::new(
+//!     MediatorBuilder::new()
+//!         .add_async_handler(|x: u32| async move { x * 2 })
+//!         .build(),
+//! );
+//! let mut service = mediator.into_service::<u32, u32, _>();
+//! assert_eq!(block_on(service.call(21)), Ok(42));
+//! ```
+//!
+//! A service is always ready, and clones of a service over an `Arc` share the same mediator.
+//!
+//! [`Mediate::into_service`]: crate::mediator::Mediate::into_service
+use crate::entry::RequestResponseAsync;
+use crate::flavor::{BoxFuture, Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A mediator dispatching messages of type `TMsg` to its asynchronous handler, see [`crate::service`].
+#[allow(clippy::type_complexity)]
+pub struct HandlerService<M, TMsg, TResp, I, S: Flavor = Local> {
+    mediator: M,
+    _handler: PhantomData<fn(TMsg) -> (TResp, I, S)>,
+}
+
+impl<M, TMsg, TResp, I, S: Flavor> HandlerService<M, TMsg, TResp, I, S> {
+    pub(crate) fn new(mediator: M) -> Self {
+        Self {
+            mediator,
+            _handler: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &M {
+        &self.mediator
+    }
+
+    pub fn into_inner(self) -> M {
+        self.mediator
+    }
+}
+
+impl<M, TMsg: 'static, TResp: 'static, I, S> HandlerService<M, TMsg, TResp, I, S>
+where
+    M: Mediate<S>,
+    S: Flavor,
+    M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+{
+    /// Always ready, since dispatching doesn't wait for capacity.
+    pub fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    pub fn call(&mut self, msg: TMsg) -> ServiceFuture<TResp, S> {
+        ServiceFuture(self.mediator.handle_async(msg))
+    }
+}
+
+impl<M: Clone, TMsg, TResp, I, S: Flavor> Clone for HandlerService<M, TMsg, TResp, I, S> {
+    fn clone(&self) -> Self {
+        Self::new(self.mediator.clone())
+    }
+}
+
+impl<M, TMsg, TResp, I, S: Flavor> fmt::Debug for HandlerService<M, TMsg, TResp, I, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandlerService")
+            .field("message", &std::any::type_name::<TMsg>())
+            .finish()
+    }
+}
+
+/// The response of a [`HandlerService`], which never fails.
+#[must_use = "futures do nothing unless polled"]
+pub struct ServiceFuture<TResp: 'static, S: Flavor>(BoxFuture<'static, TResp, S>);
+
+impl<TResp: 'static, S: Flavor> Future for ServiceFuture<TResp, S> {
+    type Output = Result<TResp, Infallible>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll(cx).map(Ok)
+    }
+}
@@ -0,0 +1,42 @@
+//! Forwarding notifications to code outside the mediator.
+//!
+//! [`MediatorBuilder::forward_notifications_to`] registers a receiver which sends a copy of every notification of a type to a [`NotificationSink`],
+//! such as the sending half of a channel whose receiving half is owned by another thread or task.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use std::sync::mpsc;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! struct OrderPlaced(u32);
+//!
+//! let (sender, receiver) = mpsc::channel();
+//! let mediator = MediatorBuilder::new()
+//!     .listen_for::<OrderPlaced>()
+//!     .forward_notifications_to::<OrderPlaced, _, _>(sender)
+//!     .build();
+//! mediator.notify(&OrderPlaced(1));
+//! assert_eq!(receiver.try_recv(), Ok(OrderPlaced(1)));
+//! ```
+//!
+//! Channels of other libraries, such as tokio's, can be used by implementing the trait for a wrapper around their senders.
+//!
+//! [`MediatorBuilder::forward_notifications_to`]: crate::mediator::MediatorBuilder::forward_notifications_to
+use std::sync::mpsc;
+
+pub trait NotificationSink<TMsg> {
+    /// Sends a notification, dropping it if nothing receives from the sink anymore.
+    fn send(&self, msg: TMsg);
+}
+
+impl<TMsg> NotificationSink<TMsg> for mpsc::Sender<TMsg> {
+    fn send(&self, msg: TMsg) {
+        let _ = mpsc::Sender::send(self, msg);
+    }
+}
+
+/// Blocks the notifying thread while the channel is full.
+impl<TMsg> NotificationSink<TMsg> for mpsc::SyncSender<TMsg> {
+    fn send(&self, msg: TMsg) {
+        let _ = mpsc::SyncSender::send(self, msg);
+    }
+}
@@ -0,0 +1,205 @@
+//! Capabilities of a mediator, for use in generic constraints.
+//!
+//! Each trait in this module holds for mediators with a given kind of receiver registered for a type of message,
+//! and implies the corresponding [`ContainsAt`] bound, so the matching [`Mediate`] method can be called on a generic mediator.
+//! `I` is the index of the receiver, as in [`ContainsAt`], and should be left to the compiler to infer.
+//!
+//! The index can't be hidden from these traits: a blanket impl of a trait without `I` would leave `I` unconstrained,
+//! which Rust rejects, since a mediator might contain the same receiver at more than one index.
+//! Generic code takes one index parameter per capability instead, which callers never spell out.
+//!
+//! Sets of capabilities required by a component can be declared as a single trait with [`crate::mediator_spec!`].
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::spec::{CanHandle, CanNotify};
+//!
+//! struct NewUser { id: i32 }
+//! struct UserCreated(i32);
+//!
+//! fn create_user<M, I, J>(mediator: &M, id: i32) -> i32
+//! where
+//!     M: CanHandle<NewUser, i32, I> + CanNotify<UserCreated, J>,
+//! {
+//!     let id = mediator.handle(NewUser { id });
+//!     mediator.notify(&UserCreated(id));
+//!     id
+//! }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|req: NewUser| req.id)
+//!     .listen_for::<UserCreated>()
+//!     .build();
+//! assert_eq!(create_user(&mediator, 5), 5);
+//! ```
+#[cfg(feature = "std")]
+use crate::entry::ReceiveNotificationBroadcast;
+use crate::entry::{
+    ReceiveNotification, ReceiveNotificationOwned, RequestResponse, TryReceiveNotification,
+    TryRequestResponse,
+};
+#[cfg(feature = "async")]
+use crate::entry::{
+    ReceiveNotificationAsync, ReceiveNotificationShared, RequestResponseAsync, RequestStream,
+    TryRequestResponseAsync,
+};
+use crate::flavor::{Flavor, Local};
+use crate::hlist::ContainsAt;
+use crate::mediator::Mediate;
+
+pub trait CanHandle<TMsg, TResp, I, S: Flavor = Local>:
+    Mediate<S, Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>>
+{
+}
+
+impl<M, TMsg, TResp, I, S: Flavor> CanHandle<TMsg, TResp, I, S> for M
+where
+    M: Mediate<S>,
+    M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+{
+}
+
+#[cfg(feature = "async")]
+pub trait CanHandleAsync<TMsg, TResp: 'static, I, S: Flavor = Local>:
+    Mediate<S, Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg, TResp: 'static, I, S: Flavor> CanHandleAsync<TMsg, TResp, I, S> for M
+where
+    M: Mediate<S>,
+    M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+{
+}
+
+pub trait CanTryHandle<TMsg, TResp, TErr, I, S: Flavor = Local>:
+    Mediate<S, Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>>
+{
+}
+
+impl<M, TMsg, TResp, TErr, I, S: Flavor> CanTryHandle<TMsg, TResp, TErr, I, S> for M
+where
+    M: Mediate<S>,
+    M::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+{
+}
+
+#[cfg(feature = "async")]
+pub trait CanTryHandleAsync<TMsg, TResp: 'static, TErr: 'static, I, S: Flavor = Local>:
+    Mediate<S, Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg, TResp: 'static, TErr: 'static, I, S: Flavor>
+    CanTryHandleAsync<TMsg, TResp, TErr, I, S> for M
+where
+    M: Mediate<S>,
+    M::Handlers: ContainsAt<TryRequestResponseAsync<TMsg, TResp, TErr, S>, I>,
+{
+}
+
+#[cfg(feature = "async")]
+pub trait CanHandleStream<TMsg, TItem, I, S: Flavor = Local>:
+    Mediate<S, Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg, TItem, I, S: Flavor> CanHandleStream<TMsg, TItem, I, S> for M
+where
+    M: Mediate<S>,
+    M::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>,
+{
+}
+
+pub trait CanNotify<TMsg: ?Sized, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>>
+{
+}
+
+impl<M, TMsg: ?Sized, I, S: Flavor> CanNotify<TMsg, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+{
+}
+
+pub trait CanTryNotify<TMsg: ?Sized, TErr, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>>
+{
+}
+
+impl<M, TMsg: ?Sized, TErr, I, S: Flavor> CanTryNotify<TMsg, TErr, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<TryReceiveNotification<TMsg, TErr, S>, I>,
+{
+}
+
+/// Both [`CanNotify`] and [`CanNotifyAsync`], for [`Mediate::notify_all`].
+#[cfg(feature = "async")]
+pub trait CanNotifyAll<TMsg, I, J, S: Flavor = Local>:
+    CanNotify<TMsg, I, S> + CanNotifyAsync<TMsg, J, S>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg, I, J, S: Flavor> CanNotifyAll<TMsg, I, J, S> for M where
+    M: CanNotify<TMsg, I, S> + CanNotifyAsync<TMsg, J, S>
+{
+}
+
+pub trait CanNotifyOwned<TMsg, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>>
+{
+}
+
+impl<M, TMsg, I, S: Flavor> CanNotifyOwned<TMsg, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationOwned<TMsg, S>, I>,
+{
+}
+
+#[cfg(feature = "async")]
+pub trait CanNotifyShared<TMsg: ?Sized, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg: ?Sized, I, S: Flavor> CanNotifyShared<TMsg, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationShared<TMsg, S>, I>,
+{
+}
+
+#[cfg(feature = "std")]
+pub trait CanNotifyBroadcast<TMsg, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>>
+{
+}
+
+#[cfg(feature = "std")]
+impl<M, TMsg, I, S: Flavor> CanNotifyBroadcast<TMsg, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationBroadcast<TMsg, S>, I>,
+{
+}
+
+#[cfg(feature = "async")]
+pub trait CanNotifyAsync<TMsg, I, S: Flavor = Local>:
+    Mediate<S, NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>>
+{
+}
+
+#[cfg(feature = "async")]
+impl<M, TMsg, I, S: Flavor> CanNotifyAsync<TMsg, I, S> for M
+where
+    M: Mediate<S>,
+    M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+{
+}
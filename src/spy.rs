@@ -0,0 +1,111 @@
+//! Assertions for testing code which dispatches through a mediator.
+//!
+//! A [`Spy`] is a [`Recording`] used to check how a component under test used its mediator,
+//! instead of counting calls from within hand-rolled handlers and receivers.
+//! Dispatches are matched by the type of their message, so the messages needn't be recorded to be counted,
+//! but [`Spy::last_message`] only sees messages recorded with [`Recording::handle_recorded`] and its siblings.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::spy::Spy;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! struct UserCreated(u32);
+//!
+//! noon::mediator_spec! {
+//!     trait Users {
+//!         notify UserCreated;
+//!     }
+//! }
+//!
+//! fn create_user<M: Users<I>, I>(mediator: &M, id: u32) {
+//!     mediator.notify(&UserCreated(id));
+//! }
+//!
+//! let spy = Spy::new(
+//!     MediatorBuilder::new()
+//!         .add_handler(|id: u32| id + 1)
+//!         .listen_for::<UserCreated>()
+//!         .build(),
+//! );
+//! create_user(&spy, 7);
+//! spy.handle_recorded(1u32);
+//!
+//! spy.expect_notified::<UserCreated>(1);
+//! assert_eq!(spy.times_handled::<u32>(), 1);
+//! assert_eq!(spy.last_message::<u32>(), Some(1));
+//! ```
+use crate::flavor::{Flavor, Local};
+use crate::hooks::{Dispatch, DispatchKind};
+use crate::mediator::Mediate;
+use crate::recording::Recording;
+
+/// A mediator which records each dispatch through it, for assertions in tests.
+pub type Spy<M, S = Local> = Recording<M, S>;
+
+fn is_handled(dispatch: &Dispatch) -> bool {
+    matches!(
+        dispatch.kind,
+        DispatchKind::Handle
+            | DispatchKind::HandleAsync
+            | DispatchKind::TryHandle
+            | DispatchKind::TryHandleAsync
+            | DispatchKind::HandleStream
+    )
+}
+
+fn is_message<TMsg: ?Sized>(dispatch: &Dispatch) -> bool {
+    dispatch.message == std::any::type_name::<TMsg>()
+}
+
+impl<M: Mediate<S>, S: Flavor> Recording<M, S> {
+    /// The number of times a handler was called with a `TMsg`.
+    pub fn times_handled<TMsg: ?Sized>(&self) -> usize {
+        self.inspect(|records| {
+            records
+                .iter()
+                .filter(|record| {
+                    is_handled(&record.dispatch) && is_message::<TMsg>(&record.dispatch)
+                })
+                .count()
+        })
+    }
+
+    /// The number of times a `TMsg` was sent to notification receivers.
+    pub fn times_notified<TMsg: ?Sized>(&self) -> usize {
+        self.inspect(|records| {
+            records
+                .iter()
+                .filter(|record| {
+                    !is_handled(&record.dispatch) && is_message::<TMsg>(&record.dispatch)
+                })
+                .count()
+        })
+    }
+
+    /// A copy of the last `TMsg` which was recorded, whether it was handled or sent to notification receivers.
+    pub fn last_message<TMsg: Clone + 'static>(&self) -> Option<TMsg> {
+        self.inspect(|records| {
+            records
+                .iter()
+                .rev()
+                .find_map(|record| record.message::<TMsg>())
+                .cloned()
+        })
+    }
+
+    /// Asserts that a `TMsg` was sent to notification receivers exactly `times` times.
+    ///
+    /// # Panics
+    /// If it wasn't, with the number of times it was.
+    #[track_caller]
+    pub fn expect_notified<TMsg: ?Sized>(&self, times: usize) {
+        let notified = self.times_notified::<TMsg>();
+        assert!(
+            notified == times,
+            "expected `{}` to be notified {} time(s), but it was notified {} time(s)",
+            std::any::type_name::<TMsg>(),
+            times,
+            notified
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Asynchronous streams, used by streaming handlers.
+//!
+//! [`Stream`] has the same shape as the `Stream` trait from `futures-core`, so adapting between the two is a one-line `poll_next` delegation.
+//! [`iter`] and [`unfold`] cover the common ways of producing a stream from a handler, and [`StreamExt::next`] consumes one.
+use std::future::Future;
+use std::ops::DerefMut;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub trait Stream {
+    type Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+}
+
+impl<S: Stream + Unpin + ?Sized> Stream for &mut S {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut **self).poll_next(cx)
+    }
+}
+
+impl<S: Stream + Unpin + ?Sized> Stream for Box<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut **self).poll_next(cx)
+    }
+}
+
+impl<P> Stream for Pin<P>
+where
+    P: DerefMut + Unpin,
+    P::Target: Stream,
+{
+    type Item = <P::Target as Stream>::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().as_mut().poll_next(cx)
+    }
+}
+
+pub trait StreamExt: Stream {
+    /// Resolves to the next item of the stream, or `None` once it's exhausted.
+    fn next(&mut self) -> Next<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Next { stream: self }
+    }
+}
+
+impl<S: Stream + ?Sized> StreamExt for S {}
+
+pub struct Next<'a, S: ?Sized> {
+    stream: &'a mut S,
+}
+
+impl<S: Stream + Unpin + ?Sized> Future for Next<'_, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+pub struct Iter<I> {
+    iter: I,
+}
+
+/// Creates a stream yielding every item of `iter`.
+pub fn iter<I: IntoIterator>(iter: I) -> Iter<I::IntoIter> {
+    Iter {
+        iter: iter.into_iter(),
+    }
+}
+
+impl<I: Iterator + Unpin> Stream for Iter<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.iter.next())
+    }
+}
+
+pub struct Unfold<T, F, Fut> {
+    state: Option<T>,
+    f: F,
+    fut: Option<Pin<Box<Fut>>>,
+}
+
+/// Creates a stream from a seed and an asynchronous step function.
+///
+/// Each call to `f` resolves to either the next item and the state to continue with, or `None` to end the stream.
+pub fn unfold<T, F, Fut, Item>(init: T, f: F) -> Unfold<T, F, Fut>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Option<(Item, T)>>,
+{
+    Unfold {
+        state: Some(init),
+        f,
+        fut: None,
+    }
+}
+
+impl<T, F, Fut, Item> Stream for Unfold<T, F, Fut>
+where
+    T: Unpin,
+    F: FnMut(T) -> Fut + Unpin,
+    Fut: Future<Output = Option<(Item, T)>>,
+{
+    type Item = Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Item>> {
+        let this = self.get_mut();
+        if let Some(state) = this.state.take() {
+            this.fut = Some(Box::pin((this.f)(state)));
+        }
+        let Some(fut) = this.fut.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match fut.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(step) => {
+                this.fut = None;
+                Poll::Ready(step.map(|(item, state)| {
+                    this.state = Some(state);
+                    item
+                }))
+            }
+        }
+    }
+}
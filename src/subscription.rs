@@ -0,0 +1,116 @@
+//! Consuming notifications as a stream.
+//!
+//! [`Mediate::subscribe_stream`] registers a receiver which queues a copy of every subsequent notification of a type,
+//! and returns a [`NotificationStream`] yielding them, so consumers can pull notifications when they're ready instead of being called back.
+//! The stream ends once the receiver is removed, by [`Mediate::unsubscribe`] with [`NotificationStream::id`] or by dropping the mediator.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::stream::StreamExt;
+//! # fn block_on<F: std::future::Future>(_: F) {}
+//!
+//! let mediator = MediatorBuilder::new().listen_for::<u32>().build();
+//! let mut numbers = mediator.subscribe_stream::<u32, _>();
+//! mediator.notify(&1);
+//! block_on(async {
+//!     assert_eq!(numbers.next().await, Some(1));
+//! });
+//! ```
+//!
+//! Notifications are queued without bound, so a stream which isn't polled holds every notification sent since it was created.
+//!
+//! [`Mediate::subscribe_stream`]: crate::mediator::Mediate::subscribe_stream
+//! [`Mediate::unsubscribe`]: crate::mediator::Mediate::unsubscribe
+use crate::entry::SubscriptionId;
+use crate::flavor::{IntoReceiver, Local, Shared};
+use crate::stream::Stream;
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Queue<TMsg> {
+    messages: VecDeque<TMsg>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// The receiver behind a [`NotificationStream`], which queues copies of the notifications.
+pub struct StreamSender<TMsg> {
+    queue: Arc<Mutex<Queue<TMsg>>>,
+}
+
+impl<TMsg: Clone> StreamSender<TMsg> {
+    fn send(&self, msg: &TMsg) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.messages.push_back(msg.clone());
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Ends the stream.
+impl<TMsg> Drop for StreamSender<TMsg> {
+    fn drop(&mut self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.closed = true;
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<TMsg: Clone + 'static> IntoReceiver<Local, TMsg> for StreamSender<TMsg> {
+    fn into_receiver(self) -> Box<dyn Fn(&TMsg)> {
+        Box::new(move |msg| self.send(msg))
+    }
+}
+
+impl<TMsg: Clone + Send + 'static> IntoReceiver<Shared, TMsg> for StreamSender<TMsg> {
+    fn into_receiver(self) -> Box<dyn Fn(&TMsg) + Send + Sync> {
+        Box::new(move |msg| self.send(msg))
+    }
+}
+
+/// The notifications of a type, see [`crate::subscription`].
+pub struct NotificationStream<TMsg> {
+    queue: Arc<Mutex<Queue<TMsg>>>,
+    id: SubscriptionId,
+}
+
+impl<TMsg> NotificationStream<TMsg> {
+    /// Creates a stream of the notifications queued by the sender passed to `subscribe`, which registers it as a receiver.
+    pub(crate) fn new(subscribe: impl FnOnce(StreamSender<TMsg>) -> SubscriptionId) -> Self {
+        let queue = Arc::new(Mutex::new(Queue {
+            messages: VecDeque::new(),
+            waker: None,
+            closed: false,
+        }));
+        let id = subscribe(StreamSender {
+            queue: Arc::clone(&queue),
+        });
+        Self { queue, id }
+    }
+
+    /// The id of the receiver queueing the notifications, to pass to [`crate::mediator::Mediate::unsubscribe`].
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+}
+
+impl<TMsg> Stream for NotificationStream<TMsg> {
+    type Item = TMsg;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TMsg>> {
+        let mut queue = self.queue.lock().unwrap();
+        match queue.messages.pop_front() {
+            Some(msg) => Poll::Ready(Some(msg)),
+            None if queue.closed => Poll::Ready(None),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
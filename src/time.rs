@@ -0,0 +1,168 @@
+//! Runtime-independent timers.
+//!
+//! [`Delay`] is driven by a single background thread, started the first time a delay is polled, so timeouts work under any executor.
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The error returned when a dispatch doesn't complete within its timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed;
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
+
+/// A future completing at a given instant.
+///
+/// While pending, the delay is registered with the timer thread along with the waker it was last polled with,
+/// until it completes or is dropped.
+#[derive(Debug)]
+pub struct Delay {
+    deadline: Instant,
+    registration: Option<u64>,
+}
+
+impl Delay {
+    pub fn new(duration: Duration) -> Self {
+        Self::until(Instant::now() + duration)
+    }
+
+    pub fn until(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            registration: None,
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        let id = Timer::get().register(self.registration, self.deadline, cx.waker());
+        self.registration = Some(id);
+        Poll::Pending
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some(id) = self.registration {
+            Timer::get().deregister(id);
+        }
+    }
+}
+
+/// Resolves to the output of `fut`, or to [`Elapsed`] if `duration` passes first.
+pub fn timeout<F: Future>(duration: Duration, fut: F) -> Timeout<F> {
+    Timeout {
+        fut: Box::pin(fut),
+        delay: Delay::new(duration),
+    }
+}
+
+pub struct Timeout<F> {
+    fut: Pin<Box<F>>,
+    delay: Delay,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(t) = self.fut.as_mut().poll(cx) {
+            return Poll::Ready(Ok(t));
+        }
+        match Pin::new(&mut self.delay).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+struct Timer {
+    pending: Mutex<Pending>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct Pending {
+    next_id: u64,
+    delays: HashMap<u64, (Instant, Waker)>,
+}
+
+impl Timer {
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<&'static Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            let timer: &'static Timer = Box::leak(Box::new(Timer {
+                pending: Mutex::default(),
+                changed: Condvar::new(),
+            }));
+            thread::Builder::new()
+                .name("noon-timer".to_string())
+                .spawn(move || timer.run())
+                .expect("failed to spawn the timer thread");
+            timer
+        })
+    }
+
+    /// Registers a delay to be woken at `deadline`, or updates the waker of the delay registered as `id`,
+    /// returning the delay's registration.
+    fn register(&self, id: Option<u64>, deadline: Instant, waker: &Waker) -> u64 {
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(id) = id {
+            if let Some((_, registered)) = pending.delays.get_mut(&id) {
+                if !registered.will_wake(waker) {
+                    registered.clone_from(waker);
+                }
+                return id;
+            }
+        }
+        let id = pending.next_id;
+        pending.next_id += 1;
+        pending.delays.insert(id, (deadline, waker.clone()));
+        self.changed.notify_one();
+        id
+    }
+
+    fn deregister(&self, id: u64) {
+        self.pending.lock().unwrap().delays.remove(&id);
+    }
+
+    fn run(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            pending.delays.retain(|_, (deadline, waker)| {
+                let expired = *deadline <= now;
+                if expired {
+                    waker.wake_by_ref();
+                }
+                !expired
+            });
+            pending = match pending.delays.values().map(|(deadline, _)| *deadline).min() {
+                Some(next) => self.changed.wait_timeout(pending, next - now).unwrap().0,
+                None => self.changed.wait(pending).unwrap(),
+            };
+        }
+    }
+}
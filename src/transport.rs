@@ -0,0 +1,291 @@
+//! Carrying serialized messages between processes.
+//!
+//! A [`Transport`] sends requests and published messages, each a tag and the bytes of a message as dispatched by [`TaggedMediator::dispatch_envelope`],
+//! and an [`Inbound`] receives them as [`Frame`]s on the other end. [`TaggedMediator::serve`] bridges an inbound to a mediator,
+//! dispatching each frame and sending back the encoded response to each request, so a mediator can be used remotely.
+//!
+//! [`channel`] connects the two sides in memory and [`TcpTransport`] and [`TcpInbound`] over TCP.
+//! Other transports, such as a message queue, can be added by implementing the traits.
+//! ```rust
+//! use noon::mediator::MediatorBuilder;
+//! use noon::codec::TaggedMediator;
+//! use noon::transport::{self, Transport};
+//! use std::thread;
+//!
+//! let (client, mut inbound) = transport::channel();
+//! let server = thread::spawn(move || {
+//!     let mediator = TaggedMediator::new(
+//!         MediatorBuilder::new()
+//!             .add_handler(|name: String| format!("Hello, {}!", name))
+//!             .build(),
+//!     )
+//!     .handle::<String, String, _>("greet");
+//!     mediator.serve(&mut inbound)
+//! });
+//!
+//! assert_eq!(client.request("greet", b"noon").unwrap(), b"Hello, noon!");
+//! assert!(client.request("shout", b"noon").is_err());
+//! drop(client);
+//! server.join().unwrap().unwrap();
+//! ```
+use crate::codec::TaggedMediator;
+use crate::flavor::Flavor;
+use crate::mediator::Mediate;
+
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{mpsc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A message whose sender waits for its response.
+    Request,
+    /// A message sent without waiting for a response.
+    Publish,
+}
+
+/// A serialized message received by an [`Inbound`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub kind: FrameKind,
+    pub tag: String,
+    pub payload: Vec<u8>,
+}
+
+/// The sending side of a transport.
+pub trait Transport {
+    /// Sends a message and waits for its encoded response.
+    ///
+    /// Errors dispatching the message on the other end are returned as errors of kind [`io::ErrorKind::Other`].
+    fn request(&self, tag: &str, payload: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Sends a message without waiting for it to be dispatched.
+    fn publish(&self, tag: &str, payload: &[u8]) -> io::Result<()>;
+}
+
+/// The receiving side of a transport.
+pub trait Inbound {
+    /// The next frame, or `None` once the sending side is closed.
+    fn receive(&mut self) -> io::Result<Option<Frame>>;
+
+    /// Sends back the response to the last request received, or the description of the error dispatching it.
+    fn respond(&mut self, response: Result<&[u8], &str>) -> io::Result<()>;
+}
+
+impl<M: Mediate<S>, S: Flavor> TaggedMediator<M, S> {
+    /// Dispatches the frames received from `inbound` until it's closed, responding to each request.
+    ///
+    /// Errors dispatching published messages are dropped, since nothing waits for them.
+    pub fn serve(&self, inbound: &mut impl Inbound) -> io::Result<()> {
+        while let Some(frame) = inbound.receive()? {
+            let result = self.dispatch_envelope(&frame.tag, &frame.payload);
+            if frame.kind == FrameKind::Request {
+                match result {
+                    Ok(resp) => inbound.respond(Ok(&resp))?,
+                    Err(err) => inbound.respond(Err(&err.to_string()))?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+type Response = Result<Vec<u8>, String>;
+
+/// Creates a transport whose frames are received by the returned inbound, in the same process.
+pub fn channel() -> (MemoryTransport, MemoryInbound) {
+    let (frames, received) = mpsc::channel();
+    (
+        MemoryTransport { frames },
+        MemoryInbound {
+            frames: received,
+            pending: None,
+        },
+    )
+}
+
+/// The sending side of a [`channel`], which can be cloned to send from several places.
+#[derive(Debug, Clone)]
+pub struct MemoryTransport {
+    frames: mpsc::Sender<(Frame, Option<mpsc::Sender<Response>>)>,
+}
+
+impl MemoryTransport {
+    fn send(&self, frame: Frame, respond: Option<mpsc::Sender<Response>>) -> io::Result<()> {
+        self.frames
+            .send((frame, respond))
+            .map_err(|_| io::Error::from(io::ErrorKind::NotConnected))
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn request(&self, tag: &str, payload: &[u8]) -> io::Result<Vec<u8>> {
+        let (respond, response) = mpsc::channel();
+        let frame = Frame {
+            kind: FrameKind::Request,
+            tag: tag.to_string(),
+            payload: payload.to_vec(),
+        };
+        self.send(frame, Some(respond))?;
+        response
+            .recv()
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionAborted))?
+            .map_err(io::Error::other)
+    }
+
+    fn publish(&self, tag: &str, payload: &[u8]) -> io::Result<()> {
+        let frame = Frame {
+            kind: FrameKind::Publish,
+            tag: tag.to_string(),
+            payload: payload.to_vec(),
+        };
+        self.send(frame, None)
+    }
+}
+
+/// The receiving side of a [`channel`], closed once every [`MemoryTransport`] is dropped.
+#[derive(Debug)]
+pub struct MemoryInbound {
+    frames: mpsc::Receiver<(Frame, Option<mpsc::Sender<Response>>)>,
+    pending: Option<mpsc::Sender<Response>>,
+}
+
+impl Inbound for MemoryInbound {
+    fn receive(&mut self) -> io::Result<Option<Frame>> {
+        Ok(self.frames.recv().ok().map(|(frame, respond)| {
+            self.pending = respond;
+            frame
+        }))
+    }
+
+    /// Responses to requests whose sender stopped waiting are dropped.
+    fn respond(&mut self, response: Result<&[u8], &str>) -> io::Result<()> {
+        let respond = self.pending.take().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no request to respond to")
+        })?;
+        let _ = respond.send(response.map(<[u8]>::to_vec).map_err(str::to_string));
+        Ok(())
+    }
+}
+
+// Frames are written as a kind byte followed by fields prefixed with their big-endian `u32` lengths:
+// requests and publishes are followed by their tag and payload, and responses by their body.
+const REQUEST: u8 = 0;
+const PUBLISH: u8 = 1;
+const OK: u8 = 2;
+const ERR: u8 = 3;
+
+fn write_field(writer: &mut impl Write, field: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(field.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame field is too long"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(field)
+}
+
+fn read_field(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let mut field = vec![0; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut field)?;
+    Ok(field)
+}
+
+fn write_frame(stream: &TcpStream, kind: u8, fields: &[&[u8]]) -> io::Result<()> {
+    let mut writer = BufWriter::new(stream);
+    writer.write_all(&[kind])?;
+    for field in fields {
+        write_field(&mut writer, field)?;
+    }
+    writer.flush()
+}
+
+fn read_kind(reader: &mut impl Read) -> io::Result<u8> {
+    let mut kind = [0];
+    reader.read_exact(&mut kind)?;
+    Ok(kind[0])
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// The sending side of a TCP connection to a [`TcpInbound`].
+///
+/// Requests from several threads are sent one at a time over the connection.
+#[derive(Debug)]
+pub struct TcpTransport {
+    stream: Mutex<TcpStream>,
+}
+
+impl TcpTransport {
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream: Mutex::new(stream),
+        }
+    }
+
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        TcpStream::connect(addr).map(Self::new)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn request(&self, tag: &str, payload: &[u8]) -> io::Result<Vec<u8>> {
+        let mut stream = self.stream.lock().unwrap();
+        write_frame(&stream, REQUEST, &[tag.as_bytes(), payload])?;
+        let kind = read_kind(&mut *stream)?;
+        let body = read_field(&mut *stream)?;
+        match kind {
+            OK => Ok(body),
+            ERR => Err(io::Error::other(String::from_utf8_lossy(&body))),
+            _ => Err(invalid_data("unknown response kind")),
+        }
+    }
+
+    fn publish(&self, tag: &str, payload: &[u8]) -> io::Result<()> {
+        let stream = self.stream.lock().unwrap();
+        write_frame(&stream, PUBLISH, &[tag.as_bytes(), payload])
+    }
+}
+
+/// The receiving side of a TCP connection from a [`TcpTransport`], closed once the connection is.
+///
+/// To serve several connections, accept them with a [`std::net::TcpListener`] and serve each with its own inbound.
+#[derive(Debug)]
+pub struct TcpInbound {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl TcpInbound {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        Ok(Self {
+            writer: stream.try_clone()?,
+            reader: BufReader::new(stream),
+        })
+    }
+}
+
+impl Inbound for TcpInbound {
+    fn receive(&mut self) -> io::Result<Option<Frame>> {
+        let kind = match read_kind(&mut self.reader) {
+            Ok(REQUEST) => FrameKind::Request,
+            Ok(PUBLISH) => FrameKind::Publish,
+            Ok(_) => return Err(invalid_data("unknown frame kind")),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let tag = String::from_utf8(read_field(&mut self.reader)?)
+            .map_err(|_| invalid_data("frame tag isn't UTF-8"))?;
+        let payload = read_field(&mut self.reader)?;
+        Ok(Some(Frame { kind, tag, payload }))
+    }
+
+    fn respond(&mut self, response: Result<&[u8], &str>) -> io::Result<()> {
+        let (kind, body) = match response {
+            Ok(body) => (OK, body),
+            Err(message) => (ERR, message.as_bytes()),
+        };
+        write_frame(&self.writer, kind, &[body])
+    }
+}
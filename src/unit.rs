@@ -0,0 +1,91 @@
+//! Running handlers within a unit of work, such as a database transaction.
+//!
+//! A [`UnitOfWork`] registered with [`MediatorBuilder::add_try_handler_with_unit_of_work`] begins a unit before the handler is called,
+//! passes it to the handler, and then commits it if the handler succeeded or rolls it back if it failed,
+//! so handlers don't each repeat the same transaction boilerplate.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::unit::UnitOfWork;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! #[derive(Default)]
+//! struct Transactions {
+//!     log: Rc<RefCell<Vec<&'static str>>>,
+//! }
+//!
+//! impl UnitOfWork<u32> for Transactions {
+//!     type Work = ();
+//!     type Error = String;
+//!
+//!     fn begin(&self, _: &u32) -> Result<(), String> {
+//!         self.log.borrow_mut().push("begin");
+//!         Ok(())
+//!     }
+//!
+//!     fn commit(&self, _: ()) -> Result<(), String> {
+//!         self.log.borrow_mut().push("commit");
+//!         Ok(())
+//!     }
+//!
+//!     fn rollback(&self, _: ()) {
+//!         self.log.borrow_mut().push("rollback")
+//!     }
+//! }
+//!
+//! let transactions = Transactions::default();
+//! let log = transactions.log.clone();
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler_with_unit_of_work(transactions, |x: u32, _: &()| {
+//!         if x == 0 {
+//!             return Err("zero".to_string());
+//!         }
+//!         Ok(100 / x)
+//!     })
+//!     .build();
+//!
+//! assert_eq!(mediator.try_handle(4), Ok::<_, String>(25));
+//! assert!(mediator.try_handle::<u32, u32, String, _>(0).is_err());
+//! assert_eq!(*log.borrow(), ["begin", "commit", "begin", "rollback"]);
+//! ```
+//!
+//! [`MediatorBuilder::add_try_handler_with_unit_of_work`]: crate::mediator::MediatorBuilder::add_try_handler_with_unit_of_work
+
+/// Begins and resolves a unit of work around the handler for `TMsg`, see [`crate::unit`].
+pub trait UnitOfWork<TMsg> {
+    /// An open unit, which is passed to the handler.
+    type Work;
+    type Error;
+
+    /// Called before the handler. If it fails, the handler isn't called and the error is returned instead.
+    fn begin(&self, msg: &TMsg) -> Result<Self::Work, Self::Error>;
+
+    /// Called after the handler succeeded. If it fails, the error is returned instead of the handler's response.
+    fn commit(&self, work: Self::Work) -> Result<(), Self::Error>;
+
+    /// Called after the handler failed, before its error is returned.
+    fn rollback(&self, work: Self::Work);
+}
+
+/// Calls `handler` within a unit begun by `unit`.
+pub(crate) fn run<TMsg, TResp, TErr, U>(
+    unit: &U,
+    msg: TMsg,
+    handler: impl FnOnce(TMsg, &U::Work) -> Result<TResp, TErr>,
+) -> Result<TResp, TErr>
+where
+    U: UnitOfWork<TMsg>,
+    TErr: From<U::Error>,
+{
+    let work = unit.begin(&msg)?;
+    match handler(msg, &work) {
+        Ok(resp) => {
+            unit.commit(work)?;
+            Ok(resp)
+        }
+        Err(err) => {
+            unit.rollback(work);
+            Err(err)
+        }
+    }
+}
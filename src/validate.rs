@@ -0,0 +1,80 @@
+//! Validating messages before they reach their handler.
+//!
+//! A [`Validator`] registered with [`MediatorBuilder::add_validator`] checks every message sent to a fallible handler before it's called.
+//! If a validator rejects the message, [`Mediate::try_handle`] returns its [`ValidationError`], converted into the handler's error type,
+//! and the handler isn't called. Validators for the same handler run in the order they were added, and the first rejection stops the others.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::validate::ValidationError;
+//!
+//! struct Register { name: String, age: u32 }
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_try_handler(|req: Register| Ok::<_, ValidationError>(format!("welcome {}", req.name)))
+//!     .add_validator(|req: &Register| {
+//!         ValidationError::ensure(!req.name.is_empty(), "name must not be empty")
+//!     })
+//!     .add_validator(|req: &Register| ValidationError::ensure(req.age >= 18, "too young"))
+//!     .build();
+//!
+//! let register = |name: &str, age| Register { name: name.to_string(), age };
+//! assert_eq!(mediator.try_handle(register("ada", 36)), Ok("welcome ada".to_string()));
+//! assert_eq!(
+//!     mediator.try_handle::<_, String, _, _>(register("", 10)).unwrap_err().message(),
+//!     "name must not be empty"
+//! );
+//! ```
+//!
+//! Validators are pipeline behaviors, see [`crate::pipeline`], so they have to be added after their handler,
+//! and run inside the behaviors added before them.
+//!
+//! [`MediatorBuilder::add_validator`]: crate::mediator::MediatorBuilder::add_validator
+//! [`Mediate::try_handle`]: crate::mediator::Mediate::try_handle
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when a [`Validator`] rejects a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    message: String,
+}
+
+impl ValidationError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Fails with `message` unless `condition` holds.
+    pub fn ensure(condition: bool, message: impl Into<String>) -> Result<(), Self> {
+        if condition {
+            Ok(())
+        } else {
+            Err(Self::new(message))
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "validation failed: {}", self.message)
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Checks a message before its handler is called, see [`crate::validate`].
+pub trait Validator<TMsg> {
+    fn validate(&self, msg: &TMsg) -> Result<(), ValidationError>;
+}
+
+impl<TMsg, F: Fn(&TMsg) -> Result<(), ValidationError>> Validator<TMsg> for F {
+    fn validate(&self, msg: &TMsg) -> Result<(), ValidationError> {
+        self(msg)
+    }
+}
@@ -0,0 +1,188 @@
+//! Restricted views of a mediator.
+//!
+//! A [`View`] only dispatches the messages listed in its capabilities, so a component can be handed a mediator
+//! without being able to reach the rest of its receivers. Views are created with [`Mediate::project`],
+//! and their capabilities are given as a tuple of the marker types in this module.
+//! ```rust
+//! use noon::mediator::{Mediate, MediatorBuilder};
+//! use noon::view::{Handle, Notify};
+//!
+//! struct GetUser(i32);
+//! struct UserSeen(i32);
+//! struct DeleteUser(i32);
+//!
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|req: GetUser| format!("user {}", req.0))
+//!     .add_handler(|_: DeleteUser| ())
+//!     .listen_for::<UserSeen>()
+//!     .build();
+//!
+//! let view = mediator.project::<(Handle<GetUser, String>, Notify<UserSeen>)>();
+//! assert_eq!(view.handle(GetUser(5)), "user 5");
+//! view.notify(&UserSeen(5));
+//! ```
+//! Dispatching a message outside of the view's capabilities is a compile-time error, even if the mediator has a receiver for it.
+//! ```rust,compile_fail
+//! # use noon::mediator::{Mediate, MediatorBuilder};
+//! # use noon::view::Handle;
+//! # struct GetUser(i32);
+//! # struct DeleteUser(i32);
+//! let mediator = MediatorBuilder::new()
+//!     .add_handler(|req: GetUser| req.0)
+//!     .add_handler(|_: DeleteUser| ())
+//!     .build();
+//! let view = mediator.project::<Handle<GetUser, i32>>();
+//! view.handle(DeleteUser(5));
+//! ```
+use crate::entry::{ReceiveNotification, RequestResponse, TryRequestResponse};
+#[cfg(feature = "async")]
+use crate::entry::{ReceiveNotificationAsync, RequestResponseAsync, RequestStream};
+#[cfg(feature = "async")]
+use crate::flavor::{BoxFuture, BoxStream};
+use crate::flavor::{Flavor, Local};
+use crate::hlist::{Cons, ContainsAt, HList, Nil};
+use crate::mediator::Mediate;
+
+use std::marker::PhantomData;
+
+/// Allows [`Mediate::handle`].
+pub struct Handle<TMsg, TResp>(PhantomData<fn(TMsg) -> TResp>);
+
+/// Allows [`Mediate::handle_async`].
+#[cfg(feature = "async")]
+pub struct HandleAsync<TMsg, TResp>(PhantomData<fn(TMsg) -> TResp>);
+
+/// Allows [`Mediate::try_handle`].
+pub struct TryHandle<TMsg, TResp, TErr>(PhantomData<fn(TMsg) -> Result<TResp, TErr>>);
+
+/// Allows [`Mediate::handle_stream`].
+#[cfg(feature = "async")]
+pub struct HandleStream<TMsg, TItem>(PhantomData<fn(TMsg) -> TItem>);
+
+/// Allows [`Mediate::notify`].
+pub struct Notify<TMsg: ?Sized>(PhantomData<fn(&TMsg)>);
+
+/// Allows [`Mediate::notify_async`].
+#[cfg(feature = "async")]
+pub struct NotifyAsync<TMsg>(PhantomData<fn(TMsg)>);
+
+/// A set of capabilities, either a single marker type or a tuple of them.
+pub trait Capabilities {
+    type List: HList;
+}
+
+macro_rules! single_capability {
+    ($($cap:ident<$($param:ident $(: ?$unsized:ident)?),*>),*) => {$(
+        impl<$($param $(: ?$unsized)?),*> Capabilities for $cap<$($param),*> {
+            type List = Cons<Self, Nil>;
+        }
+    )*};
+}
+
+single_capability!(
+    Handle<TMsg, TResp>,
+    TryHandle<TMsg, TResp, TErr>,
+    Notify<TMsg: ?Sized>
+);
+
+#[cfg(feature = "async")]
+single_capability!(
+    HandleAsync<TMsg, TResp>,
+    HandleStream<TMsg, TItem>,
+    NotifyAsync<TMsg>
+);
+
+macro_rules! tuple_capabilities {
+    () => {
+        impl Capabilities for () {
+            type List = Nil;
+        }
+    };
+    ($head:ident $(, $tail:ident)*) => {
+        impl<$head $(, $tail)*> Capabilities for ($head, $($tail,)*) {
+            type List = Cons<$head, <($($tail,)*) as Capabilities>::List>;
+        }
+        tuple_capabilities!($($tail),*);
+    };
+}
+
+tuple_capabilities!(C0, C1, C2, C3, C4, C5, C6, C7, C8, C9, C10, C11);
+
+/// A view of a mediator restricted to the capabilities `P`, see [`Mediate::project`].
+///
+/// Its methods take the index `J` of the capability in `P` along with the index `I` of the receiver, and both are inferred.
+pub struct View<'a, M: ?Sized, P, S: Flavor = Local> {
+    mediator: &'a M,
+    _capabilities: PhantomData<fn() -> (P, S)>,
+}
+
+impl<M: ?Sized, P, S: Flavor> Clone for View<'_, M, P, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: ?Sized, P, S: Flavor> Copy for View<'_, M, P, S> {}
+
+impl<'a, M: Mediate<S> + ?Sized, P: Capabilities, S: Flavor> View<'a, M, P, S> {
+    pub(crate) fn new(mediator: &'a M) -> Self {
+        Self {
+            mediator,
+            _capabilities: PhantomData,
+        }
+    }
+
+    pub fn handle<TMsg, TResp, I, J>(&self, msg: TMsg) -> TResp
+    where
+        M::Handlers: ContainsAt<RequestResponse<TMsg, TResp, S>, I>,
+        P::List: ContainsAt<Handle<TMsg, TResp>, J>,
+    {
+        self.mediator.handle(msg)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn handle_async<TMsg: 'static, TResp: 'static, I, J>(
+        &self,
+        msg: TMsg,
+    ) -> BoxFuture<'static, TResp, S>
+    where
+        M::Handlers: ContainsAt<RequestResponseAsync<TMsg, TResp, S>, I>,
+        P::List: ContainsAt<HandleAsync<TMsg, TResp>, J>,
+    {
+        self.mediator.handle_async(msg)
+    }
+
+    pub fn try_handle<TMsg, TResp, TErr, I, J>(&self, msg: TMsg) -> Result<TResp, TErr>
+    where
+        M::Handlers: ContainsAt<TryRequestResponse<TMsg, TResp, TErr, S>, I>,
+        P::List: ContainsAt<TryHandle<TMsg, TResp, TErr>, J>,
+    {
+        self.mediator.try_handle(msg)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn handle_stream<TMsg, TItem, I, J>(&self, msg: TMsg) -> BoxStream<TItem, S>
+    where
+        M::Handlers: ContainsAt<RequestStream<TMsg, TItem, S>, I>,
+        P::List: ContainsAt<HandleStream<TMsg, TItem>, J>,
+    {
+        self.mediator.handle_stream(msg)
+    }
+
+    pub fn notify<TMsg: ?Sized, I, J>(&self, msg: &TMsg)
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotification<TMsg, S>, I>,
+        P::List: ContainsAt<Notify<TMsg>, J>,
+    {
+        self.mediator.notify(msg)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn notify_async<TMsg: Clone + 'static, I, J>(&self, msg: TMsg) -> BoxFuture<'a, (), S>
+    where
+        M::NotifyReceivers: ContainsAt<ReceiveNotificationAsync<TMsg, S>, I>,
+        P::List: ContainsAt<NotifyAsync<TMsg>, J>,
+    {
+        self.mediator.notify_async(msg)
+    }
+}